@@ -0,0 +1,11 @@
+-- Normalize note timestamps to RFC-3339 UTC with millisecond precision
+-- (e.g. 2024-01-01T12:30:45.123Z) so lexicographic order matches time order
+UPDATE notes
+SET created_at = strftime('%Y-%m-%dT%H:%M:%fZ', created_at)
+WHERE strftime('%Y-%m-%dT%H:%M:%fZ', created_at) IS NOT NULL
+  AND created_at != strftime('%Y-%m-%dT%H:%M:%fZ', created_at);
+
+UPDATE notes
+SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', updated_at)
+WHERE strftime('%Y-%m-%dT%H:%M:%fZ', updated_at) IS NOT NULL
+  AND updated_at != strftime('%Y-%m-%dT%H:%M:%fZ', updated_at);
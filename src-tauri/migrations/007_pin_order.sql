@@ -0,0 +1,13 @@
+-- Track when notes were pinned so pin caps can trim the oldest pins first
+CREATE TABLE IF NOT EXISTS note_pins (
+    note_id INTEGER PRIMARY KEY,
+    pinned_at TEXT NOT NULL
+);
+
+-- Backfill notes pinned before pin order was tracked
+INSERT OR IGNORE INTO note_pins (note_id, pinned_at)
+SELECT id, updated_at FROM notes WHERE is_pinned = 1;
+
+CREATE TRIGGER IF NOT EXISTS note_pins_delete AFTER DELETE ON notes BEGIN
+    DELETE FROM note_pins WHERE note_id = old.id;
+END;
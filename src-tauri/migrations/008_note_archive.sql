@@ -0,0 +1,9 @@
+-- Track archived notes without altering the notes table
+CREATE TABLE IF NOT EXISTS note_archive (
+    note_id INTEGER PRIMARY KEY,
+    archived_at TEXT NOT NULL
+);
+
+CREATE TRIGGER IF NOT EXISTS note_archive_delete AFTER DELETE ON notes BEGIN
+    DELETE FROM note_archive WHERE note_id = old.id;
+END;
@@ -0,0 +1,11 @@
+-- Staged drafts awaiting flush into the notes table
+-- Persisted so unflushed edits survive a crash and can be recovered on startup
+CREATE TABLE IF NOT EXISTS note_drafts (
+    note_id INTEGER PRIMARY KEY,
+    content TEXT NOT NULL,
+    staged_at TEXT NOT NULL
+);
+
+CREATE TRIGGER IF NOT EXISTS note_drafts_delete AFTER DELETE ON notes BEGIN
+    DELETE FROM note_drafts WHERE note_id = old.id;
+END;
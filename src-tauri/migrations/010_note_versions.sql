@@ -0,0 +1,12 @@
+-- Content history for notes; version numbers start at 1 and increase per note
+CREATE TABLE IF NOT EXISTS note_versions (
+    note_id INTEGER NOT NULL,
+    version INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (note_id, version)
+);
+
+CREATE TRIGGER IF NOT EXISTS note_versions_delete AFTER DELETE ON notes BEGIN
+    DELETE FROM note_versions WHERE note_id = old.id;
+END;
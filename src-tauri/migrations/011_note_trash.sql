@@ -0,0 +1,11 @@
+-- Soft-deleted notes awaiting restore or permanent deletion
+CREATE TABLE IF NOT EXISTS note_trash (
+    note_id INTEGER PRIMARY KEY,
+    deleted_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_trash_deleted_at ON note_trash(deleted_at);
+
+CREATE TRIGGER IF NOT EXISTS note_trash_delete AFTER DELETE ON notes BEGIN
+    DELETE FROM note_trash WHERE note_id = old.id;
+END;
@@ -0,0 +1,8 @@
+-- Opt-in history of recent search queries, most recent last
+CREATE TABLE IF NOT EXISTS search_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    query TEXT NOT NULL UNIQUE,
+    searched_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_history_searched_at ON search_history(searched_at);
@@ -0,0 +1,10 @@
+-- Compressed content of large notes; a row here means notes.content is empty
+-- and the note's text is stored zlib-compressed in data
+CREATE TABLE IF NOT EXISTS note_compressed_content (
+    note_id INTEGER PRIMARY KEY,
+    data BLOB NOT NULL
+);
+
+CREATE TRIGGER IF NOT EXISTS note_compressed_content_delete AFTER DELETE ON notes BEGIN
+    DELETE FROM note_compressed_content WHERE note_id = old.id;
+END;
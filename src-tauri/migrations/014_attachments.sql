@@ -0,0 +1,14 @@
+-- References from notes to external files; the files themselves are never copied
+CREATE TABLE IF NOT EXISTS attachments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    note_id INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    added_at TEXT NOT NULL,
+    UNIQUE (note_id, path)
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachments_note_id ON attachments(note_id);
+
+CREATE TRIGGER IF NOT EXISTS attachments_delete AFTER DELETE ON notes BEGIN
+    DELETE FROM attachments WHERE note_id = old.id;
+END;
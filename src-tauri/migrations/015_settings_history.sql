@@ -0,0 +1,10 @@
+-- Audit trail of setting changes, newest last; a NULL value means the setting was unset
+CREATE TABLE IF NOT EXISTS settings_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    key TEXT NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    changed_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_settings_history_key ON settings_history(key, id);
@@ -0,0 +1,11 @@
+-- When each note was last opened, for jumping back to recent notes
+CREATE TABLE IF NOT EXISTS note_access (
+    note_id INTEGER PRIMARY KEY,
+    last_accessed_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_access_last_accessed_at ON note_access(last_accessed_at);
+
+CREATE TRIGGER IF NOT EXISTS note_access_delete AFTER DELETE ON notes BEGIN
+    DELETE FROM note_access WHERE note_id = old.id;
+END;
@@ -0,0 +1,10 @@
+-- How often each note has been opened, and whether the auto-pin rule already pinned it
+CREATE TABLE IF NOT EXISTS note_access_counts (
+    note_id INTEGER PRIMARY KEY,
+    access_count INTEGER NOT NULL DEFAULT 0,
+    auto_pinned_at TEXT
+);
+
+CREATE TRIGGER IF NOT EXISTS note_access_counts_delete AFTER DELETE ON notes BEGIN
+    DELETE FROM note_access_counts WHERE note_id = old.id;
+END;
@@ -0,0 +1,17 @@
+-- Encrypted content of notes marked encrypted; a row here means notes.content is
+-- empty, the note has no FTS row and no stored versions
+CREATE TABLE IF NOT EXISTS note_encrypted_content (
+    note_id INTEGER PRIMARY KEY,
+    data BLOB NOT NULL
+);
+
+CREATE TRIGGER IF NOT EXISTS note_encrypted_content_delete AFTER DELETE ON notes BEGIN
+    DELETE FROM note_encrypted_content WHERE note_id = old.id;
+END;
+
+-- Salt and key check for the encryption passphrase; at most one row
+CREATE TABLE IF NOT EXISTS note_encryption_key (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    salt BLOB NOT NULL,
+    key_check BLOB NOT NULL
+);
@@ -0,0 +1,10 @@
+-- Notes locked against edits; a row here means content writes are rejected
+-- unless made through quick capture while notes.capture_appends_to_locked is on
+CREATE TABLE IF NOT EXISTS note_locks (
+    note_id INTEGER PRIMARY KEY,
+    locked_at TEXT NOT NULL
+);
+
+CREATE TRIGGER IF NOT EXISTS note_locks_delete AFTER DELETE ON notes BEGIN
+    DELETE FROM note_locks WHERE note_id = old.id;
+END;
@@ -0,0 +1,13 @@
+-- [[wiki-link]] targets written in each note, by nickname as written; a
+-- target is matched to notes case-insensitively when backlinks are queried
+CREATE TABLE IF NOT EXISTS note_links (
+    source_id INTEGER NOT NULL,
+    target TEXT NOT NULL COLLATE NOCASE,
+    PRIMARY KEY (source_id, target)
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_links_target ON note_links(target);
+
+CREATE TRIGGER IF NOT EXISTS note_links_delete AFTER DELETE ON notes BEGIN
+    DELETE FROM note_links WHERE source_id = old.id;
+END;
@@ -0,0 +1,17 @@
+-- Stable UUIDs identifying notes to external tools, kept across archive export and import
+CREATE TABLE IF NOT EXISTS note_external_ids (
+    note_id INTEGER PRIMARY KEY,
+    external_id TEXT NOT NULL UNIQUE
+);
+
+CREATE TRIGGER IF NOT EXISTS note_external_ids_delete AFTER DELETE ON notes BEGIN
+    DELETE FROM note_external_ids WHERE note_id = old.id;
+END;
+
+-- Give notes without one a random (version 4) UUID
+INSERT INTO note_external_ids (note_id, external_id)
+SELECT id, lower(
+    hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+    || substr('89ab', 1 + abs(random()) % 4, 1) || substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6))
+)
+FROM notes WHERE id NOT IN (SELECT note_id FROM note_external_ids);
@@ -0,0 +1,23 @@
+-- Position of each note in the manual (drag-and-drop) order, lowest first
+CREATE TABLE IF NOT EXISTS note_sort_order (
+    note_id INTEGER PRIMARY KEY,
+    sort_order INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_sort_order ON note_sort_order(sort_order);
+
+CREATE TRIGGER IF NOT EXISTS note_sort_order_delete AFTER DELETE ON notes BEGIN
+    DELETE FROM note_sort_order WHERE note_id = old.id;
+END;
+
+-- New notes go to the end of the manual order
+CREATE TRIGGER IF NOT EXISTS note_sort_order_insert AFTER INSERT ON notes BEGIN
+    INSERT OR IGNORE INTO note_sort_order (note_id, sort_order)
+    VALUES (new.id, COALESCE((SELECT MAX(sort_order) FROM note_sort_order), 0) + 1);
+END;
+
+-- Place notes without a position after the rest, oldest first
+INSERT INTO note_sort_order (note_id, sort_order)
+SELECT id, (SELECT COALESCE(MAX(sort_order), 0) FROM note_sort_order)
+    + ROW_NUMBER() OVER (ORDER BY created_at, id)
+FROM notes WHERE id NOT IN (SELECT note_id FROM note_sort_order);
@@ -0,0 +1,10 @@
+-- The notes_fts triggers re-read notes.content, which holds '' for compressed and
+-- encrypted notes and already holds the new text by the time an AFTER UPDATE
+-- trigger runs, so they removed the wrong tokens. The index is now maintained
+-- explicitly by DbService, which passes the indexed text to FTS5 'delete'.
+DROP TRIGGER IF EXISTS notes_fts_insert;
+DROP TRIGGER IF EXISTS notes_fts_update;
+DROP TRIGGER IF EXISTS notes_fts_delete;
+
+-- Clear the index; it is rebuilt from the full note text after this migration
+INSERT INTO notes_fts(notes_fts) VALUES('delete-all');
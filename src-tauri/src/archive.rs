@@ -0,0 +1,240 @@
+// archive.rs - Whole-database backups as newline-delimited JSON
+
+use crate::database::{current_timestamp, DbService};
+use crate::error::AppError;
+use crate::import::{
+    overwrite_note, resolve_conflict, ConflictStrategy, ImportMatchKey, ImportMatcher, ImportResolution, ImportSummary,
+};
+use crate::models::{Note, NoteVersion};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Version of the archive layout written by `render_archive`
+/// Bump whenever a record gains, loses or changes a field
+pub const ARCHIVE_SCHEMA_VERSION: u32 = 1;
+
+/// First line of every archive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveHeader {
+    pub schema_version: u32,
+    pub exported_at: String,
+    /// Whether `version` records follow their notes
+    pub include_history: bool,
+}
+
+/// One line of an archive, tagged by `kind`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum ArchiveRecord {
+    Header(ArchiveHeader),
+    Note(Note),
+    Version(NoteVersion),
+}
+
+/// A note together with its stored versions, oldest first
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchivedNote {
+    pub note: Note,
+    pub versions: Vec<NoteVersion>,
+}
+
+/// A parsed archive
+#[derive(Debug, Clone, PartialEq)]
+pub struct Archive {
+    pub header: ArchiveHeader,
+    pub notes: Vec<ArchivedNote>,
+}
+
+/// Render notes as an archive: a header line, then each note followed by its versions
+/// Versions are only written when `include_history` is set
+pub fn render_archive(notes: &[ArchivedNote], include_history: bool) -> Result<String, AppError> {
+    let header = ArchiveHeader {
+        schema_version: ARCHIVE_SCHEMA_VERSION,
+        exported_at: current_timestamp(),
+        include_history,
+    };
+
+    let mut out = serde_json::to_string(&ArchiveRecord::Header(header))?;
+    out.push('\n');
+    for archived in notes {
+        out.push_str(&serde_json::to_string(&ArchiveRecord::Note(archived.note.clone()))?);
+        out.push('\n');
+        if include_history {
+            for version in &archived.versions {
+                out.push_str(&serde_json::to_string(&ArchiveRecord::Version(version.clone()))?);
+                out.push('\n');
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Parse an archive written by `render_archive`
+///
+/// The header must come first and must not carry a schema version newer than
+/// `ARCHIVE_SCHEMA_VERSION`. Every `version` record must follow the note it
+/// belongs to. Blank lines are ignored.
+pub fn parse_archive(text: &str) -> Result<Archive, AppError> {
+    let invalid = |line: usize, message: String| AppError::Validation {
+        field: "archive".to_string(),
+        message: format!("Line {}: {}", line, message),
+    };
+
+    let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let header = match lines.next() {
+        Some((index, line)) => match serde_json::from_str(line) {
+            Ok(ArchiveRecord::Header(header)) => header,
+            Ok(_) => return Err(invalid(index + 1, "Archive must start with a header".to_string())),
+            Err(e) => return Err(invalid(index + 1, e.to_string())),
+        },
+        None => return Err(invalid(1, "Archive is empty".to_string())),
+    };
+    if header.schema_version > ARCHIVE_SCHEMA_VERSION {
+        return Err(invalid(1, format!(
+            "Archive schema version {} is newer than the supported version {}",
+            header.schema_version, ARCHIVE_SCHEMA_VERSION
+        )));
+    }
+
+    let mut notes: Vec<ArchivedNote> = Vec::new();
+    let mut positions: HashMap<i64, usize> = HashMap::new();
+    for (index, line) in lines {
+        match serde_json::from_str(line).map_err(|e| invalid(index + 1, e.to_string()))? {
+            ArchiveRecord::Header(_) => return Err(invalid(index + 1, "Archive has more than one header".to_string())),
+            ArchiveRecord::Note(note) => {
+                positions.insert(note.id, notes.len());
+                notes.push(ArchivedNote { note, versions: Vec::new() });
+            }
+            ArchiveRecord::Version(version) => {
+                let position = *positions.get(&version.note_id).ok_or_else(|| {
+                    invalid(index + 1, format!("Version for note {} appears before the note", version.note_id))
+                })?;
+                notes[position].versions.push(version);
+            }
+        }
+    }
+
+    Ok(Archive { header, notes })
+}
+
+/// Render every note in the database as an archive, optionally with version history
+///
+/// Encrypted notes are written decrypted, so this needs an unlocked session when
+/// there are any; `is_encrypted` is kept so an import encrypts them again.
+pub async fn export_archive(db: &DbService, include_history: bool) -> Result<String, AppError> {
+    let mut archived = Vec::new();
+    for note in db.get_all_notes().await? {
+        let note = if note.is_encrypted {
+            if !db.is_encryption_unlocked() {
+                return Err(AppError::Security {
+                    message: "Unlock encrypted notes before exporting an archive".to_string(),
+                });
+            }
+            db.get_note(note.id).await?.ok_or(AppError::NotFound { id: note.id })?
+        } else {
+            note
+        };
+        let versions = if include_history { db.get_note_versions(note.id).await? } else { Vec::new() };
+        archived.push(ArchivedNote { note, versions });
+    }
+    render_archive(&archived, include_history)
+}
+
+/// Recreate the notes of an archive, resolving matches with existing notes by `strategy`
+///
+/// The archive is parsed in full before anything is written, so a malformed file
+/// imports nothing. Notes are imported one at a time; a note that fails
+/// validation stops the import, keeping the notes already written. Created notes
+/// bring their version history; overwritten notes keep their own and gain a
+/// version. Notes archived as encrypted are encrypted again, which needs an
+/// unlocked session.
+pub async fn import_archive(
+    db: &DbService,
+    text: &str,
+    strategy: ConflictStrategy,
+    match_key: ImportMatchKey,
+) -> Result<ImportSummary, AppError> {
+    let archive = parse_archive(text)?;
+    let mut matcher = ImportMatcher::load(db, match_key).await?;
+    let mut summary = ImportSummary::default();
+    for archived in &archive.notes {
+        let incoming = &archived.note;
+        let existing = matcher.find(&incoming.content, incoming.nickname.as_deref()).cloned();
+        let resolution = resolve_conflict(strategy, existing.as_ref(), &incoming.updated_at)?;
+        let note = match (resolution, &existing) {
+            (ImportResolution::Skip, _) => None,
+            (ImportResolution::Overwrite, Some(existing)) => Some(overwrite_note(db, existing, incoming).await?),
+            _ => Some(db.import_note_with_history(incoming, &archived.versions).await?),
+        };
+        let note = match note {
+            Some(note) if incoming.is_encrypted => Some(db.set_note_encrypted(note.id, true).await?),
+            note => note,
+        };
+        if let Some(note) = &note {
+            // Index by the plaintext just imported; encrypted notes now read back as a placeholder
+            matcher.remember(&Note { content: incoming.content.clone(), is_encrypted: false, ..note.clone() });
+        }
+        summary.record(resolution, note);
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_archive_round_trips_version_history() {
+        let source_dir = tempdir().unwrap();
+        let source = DbService::new(source_dir.path().join("test.db")).unwrap();
+        let note = source.create_note_with_nickname("first draft".to_string(), Some("Plans".to_string()), false).await.unwrap();
+        let second = source.update_note(Note { content: "second draft".to_string(), ..note.clone() }).await.unwrap();
+        source.update_note(Note { content: "final draft".to_string(), ..second }).await.unwrap();
+        let history = source.get_note_versions(note.id).await.unwrap();
+        assert_eq!(history.len(), 3);
+
+        let text = export_archive(&source, true).await.unwrap();
+        assert!(text.lines().next().unwrap().contains(&format!("\"schema_version\":{}", ARCHIVE_SCHEMA_VERSION)));
+
+        let target_dir = tempdir().unwrap();
+        let target = DbService::new(target_dir.path().join("test.db")).unwrap();
+        target.create_note("already here".to_string()).await.unwrap();
+        let summary = import_archive(&target, &text, ConflictStrategy::Duplicate, ImportMatchKey::ContentHash).await.unwrap();
+        assert_eq!(summary.created, 1);
+        let imported = summary.notes;
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].content, "final draft");
+        assert_eq!(imported[0].nickname.as_deref(), Some("Plans"));
+        assert_eq!(imported[0].external_id, note.external_id);
+
+        let restored = target.get_note_versions(imported[0].id).await.unwrap();
+        assert_eq!(restored.len(), history.len());
+        for (restored, original) in restored.iter().zip(&history) {
+            assert_eq!(restored.note_id, imported[0].id);
+            assert_eq!(restored.version, original.version);
+            assert_eq!(restored.content, original.content);
+            assert_eq!(restored.created_at, original.created_at);
+        }
+
+        // Without history only the current content is archived
+        let text = export_archive(&source, false).await.unwrap();
+        let archive = parse_archive(&text).unwrap();
+        assert!(!archive.header.include_history);
+        assert!(archive.notes[0].versions.is_empty());
+    }
+
+    #[test]
+    fn test_parse_archive_rejects_newer_schema_and_orphan_versions() {
+        let newer = format!(
+            "{{\"kind\":\"header\",\"schema_version\":{},\"exported_at\":\"2024-01-01T00:00:00Z\",\"include_history\":true}}\n",
+            ARCHIVE_SCHEMA_VERSION + 1
+        );
+        assert!(parse_archive(&newer).is_err());
+        assert!(parse_archive("").is_err());
+
+        let orphan = "{\"kind\":\"header\",\"schema_version\":1,\"exported_at\":\"2024-01-01T00:00:00Z\",\"include_history\":true}\n\
+            {\"kind\":\"version\",\"note_id\":7,\"version\":1,\"content\":\"x\",\"created_at\":\"2024-01-01T00:00:00Z\"}\n";
+        assert!(parse_archive(orphan).is_err());
+    }
+}
@@ -0,0 +1,163 @@
+// capture.rs - Normalizing quick-capture content before it is stored
+
+use crate::database::DbService;
+use crate::error::AppError;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Setting turning on trimming of trailing whitespace and surrounding blank lines
+pub const CAPTURE_TRIM_SETTING_KEY: &str = "capture.trim";
+
+/// Setting turning on collapsing runs of blank lines into one
+pub const CAPTURE_COLLAPSE_BLANK_LINES_SETTING_KEY: &str = "capture.collapse_blank_lines";
+
+/// Setting turning on removal of tracking parameters from URLs
+pub const CAPTURE_STRIP_URL_PARAMS_SETTING_KEY: &str = "capture.strip_url_params";
+
+/// Query parameters removed by `strip_tracking_params`, besides any `utm_*` one
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "dclid", "msclkid", "mc_cid", "mc_eid", "igshid", "yclid", "_hsenc", "_hsmi"];
+
+/// Transforms applied to captured content, in the order listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureTransforms {
+    pub strip_url_params: bool,
+    pub trim: bool,
+    pub collapse_blank_lines: bool,
+}
+
+impl Default for CaptureTransforms {
+    fn default() -> Self {
+        Self { strip_url_params: true, trim: true, collapse_blank_lines: true }
+    }
+}
+
+impl CaptureTransforms {
+    /// Read the `capture.*` settings; unset ones are on
+    pub async fn load(db: &DbService) -> Result<Self, AppError> {
+        let enabled = |key: &'static str, value: Option<String>| match value {
+            Some(value) => value.trim().parse::<bool>().map_err(|_| AppError::Validation {
+                field: key.to_string(),
+                message: format!("Invalid capture setting '{}': expected true or false", value),
+            }),
+            None => Ok(true),
+        };
+        Ok(Self {
+            strip_url_params: enabled(CAPTURE_STRIP_URL_PARAMS_SETTING_KEY, db.get_setting(CAPTURE_STRIP_URL_PARAMS_SETTING_KEY).await?)?,
+            trim: enabled(CAPTURE_TRIM_SETTING_KEY, db.get_setting(CAPTURE_TRIM_SETTING_KEY).await?)?,
+            collapse_blank_lines: enabled(
+                CAPTURE_COLLAPSE_BLANK_LINES_SETTING_KEY,
+                db.get_setting(CAPTURE_COLLAPSE_BLANK_LINES_SETTING_KEY).await?,
+            )?,
+        })
+    }
+
+    /// Run the enabled transforms over `content`
+    ///
+    /// This happens before note validation, so it may turn content that would be
+    /// rejected (e.g. a URL with `&utm_source=...`) into content that is accepted.
+    pub fn apply(&self, content: &str) -> String {
+        let mut content = content.to_string();
+        if self.strip_url_params {
+            content = strip_tracking_params(&content);
+        }
+        if self.trim {
+            content = trim_whitespace(&content);
+        }
+        if self.collapse_blank_lines {
+            content = collapse_blank_lines(&content);
+        }
+        content
+    }
+}
+
+/// Drop trailing whitespace from every line and blank lines from both ends
+pub fn trim_whitespace(content: &str) -> String {
+    let lines: Vec<&str> = content.lines().map(str::trim_end).collect();
+    let start = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !line.is_empty()).map_or(start, |last| last + 1);
+    lines[start..end].join("\n")
+}
+
+/// Replace every run of blank (or whitespace-only) lines with a single empty line
+pub fn collapse_blank_lines(content: &str) -> String {
+    let mut out: Vec<&str> = Vec::new();
+    for line in content.lines() {
+        let blank = line.trim().is_empty();
+        if blank && out.last().is_some_and(|last| last.is_empty()) {
+            continue;
+        }
+        out.push(if blank { "" } else { line });
+    }
+    out.join("\n")
+}
+
+/// Remove `utm_*` and other tracking query parameters from http(s) URLs
+/// The query is dropped entirely when nothing else is left; fragments are kept
+pub fn strip_tracking_params(content: &str) -> String {
+    static URL: OnceLock<Regex> = OnceLock::new();
+    let url = URL.get_or_init(|| Regex::new(r#"https?://[^\s<>"']+"#).unwrap());
+    url.replace_all(content, |captures: &regex::Captures| strip_url(&captures[0])).into_owned()
+}
+
+fn strip_url(url: &str) -> String {
+    let (rest, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (url, None),
+    };
+    let Some((base, query)) = rest.split_once('?') else {
+        return url.to_string();
+    };
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|param| {
+            let name = param.split('=').next().unwrap_or_default().to_lowercase();
+            !param.is_empty() && !name.starts_with("utm_") && !TRACKING_PARAMS.contains(&name.as_str())
+        })
+        .collect();
+
+    let mut stripped = base.to_string();
+    if !kept.is_empty() {
+        stripped.push('?');
+        stripped.push_str(&kept.join("&"));
+    }
+    if let Some(fragment) = fragment {
+        stripped.push('#');
+        stripped.push_str(fragment);
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_each_transform_normalizes_content() {
+        assert_eq!(trim_whitespace("\n  \nfirst line   \nsecond\t\n\n"), "first line\nsecond");
+        assert_eq!(collapse_blank_lines("one\n\n\n  \ntwo\n\nthree"), "one\n\ntwo\n\nthree");
+        assert_eq!(
+            strip_tracking_params("Read https://example.com/post?id=7&utm_source=mail&fbclid=abc#intro now"),
+            "Read https://example.com/post?id=7#intro now"
+        );
+        assert_eq!(strip_tracking_params("https://example.com/?utm_medium=social"), "https://example.com/");
+        assert_eq!(strip_tracking_params("https://example.com/search?q=rust"), "https://example.com/search?q=rust");
+    }
+
+    #[tokio::test]
+    async fn test_disabled_transforms_keep_raw_input() {
+        let temp_dir = tempdir().unwrap();
+        let db = DbService::new(temp_dir.path().join("test.db")).unwrap();
+        let raw = "  link https://example.com/a?utm_campaign=x  \n\n\n\nend  \n";
+
+        let transforms = CaptureTransforms::load(&db).await.unwrap();
+        assert_eq!(transforms, CaptureTransforms::default());
+        assert_eq!(transforms.apply(raw), "  link https://example.com/a\n\nend");
+
+        for key in [CAPTURE_TRIM_SETTING_KEY, CAPTURE_COLLAPSE_BLANK_LINES_SETTING_KEY, CAPTURE_STRIP_URL_PARAMS_SETTING_KEY] {
+            db.set_setting(key, "false").await.unwrap();
+        }
+        let transforms = CaptureTransforms::load(&db).await.unwrap();
+        assert_eq!(transforms.apply(raw), raw);
+    }
+}
@@ -1,12 +1,28 @@
 use clap::{Arg, Command, ArgMatches};
+use serde::{Deserialize, Serialize};
 use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
 use std::path::PathBuf;
+use std::time::Duration;
+use subtle::ConstantTimeEq;
+use crate::capture::CaptureTransforms;
 use crate::database::DbService;
 use crate::error::AppError;
+use crate::models::Note;
+use crate::validation::SecurityValidator;
+
+/// How long a second launch waits for the running instance before falling back
+const HANDOFF_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Largest handoff line accepted: the note content limit plus room for the JSON around it
+const MAX_HANDOFF_MESSAGE_BYTES: u64 = 2 * SecurityValidator::MAX_NOTE_CONTENT_LENGTH as u64;
 
 #[derive(Debug)]
 pub struct CliArgs {
     pub content: Option<String>,
+    /// Append the content to today's daily note (`--append daily`) instead of creating a note
+    pub append_daily: bool,
     pub should_show_gui: bool,
 }
 
@@ -34,6 +50,13 @@ fn create_cli_app() -> Command {
                         .required(true)
                         .num_args(1..)
                 )
+                .arg(
+                    Arg::new("append")
+                        .long("append")
+                        .help("Append to an existing note instead of creating one")
+                        .value_name("TARGET")
+                        .value_parser(["daily"])
+                )
         )
 }
 
@@ -53,11 +76,13 @@ fn parse_matches(matches: ArgMatches) -> CliArgs {
 
         CliArgs {
             content,
+            append_daily: create_matches.get_one::<String>("append").is_some_and(|target| target == "daily"),
             should_show_gui: false,
         }
     } else {
         CliArgs {
             content: None,
+            append_daily: false,
             should_show_gui: true,
         }
     }
@@ -66,15 +91,188 @@ fn parse_matches(matches: ArgMatches) -> CliArgs {
 /// Handle CLI arguments
 pub async fn handle_cli_args(args: &CliArgs, db_service: &DbService) -> Result<(), AppError> {
     if let Some(content) = &args.content {
-        // Create the note in the database
-        let _note = db_service.create_note(content.clone()).await?;
-        println!("Note created successfully!");
-        return Ok(());
+        capture_content(db_service, content, args.append_daily).await?;
+        if args.append_daily {
+            println!("Appended to today's daily note!");
+        } else {
+            println!("Note created successfully!");
+        }
     }
     
     Ok(())
 }
 
+/// Store captured content as a new note, or append it to today's daily note
+/// Returns the created or appended-to note
+pub async fn capture_content(db_service: &DbService, content: &str, append_daily: bool) -> Result<Note, AppError> {
+    // Normalize before the content is validated and stored
+    let content = CaptureTransforms::load(db_service).await?.apply(content);
+    if append_daily {
+        db_service.append_to_daily_note(&content).await
+    } else {
+        db_service.create_note(content).await
+    }
+}
+
+/// Request sent by a second launch to the running instance
+/// The running instance shows its window and captures `content` when there is any
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandoffMessage {
+    /// Token from the handoff file, so only processes able to read it are served
+    pub token: String,
+    pub content: Option<String>,
+    #[serde(default)]
+    pub append_daily: bool,
+}
+
+impl HandoffMessage {
+    pub fn from_args(args: &CliArgs, token: &str) -> Self {
+        Self {
+            token: token.to_string(),
+            content: args.content.clone(),
+            append_daily: args.append_daily,
+        }
+    }
+
+    /// The message as one newline-terminated JSON line
+    pub fn to_line(&self) -> Result<String, AppError> {
+        let mut line = serde_json::to_string(self)?;
+        line.push('\n');
+        Ok(line)
+    }
+
+    pub fn parse_line(line: &str) -> Result<Self, AppError> {
+        serde_json::from_str(line.trim_end()).map_err(|e| AppError::Validation {
+            field: "handoff".to_string(),
+            message: format!("Invalid handoff message: {}", e),
+        })
+    }
+}
+
+/// Answer of the running instance to a handoff
+#[derive(Debug, Clone, PartialEq)]
+pub enum HandoffReply {
+    Accepted,
+    /// The instance received the request but could not carry it out
+    Rejected(String),
+}
+
+impl HandoffReply {
+    fn to_line(&self) -> String {
+        match self {
+            HandoffReply::Accepted => "ok\n".to_string(),
+            HandoffReply::Rejected(message) => format!("error {}\n", message.replace('\n', " ")),
+        }
+    }
+
+    fn parse_line(line: &str) -> Result<Self, AppError> {
+        let line = line.trim_end();
+        if line == "ok" {
+            return Ok(HandoffReply::Accepted);
+        }
+        match line.strip_prefix("error ") {
+            Some(message) => Ok(HandoffReply::Rejected(message.to_string())),
+            None => Err(AppError::Runtime {
+                message: format!("Unexpected handoff reply: {}", line),
+            }),
+        }
+    }
+}
+
+/// Hand `args` to the running instance
+///
+/// Errors mean the instance could not be reached (no handoff file, stale port,
+/// timeout), in which case the caller should fall back to handling `args` itself.
+pub fn send_handoff(args: &CliArgs) -> Result<HandoffReply, AppError> {
+    let handoff = fs::read_to_string(get_handoff_file_path())?;
+    let (port, token) = handoff.trim().split_once(' ').and_then(|(port, token)| {
+        port.parse::<u16>().ok().map(|port| (port, token))
+    }).ok_or_else(|| AppError::Runtime {
+        message: "Malformed handoff file".to_string(),
+    })?;
+
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let mut stream = TcpStream::connect_timeout(&address, HANDOFF_TIMEOUT)?;
+    stream.set_read_timeout(Some(HANDOFF_TIMEOUT))?;
+    stream.set_write_timeout(Some(HANDOFF_TIMEOUT))?;
+    stream.write_all(HandoffMessage::from_args(args, token).to_line()?.as_bytes())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    HandoffReply::parse_line(&reply)
+}
+
+/// Listen for handoffs from later launches on a localhost port
+///
+/// The port and a fresh token are written to the handoff file next to the lock
+/// file. Each connection carries one message, passed to `handler` on a
+/// background thread; its result is sent back to the second launch.
+pub fn start_handoff_listener<F>(handler: F) -> Result<(), AppError>
+where
+    F: Fn(HandoffMessage) -> Result<(), AppError> + Send + 'static,
+{
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let port = listener.local_addr()?.port();
+    let token = uuid::Uuid::new_v4().to_string();
+    write_handoff_file(&format!("{} {}", port, token))?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    log::warn!("Failed to accept handoff connection: {}", e);
+                    continue;
+                }
+            };
+            if let Err(e) = serve_handoff(stream, &token, &handler) {
+                log::warn!("Failed to serve handoff: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Compare a presented handoff token in constant time, so timing does not reveal a matching prefix
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.as_bytes().ct_eq(expected.as_bytes()).into()
+}
+
+fn serve_handoff<F>(mut stream: TcpStream, token: &str, handler: &F) -> Result<(), AppError>
+where
+    F: Fn(HandoffMessage) -> Result<(), AppError>,
+{
+    stream.set_read_timeout(Some(HANDOFF_TIMEOUT))?;
+    let mut line = String::new();
+    BufReader::new((&stream).take(MAX_HANDOFF_MESSAGE_BYTES)).read_line(&mut line)?;
+
+    let message = HandoffMessage::parse_line(&line)?;
+    if !tokens_match(&message.token, token) {
+        return Err(AppError::Security {
+            message: "Handoff token mismatch".to_string(),
+        });
+    }
+    let reply = match handler(message) {
+        Ok(()) => HandoffReply::Accepted,
+        Err(e) => HandoffReply::Rejected(e.to_string()),
+    };
+    stream.write_all(reply.to_line().as_bytes())?;
+    Ok(())
+}
+
+fn write_handoff_file(contents: &str) -> Result<(), AppError> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    // Keep the token private to the current user
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(get_handoff_file_path())?.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
 /// Create a lock file to prevent multiple instances
 pub fn create_lock_file() -> Result<PathBuf, AppError> {
     let lock_path = get_lock_file_path();
@@ -122,10 +320,11 @@ pub fn create_lock_file() -> Result<PathBuf, AppError> {
     Ok(lock_path)
 }
 
-/// Clean up the lock file
+/// Clean up the lock file and the handoff file
 pub fn cleanup_lock_file() {
     let lock_path = get_lock_file_path();
     let _ = fs::remove_file(lock_path);
+    let _ = fs::remove_file(get_handoff_file_path());
 }
 
 fn get_lock_file_path() -> PathBuf {
@@ -134,6 +333,12 @@ fn get_lock_file_path() -> PathBuf {
     path
 }
 
+fn get_handoff_file_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push("scratch-pad.handoff");
+    path
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,6 +388,21 @@ mod tests {
         assert!(!cli_args.should_show_gui);
     }
 
+    #[test]
+    fn test_parse_cli_args_append_daily() {
+        let app = create_cli_app();
+        let matches = app.try_get_matches_from(vec!["test", "create", "--append", "daily", "Standup", "notes"])
+            .expect("Failed to parse CLI arguments in test");
+        let cli_args = parse_matches(matches);
+        
+        assert_eq!(cli_args.content, Some("Standup notes".to_string()));
+        assert!(cli_args.append_daily);
+        
+        // Only the daily note can be appended to
+        let app = create_cli_app();
+        assert!(app.try_get_matches_from(vec!["test", "create", "--append", "weekly", "Hello"]).is_err());
+    }
+
     #[tokio::test]
     async fn test_handle_cli_args() -> Result<(), anyhow::Error> {
         use anyhow::Context;
@@ -198,6 +418,7 @@ mod tests {
 
         let cli_args = CliArgs {
             content: Some("Test note content".to_string()),
+            append_daily: false,
             should_show_gui: false,
         };
 
@@ -233,6 +454,42 @@ mod tests {
         cleanup_lock_file();
     }
 
+    #[test]
+    fn test_handoff_message_is_one_line_and_round_trips() {
+        let cli_args = CliArgs {
+            content: Some("Standup\n- ship it".to_string()),
+            append_daily: true,
+            should_show_gui: false,
+        };
+        let message = HandoffMessage::from_args(&cli_args, "secret");
+        assert_eq!(message.token, "secret");
+        assert_eq!(message.content.as_deref(), Some("Standup\n- ship it"));
+        assert!(message.append_daily);
+
+        let line = message.to_line().unwrap();
+        assert!(line.ends_with('\n'));
+        assert_eq!(line.matches('\n').count(), 1);
+        assert_eq!(HandoffMessage::parse_line(&line).unwrap(), message);
+        assert!(HandoffMessage::parse_line("not json\n").is_err());
+
+        // A plain second launch only asks for the window
+        let show_only = HandoffMessage::from_args(&CliArgs { content: None, append_daily: false, should_show_gui: true }, "secret");
+        assert!(show_only.content.is_none());
+
+        for reply in [HandoffReply::Accepted, HandoffReply::Rejected("Content too long".to_string())] {
+            assert_eq!(HandoffReply::parse_line(&reply.to_line()).unwrap(), reply);
+        }
+    }
+
+    #[test]
+    fn test_handoff_tokens_must_match_exactly() {
+        let token = uuid::Uuid::new_v4().to_string();
+        assert!(tokens_match(&token, &token));
+        assert!(!tokens_match(&token[..token.len() - 1], &token));
+        assert!(!tokens_match(&format!("{}0", token), &token));
+        assert!(!tokens_match("", &token));
+    }
+
     #[test]
     fn test_get_lock_file_path() {
         let path = get_lock_file_path();
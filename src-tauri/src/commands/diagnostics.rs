@@ -6,8 +6,12 @@
 use crate::commands::shared::{
     validate_ipc_operation, CommandPerformanceTracker, log_security_event
 };
+use crate::commands::registry::{self, CommandSpec};
 use crate::error::ApiError;
-use crate::validation::OperationCapability;
+use crate::logging;
+use crate::performance::get_performance_monitor;
+use crate::performance::system::{PlatformInfo, get_system_monitor};
+use crate::validation::{FrequencyStatus, SecurityValidator};
 use crate::AppState;
 use std::collections::HashMap;
 use tauri::{State, AppHandle};
@@ -54,7 +58,7 @@ pub async fn report_frontend_error(
     // Validate IPC operation with required capabilities
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "report_frontend_error"
     )?;
     
     // Validate error report fields
@@ -71,8 +75,9 @@ pub async fn report_frontend_error(
     );
     
     // Log the error with security considerations (avoid logging sensitive data)
-    eprintln!(
-        "[ERROR_REPORT] {} - {} - Message: {} - Component: {}",
+    log::error!(
+        target: "frontend",
+        "{} - {} - Message: {} - Component: {}",
         error_report.timestamp.as_deref().unwrap_or("unknown"),
         error_report.error_id,
         sanitize_for_logging(&error_report.message),
@@ -135,6 +140,161 @@ pub async fn get_backend_error_details(
     Ok(details)
 }
 
+/// Retrieves platform information for About/Support screens
+/// 
+/// Security features:
+/// - IPC operation validation (read-only)
+/// - Hostname lookup failure reported as a sentinel, not an error
+/// - Performance monitoring
+#[tauri::command]
+pub async fn get_platform_info(
+    app_state: State<'_, AppState>,
+) -> Result<PlatformInfo, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_platform_info");
+    
+    // Validate IPC operation
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_platform_info"
+    )?;
+    
+    let platform_info = get_system_monitor()?.get_platform_info()?;
+    
+    // Log diagnostic access
+    log_security_event(
+        "PLATFORM_INFO_ACCESS",
+        "IPC",
+        true,
+        "Platform information requested"
+    );
+    
+    Ok(platform_info)
+}
+
+/// Reports how much of each operation source's frequency budget is in use
+/// 
+/// Security features:
+/// - Read-only counters, no note data exposed
+/// - Skips IPC operation validation so checking the budget does not spend it
+/// - Performance monitoring
+#[tauri::command]
+pub async fn get_frequency_status(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<FrequencyStatus>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_frequency_status");
+    
+    let status = app_state.security_validator.frequency_status()?;
+    
+    // Log diagnostic access
+    log_security_event(
+        "FREQUENCY_STATUS_ACCESS",
+        "IPC",
+        true,
+        "Frequency status requested"
+    );
+    
+    Ok(status)
+}
+
+/// Asks a running cancellable operation to stop
+/// 
+/// `operation_id` is the id the operation was started with (see `search_notes_regex`
+/// and `export_query_results`); it stops at its next check and returns `CANCELLED`.
+/// Returns false when no operation with that id is running.
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - Operation id validated with the correlation id rules
+#[tauri::command]
+pub async fn cancel_operation(
+    operation_id: String,
+    app_state: State<'_, AppState>,
+) -> Result<bool, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("cancel_operation");
+    
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "cancel_operation"
+    )?;
+    SecurityValidator::validate_correlation_id(&operation_id)?;
+    
+    let cancelled = get_performance_monitor().cancel_operation(&operation_id);
+    
+    log_security_event(
+        "OPERATION_CANCEL",
+        "IPC",
+        cancelled,
+        &format!("Cancellation requested for operation {}", operation_id)
+    );
+    
+    Ok(cancelled)
+}
+
+/// Returns the last `lines` lines of the log file, oldest first, for support bundles
+/// 
+/// Empty when logging to file is not active. At most `MAX_RECENT_LOG_LINES` lines
+/// are returned; older lines are read from the last rotated file when needed.
+/// 
+/// Security features:
+/// - IPC operation context validation with SystemAccess capability, since the log
+///   holds the security audit trail
+/// - Line count validation
+/// - Audit logging for log access
+#[tauri::command]
+pub async fn get_recent_logs(
+    lines: usize,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<String>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_recent_logs");
+    
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_recent_logs"
+    )?;
+    
+    if lines == 0 || lines > logging::MAX_RECENT_LOG_LINES {
+        return Err(ApiError {
+            code: "VALIDATION_ERROR".to_string(),
+            message: format!("Line count must be between 1 and {}", logging::MAX_RECENT_LOG_LINES),
+        });
+    }
+    
+    log_security_event(
+        "LOG_ACCESS",
+        "IPC",
+        true,
+        &format!("Last {} log lines requested", lines)
+    );
+    
+    let recent = match logging::log_file_path() {
+        Some(path) => logging::read_recent_lines(&path, lines)?,
+        None => Vec::new(),
+    };
+    
+    Ok(recent)
+}
+
+/// Lists every IPC command with the capabilities it requires, from the command registry
+/// 
+/// For permissions UIs and documentation. Commands listed without capabilities
+/// skip operation context validation.
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+#[tauri::command]
+pub async fn list_commands(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<CommandSpec>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("list_commands");
+    
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "list_commands"
+    )?;
+    
+    Ok(registry::COMMANDS.to_vec())
+}
+
 /// Validates frontend error report for security
 fn validate_error_report(report: &FrontendErrorReport) -> Result<(), ApiError> {
     // Validate error_id
@@ -239,7 +399,7 @@ fn sanitize_for_logging(content: &str) -> String {
 #[allow(unused)]
 mod tests_disabled {
     use super::*;
-    use crate::validation::{SecurityValidator, OperationContext};
+    use crate::validation::{SecurityValidator, OperationCapability, OperationContext};
     use crate::database::DbService;
     use crate::search::SearchService;
     use crate::settings::SettingsService;
@@ -258,7 +418,7 @@ mod tests_disabled {
         let security_validator = Arc::new(SecurityValidator::new());
         let search_service = Arc::new(SearchService::new(db_service.clone()));
         let settings_service = Arc::new(SettingsService::new(db_service.clone()));
-        let plugin_manager = Arc::new(tokio::sync::Mutex::new(PluginManager::new()));
+        let plugin_manager = Arc::new(tokio::sync::RwLock::new(PluginManager::new()));
         
         AppState {
             db: db_service,
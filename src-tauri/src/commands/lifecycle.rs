@@ -8,31 +8,42 @@ use crate::commands::shared::{
     validate_ipc_operation, CommandPerformanceTracker, log_security_event
 };
 use crate::error::ApiError;
-use crate::validation::OperationCapability;
+use crate::shutdown::ShutdownStep;
 use crate::AppState;
 use tauri::State;
+use serde::Serialize;
 
-/// Checks if the application is currently shutting down
+/// Shutdown state reported to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct ShutdownState {
+    pub shutting_down: bool,
+    /// The step currently executing (or the last one run) during shutdown
+    pub current_step: Option<ShutdownStep>,
+}
+
+/// Checks if the application is currently shutting down, and which step it is on
 /// 
 /// Security features:
-/// - IPC operation context validation with SystemAccess capability
+/// - IPC operation context validation with ReadNotes capability, so it keeps
+///   working after shutdown starts blocking writes
 /// - Read-only operation (minimal security risk)
 /// - Performance monitoring
 /// - Audit logging for shutdown state queries
 #[tauri::command]
 pub async fn is_shutting_down(
     app_state: State<'_, AppState>
-) -> Result<bool, ApiError> {
+) -> Result<ShutdownState, ApiError> {
     let _tracker = CommandPerformanceTracker::new("is_shutting_down");
     
     // Validate IPC operation with required capabilities
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "is_shutting_down"
     )?;
     
     // Check shutdown state
     let shutting_down = app_state.shutdown_manager.is_shutting_down();
+    let current_step = app_state.shutdown_manager.current_step();
     
     // Log shutdown state query (only log if actually shutting down to reduce noise)
     if shutting_down {
@@ -44,7 +55,10 @@ pub async fn is_shutting_down(
         );
     }
     
-    Ok(shutting_down)
+    Ok(ShutdownState {
+        shutting_down,
+        current_step,
+    })
 }
 
 /// Initiates graceful application shutdown
@@ -65,7 +79,7 @@ pub async fn initiate_shutdown(
     // Validate IPC operation with required capabilities
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "initiate_shutdown"
     )?;
     
     // Log shutdown initiation
@@ -114,7 +128,7 @@ pub async fn initiate_shutdown(
 #[allow(unused)]
 mod tests_disabled {
     use super::*;
-    use crate::validation::{SecurityValidator, OperationContext};
+    use crate::validation::{SecurityValidator, OperationCapability, OperationContext};
     use crate::database::DbService;
     use crate::search::SearchService;
     use crate::settings::SettingsService;
@@ -133,7 +147,7 @@ mod tests_disabled {
         let security_validator = Arc::new(SecurityValidator::new());
         let search_service = Arc::new(SearchService::new(db_service.clone()));
         let settings_service = Arc::new(SettingsService::new(db_service.clone()));
-        let plugin_manager = Arc::new(tokio::sync::Mutex::new(PluginManager::new()));
+        let plugin_manager = Arc::new(tokio::sync::RwLock::new(PluginManager::new()));
         
         AppState {
             db: db_service,
@@ -9,29 +9,39 @@ pub mod settings;
 pub mod system;
 pub mod lifecycle;
 pub mod diagnostics;
+pub mod performance;
+pub mod plugins;
 pub mod shared;
+pub mod registry;
 
 // Note Management Commands
 pub use notes::{
-    create_note, update_note, delete_note, get_note, get_notes_paginated,
-    get_all_notes
+    create_note, update_note, delete_note, get_note, get_or_create_daily_note, resolve_note_by_path, get_note_by_external_id, get_notes_paginated, query_notes, get_note_previews,
+    get_all_notes, stage_draft, get_pending_drafts, discard_draft,
+    set_note_pinned, toggle_pin, apply_auto_pin, find_and_replace, set_note_archived, set_note_locked, reorder_notes, set_note_encrypted, unlock_encryption, lock_encryption, copy_note_to_clipboard, diff_note_versions, note_word_frequency, resolve_links, get_backlinks,
+    get_note_counts, restore_note, undo_delete, restore_all_trash, convert_note_format, export_query_results, preview_export, export_note_html, create_note_from_file,
+    add_attachment, remove_attachment, list_attachments, add_note_tag, get_note_tags, rename_tag
 };
 
 // Search Commands  
 pub use search::{
     search_notes, search_notes_paginated, search_notes_boolean_paginated,
-    validate_boolean_search_query
+    validate_boolean_search_query, parse_boolean_query, explain_search,
+    count_search_results, count_boolean_search_results,
+    get_search_history, clear_search_history, search_batch, extract_query_terms, search_in_note,
+    search_notes_regex, get_index_stats, optimize_index
 };
 
 pub use settings::{
     get_setting, set_setting, get_all_settings, delete_setting,
-    save_settings, load_settings, register_global_shortcut
+    save_settings, load_settings, register_global_shortcut,
+    get_settings_namespace, delete_settings_namespace, reset_to_defaults, get_setting_history, revert_setting
 };
 
 pub use system::{
     unregister_global_shortcut,
     toggle_window_visibility, show_window, hide_window, 
-    is_window_visible, get_current_shortcut, shutdown_application
+    is_window_visible, get_current_shortcut, shutdown_application, get_database_stats, health_check
 };
 
 pub use lifecycle::{
@@ -39,7 +49,19 @@ pub use lifecycle::{
 };
 
 pub use diagnostics::{
-    report_frontend_error, get_backend_error_details
+    report_frontend_error, get_backend_error_details, get_platform_info, get_frequency_status,
+    cancel_operation, get_recent_logs, list_commands
+};
+
+pub use performance::{
+    get_performance_overview, get_performance_metrics, get_performance_analytics, get_performance_summary_by_source,
+    record_frontend_metrics, get_performance_alerts, update_performance_budget,
+    get_performance_budget, list_budget_presets, apply_budget_preset,
+    set_metrics_interval, get_system_thresholds, set_system_thresholds
+};
+
+pub use plugins::{
+    list_plugins, get_plugin
 };
 
 // Shared utilities for command implementations
@@ -58,6 +80,8 @@ pub use shared::{
 /// - All system commands with capability verification
 /// - All lifecycle commands with shutdown coordination
 /// - All diagnostic commands with error reporting
+/// - All performance commands with budget management
+/// - All plugin queries under a shared read lock
 /// - Performance monitoring for all commands
 /// - Security logging for all operations
 #[macro_export]
@@ -68,15 +92,65 @@ macro_rules! generate_command_handler {
             crate::commands::notes::create_note,
             crate::commands::notes::update_note,
             crate::commands::notes::delete_note,
+            crate::commands::notes::restore_note,
+            crate::commands::notes::undo_delete,
+            crate::commands::notes::restore_all_trash,
             crate::commands::notes::get_note,
+            crate::commands::notes::get_or_create_daily_note,
+            crate::commands::notes::resolve_note_by_path,
+            crate::commands::notes::get_note_by_external_id,
             crate::commands::notes::get_notes_paginated,
+            crate::commands::notes::query_notes,
+            crate::commands::notes::get_note_previews,
             crate::commands::notes::get_all_notes,
+            crate::commands::notes::stage_draft,
+            crate::commands::notes::get_pending_drafts,
+            crate::commands::notes::discard_draft,
+            crate::commands::notes::set_note_pinned,
+            crate::commands::notes::toggle_pin,
+            crate::commands::notes::find_and_replace,
+            crate::commands::notes::apply_auto_pin,
+            crate::commands::notes::set_note_archived,
+            crate::commands::notes::set_note_locked,
+            crate::commands::notes::reorder_notes,
+            crate::commands::notes::set_note_encrypted,
+            crate::commands::notes::unlock_encryption,
+            crate::commands::notes::lock_encryption,
+            crate::commands::notes::get_note_counts,
+            crate::commands::notes::convert_note_format,
+            crate::commands::notes::export_query_results,
+            crate::commands::notes::preview_export,
+            crate::commands::notes::export_note_html,
+            crate::commands::notes::create_note_from_file,
+            crate::commands::notes::add_attachment,
+            crate::commands::notes::remove_attachment,
+            crate::commands::notes::list_attachments,
+            crate::commands::notes::add_note_tag,
+            crate::commands::notes::get_note_tags,
+            crate::commands::notes::rename_tag,
+            crate::commands::notes::copy_note_to_clipboard,
+            crate::commands::notes::diff_note_versions,
+            crate::commands::notes::note_word_frequency,
+            crate::commands::notes::resolve_links,
+            crate::commands::notes::get_backlinks,
             
             // Search Commands
             crate::commands::search::search_notes,
             crate::commands::search::search_notes_paginated,
             crate::commands::search::search_notes_boolean_paginated,
             crate::commands::search::validate_boolean_search_query,
+            crate::commands::search::parse_boolean_query,
+            crate::commands::search::count_search_results,
+            crate::commands::search::count_boolean_search_results,
+            crate::commands::search::explain_search,
+            crate::commands::search::get_search_history,
+            crate::commands::search::clear_search_history,
+            crate::commands::search::search_batch,
+            crate::commands::search::extract_query_terms,
+            crate::commands::search::search_in_note,
+            crate::commands::search::search_notes_regex,
+            crate::commands::search::get_index_stats,
+            crate::commands::search::optimize_index,
             
             // Settings Commands
             crate::commands::settings::get_setting,
@@ -86,6 +160,11 @@ macro_rules! generate_command_handler {
             crate::commands::settings::save_settings,
             crate::commands::settings::load_settings,
             crate::commands::settings::register_global_shortcut,
+            crate::commands::settings::get_settings_namespace,
+            crate::commands::settings::delete_settings_namespace,
+            crate::commands::settings::reset_to_defaults,
+            crate::commands::settings::get_setting_history,
+            crate::commands::settings::revert_setting,
             
             // System Commands  
             crate::commands::system::unregister_global_shortcut,
@@ -95,6 +174,8 @@ macro_rules! generate_command_handler {
             crate::commands::system::is_window_visible,
             crate::commands::system::get_current_shortcut,
             crate::commands::system::shutdown_application,
+            crate::commands::system::get_database_stats,
+            crate::commands::system::health_check,
             
             // Lifecycle Commands
             crate::commands::lifecycle::is_shutting_down,
@@ -102,7 +183,31 @@ macro_rules! generate_command_handler {
             
             // Diagnostic Commands
             crate::commands::diagnostics::report_frontend_error,
-            crate::commands::diagnostics::get_backend_error_details
+            crate::commands::diagnostics::get_backend_error_details,
+            crate::commands::diagnostics::get_platform_info,
+            crate::commands::diagnostics::get_frequency_status,
+            crate::commands::diagnostics::cancel_operation,
+            crate::commands::diagnostics::get_recent_logs,
+            crate::commands::diagnostics::list_commands,
+            
+            // Performance Commands
+            crate::commands::performance::get_performance_overview,
+            crate::commands::performance::get_performance_metrics,
+            crate::commands::performance::get_performance_analytics,
+            crate::commands::performance::get_performance_summary_by_source,
+            crate::commands::performance::record_frontend_metrics,
+            crate::commands::performance::get_performance_alerts,
+            crate::commands::performance::update_performance_budget,
+            crate::commands::performance::get_performance_budget,
+            crate::commands::performance::list_budget_presets,
+            crate::commands::performance::apply_budget_preset,
+            crate::commands::performance::set_metrics_interval,
+            crate::commands::performance::get_system_thresholds,
+            crate::commands::performance::set_system_thresholds,
+            
+            // Plugin Commands
+            crate::commands::plugins::list_plugins,
+            crate::commands::plugins::get_plugin
         ]
     };
 }
\ No newline at end of file
@@ -5,14 +5,209 @@
 /// monolithic implementation while providing better organization.
 
 use crate::commands::shared::{
-    validate_ipc_operation, validate_note_content_secure, validate_id_secure,
-    validate_pagination_secure, CommandPerformanceTracker, log_security_event
+    validate_ipc_operation, validate_ipc_operation_correlated, validate_note_content_secure, validate_id_secure,
+    validate_pagination_secure, validate_search_query_secure, run_with_timeout, run_with_timeout_in,
+    run_cancellable_in,
+    CommandPerformanceTracker, log_security_event
 };
-use crate::error::ApiError;
-use crate::models::Note;
-use crate::validation::OperationCapability;
+use crate::database::DbService;
+use crate::diff::{diff_lines, DiffHunk, DIFF_CONTEXT_LINES};
+use crate::error::{ApiError, AppError};
+use crate::export::{self, ExportFormat, ExportManifest, ExportPreview};
+use crate::import::{self, ConflictStrategy, ImportMatchKey, ImportResolution, ImportSummary, IMPORT_BASE_DIR_SETTING_KEY};
+use crate::links::NoteLink;
+use crate::markdown;
+use crate::search::{NoteReplacement, TermCount};
+use crate::models::{Note, NoteAttachment, NoteCounts, NoteDetails, NoteDraft, NoteFilter, NoteFormat, NotePreview, UndoToken};
+use crate::traits::clipboard::ClipboardWriter;
+use crate::traits::events::EventEmitter;
+use crate::validation::{OperationContext, SecurityValidator};
 use crate::AppState;
-use tauri::State;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+
+/// Event emitted after any note mutation so all windows can refresh
+pub const NOTES_CHANGED_EVENT: &str = "notes-changed";
+
+/// Event emitted when `delete_note` trashes a note, carrying its undo token
+pub const NOTE_DELETED_EVENT: &str = "note-deleted";
+
+/// Kind of change carried by a `notes-changed` event
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum NoteChangeKind {
+    #[serde(rename = "created")]
+    Created,
+    #[serde(rename = "updated")]
+    Updated,
+    #[serde(rename = "deleted")]
+    Deleted,
+    /// Pin state changed (pinned or unpinned)
+    #[serde(rename = "pinned")]
+    Pinned,
+    /// Archive state changed (archived or unarchived)
+    #[serde(rename = "archived")]
+    Archived,
+    /// Edit lock changed (locked or unlocked)
+    #[serde(rename = "locked")]
+    Locked,
+    /// Manual order changed
+    #[serde(rename = "reordered")]
+    Reordered,
+}
+
+/// Payload of a `notes-changed` event
+/// Single-note mutations list one id; batch operations emit one event listing every affected id
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NotesChangedEvent {
+    pub kind: NoteChangeKind,
+    pub ids: Vec<i64>,
+}
+
+/// Emit a `notes-changed` event; emission failures are logged, never surfaced to the caller
+pub fn emit_notes_changed(emitter: &dyn EventEmitter, kind: NoteChangeKind, ids: Vec<i64>) {
+    if ids.is_empty() {
+        return;
+    }
+    let event = NotesChangedEvent { kind, ids };
+    if let Err(e) = emitter.emit_event(NOTES_CHANGED_EVENT, serde_json::json!(event)) {
+        log::warn!("Failed to emit {}: {}", NOTES_CHANGED_EVENT, e);
+    }
+}
+
+/// Update a note's content and notify other windows
+async fn update_note_and_notify(
+    db: Arc<DbService>,
+    emitter: &dyn EventEmitter,
+    context: &OperationContext,
+    id: i64,
+    content: String,
+) -> Result<Note, AppError> {
+    let note = run_with_timeout_in(context, "note_crud", async move { db.update_note_content(id, content).await }).await?;
+    emit_notes_changed(emitter, NoteChangeKind::Updated, vec![note.id]);
+    Ok(note)
+}
+
+/// Representation written to the clipboard by `copy_note_to_clipboard`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum ClipboardFormat {
+    /// Stored content, byte for byte
+    #[serde(rename = "raw")]
+    Raw,
+    /// Markdown source; plain text notes are copied as they are
+    #[serde(rename = "markdown")]
+    Markdown,
+    /// Markdown notes with formatting stripped; plain text notes as they are
+    #[serde(rename = "rendered_text")]
+    RenderedText,
+}
+
+/// Render a note's content in the requested clipboard format
+pub fn render_for_clipboard(note: &Note, format: ClipboardFormat) -> String {
+    match format {
+        ClipboardFormat::Raw | ClipboardFormat::Markdown => note.content.clone(),
+        ClipboardFormat::RenderedText => match note.format {
+            NoteFormat::Markdown => markdown::to_plain_text(&note.content),
+            NoteFormat::PlainText => note.content.clone(),
+        },
+    }
+}
+
+/// Look up a note and write it to the clipboard, returning the copied text
+async fn copy_note(
+    db: Arc<DbService>,
+    clipboard: &dyn ClipboardWriter,
+    id: i64,
+    format: ClipboardFormat,
+) -> Result<String, AppError> {
+    let note = run_with_timeout("note_crud", async move { db.get_note(id).await })
+        .await?
+        .ok_or(AppError::NotFound { id })?;
+    let text = render_for_clipboard(&note, format);
+    clipboard.write_text(&text)?;
+    Ok(text)
+}
+
+/// Look up a note and flag whether it exceeds the configured soft character limit
+async fn note_details(db: Arc<DbService>, id: i64) -> Result<Option<NoteDetails>, AppError> {
+    run_with_timeout("note_crud", async move {
+        let Some(note) = db.get_note(id).await? else {
+            return Ok(None);
+        };
+        let limit = db.soft_char_limit().await?;
+        let over_soft_limit = limit > 0 && note.content.chars().count() > limit;
+        Ok(Some(NoteDetails { note, over_soft_limit }))
+    }).await
+}
+
+/// Change a note's format, optionally rewriting its content to match
+/// 
+/// With `rewrite_content`, PlainText→Markdown escapes Markdown syntax so the text
+/// renders as before, and Markdown→PlainText strips formatting. Saving the note
+/// re-indexes it for search.
+async fn convert_format(
+    db: Arc<DbService>,
+    id: i64,
+    to: NoteFormat,
+    rewrite_content: bool,
+) -> Result<Note, AppError> {
+    let lookup_db = db.clone();
+    let mut note = run_with_timeout("note_crud", async move { lookup_db.get_note(id).await })
+        .await?
+        .ok_or(AppError::NotFound { id })?;
+    if note.format == to {
+        return Ok(note);
+    }
+    
+    if rewrite_content {
+        note.content = match to {
+            NoteFormat::Markdown => markdown::escape_markdown(&note.content),
+            NoteFormat::PlainText => markdown::to_plain_text(&note.content),
+        };
+        SecurityValidator::validate_note_content(&note.content)?;
+    }
+    note.format = to;
+    
+    run_with_timeout("note_crud", async move { db.update_note(note).await }).await
+}
+
+/// Line-based diff between two versions of a note
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NoteDiff {
+    pub note_id: i64,
+    pub from_version: i64,
+    pub to_version: i64,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Diff two stored versions of a note, failing if either is missing
+async fn diff_versions(
+    db: Arc<DbService>,
+    id: i64,
+    from_version: i64,
+    to_version: i64,
+) -> Result<NoteDiff, AppError> {
+    if db.get_note(id).await?.is_none() {
+        return Err(AppError::NotFound { id });
+    }
+    
+    let mut contents = Vec::with_capacity(2);
+    for (field, version) in [("from_version", from_version), ("to_version", to_version)] {
+        let note_version = db.get_note_version(id, version).await?.ok_or_else(|| AppError::Validation {
+            field: field.to_string(),
+            message: format!("Version {} does not exist for note {}", version, id),
+        })?;
+        contents.push(note_version.content);
+    }
+    
+    Ok(NoteDiff {
+        note_id: id,
+        from_version,
+        to_version,
+        hunks: diff_lines(&contents[0], &contents[1], DIFF_CONTEXT_LINES),
+    })
+}
 
 /// Creates a new note with security validation
 /// 
@@ -21,17 +216,30 @@ use tauri::State;
 /// - Content validation (1MB limit, malicious pattern detection)
 /// - Frequency limit enforcement (15 operations/minute for IPC)
 /// - Performance monitoring (<2ms overhead target)
+/// 
+/// With `auto_nickname` set and no nickname given, a nickname is derived from the first line.
+/// Without a `format` the note takes the `notes.default_format` setting.
+/// An optional `correlation_id` is used as the operation id in the recorded metrics.
+/// 
+/// Returns the complete stored note (id, timestamps, path, format and flags),
+/// so the UI can render it without a follow-up `get_note`.
 #[tauri::command]
 pub async fn create_note(
     content: String,
+    nickname: Option<String>,
+    auto_nickname: Option<bool>,
+    format: Option<NoteFormat>,
+    correlation_id: Option<String>,
+    app_handle: AppHandle,
     app_state: State<'_, AppState>,
 ) -> Result<Note, ApiError> {
     let _tracker = CommandPerformanceTracker::new("create_note");
     
     // Validate IPC operation with required capabilities
-    let context = validate_ipc_operation(
+    let context = validate_ipc_operation_correlated(
         &app_state.security_validator,
-        vec![OperationCapability::WriteNotes]
+        "create_note",
+        correlation_id.as_deref()
     )?;
     
     // Validate note content with security context
@@ -46,7 +254,12 @@ pub async fn create_note(
     );
     
     // Create note using database service
-    let note = app_state.db.create_note(content).await?;
+    let db = app_state.db.clone();
+    let auto_nickname = auto_nickname.unwrap_or(false);
+    let note = run_with_timeout_in(&context, "note_crud", async move {
+        db.create_note_with_format(content, nickname, auto_nickname, format).await
+    }).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Created, vec![note.id]);
     
     Ok(note)
 }
@@ -58,24 +271,129 @@ pub async fn create_note(
 /// - ID validation (positive integers, reasonable bounds)
 /// - Frequency limit enforcement
 /// - Performance monitoring
+/// 
+/// The note carries `over_soft_limit`, an advisory flag for content longer than
+/// the `notes.soft_char_limit` setting.
 #[tauri::command]
 pub async fn get_note(
     id: i64,
     app_state: State<'_, AppState>,
-) -> Result<Option<Note>, ApiError> {
+) -> Result<Option<NoteDetails>, ApiError> {
     let _tracker = CommandPerformanceTracker::new("get_note");
     
     // Validate IPC operation with required capabilities
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::ReadNotes]
+        "get_note"
     )?;
     
     // Validate ID parameter
     validate_id_secure(id)?;
     
     // Retrieve note from database
-    let note = app_state.db.get_note(id).await?;
+    let note = note_details(app_state.db.clone(), id).await?;
+    
+    // Remember the note as recently opened for the recent note shortcut
+    if note.is_some() {
+        if let Err(e) = app_state.db.record_note_access(id).await {
+            log::warn!("Failed to record access to note {}: {}", id, e);
+        }
+    }
+    
+    Ok(note)
+}
+
+/// Returns today's daily note, creating it if this is the first request of the day
+/// 
+/// The daily note is the one with the reserved nickname for the local date
+/// (e.g. "Daily 2024-05-01"); after local midnight a new one is started.
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes and WriteNotes capabilities
+/// - Frequency limit enforcement
+/// - Performance monitoring
+#[tauri::command]
+pub async fn get_or_create_daily_note(
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<Note, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_or_create_daily_note");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_or_create_daily_note"
+    )?;
+    
+    let db = app_state.db.clone();
+    let (note, created) = run_with_timeout("note_crud", async move { db.get_or_create_daily_note().await }).await?;
+    if created {
+        log_security_event("NOTE_CREATE", "IPC", true, "Created today's daily note");
+        emit_notes_changed(&app_handle, NoteChangeKind::Created, vec![note.id]);
+    }
+    
+    Ok(note)
+}
+
+/// Looks up a note by its stable external id, a UUID kept across export and import
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - UUID format validation
+/// - Frequency limit enforcement
+/// - Performance monitoring
+#[tauri::command]
+pub async fn get_note_by_external_id(
+    external_id: String,
+    app_state: State<'_, AppState>,
+) -> Result<Option<Note>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_note_by_external_id");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_note_by_external_id"
+    )?;
+    
+    let db = app_state.db.clone();
+    let note = run_with_timeout("note_crud", async move {
+        db.get_note_by_external_id(&external_id).await
+    }).await?;
+    
+    Ok(note)
+}
+
+/// Looks up a note from its `path` (e.g. `/note/1` or `/note/1/my-title`)
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - Path length bounds; the id parsed from the path is validated
+/// - Frequency limit enforcement
+/// - Performance monitoring
+#[tauri::command]
+pub async fn resolve_note_by_path(
+    path: String,
+    app_state: State<'_, AppState>,
+) -> Result<Option<Note>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("resolve_note_by_path");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "resolve_note_by_path"
+    )?;
+    
+    if path.len() > SecurityValidator::MAX_PATH_LENGTH {
+        return Err(AppError::Validation {
+            field: "path".to_string(),
+            message: "Path too long".to_string(),
+        }.into());
+    }
+    
+    let db = app_state.db.clone();
+    let note = run_with_timeout("note_crud", async move {
+        db.resolve_note_by_path(&path).await
+    }).await?;
     
     Ok(note)
 }
@@ -96,14 +414,15 @@ pub async fn get_all_notes(
     // Validate IPC operation with required capabilities
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::ReadNotes]
+        "get_all_notes"
     )?;
     
     // Log security event
     log_security_event("NOTE_LIST_ALL", "IPC", true, "Retrieving all notes");
     
     // Retrieve all notes from database (Fixed: pass None, None for no pagination)
-    let notes = app_state.db.get_all_notes().await?;
+    let db = app_state.db.clone();
+    let notes = run_with_timeout("note_list", async move { db.get_all_notes().await }).await?;
     
     Ok(notes)
 }
@@ -126,7 +445,7 @@ pub async fn get_notes_paginated(
     // Validate IPC operation with required capabilities
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::ReadNotes]
+        "get_notes_paginated"
     )?;
     
     // Validate pagination parameters (convert to usize for validation)
@@ -141,11 +460,85 @@ pub async fn get_notes_paginated(
     );
     
     // Retrieve paginated notes from database (Fixed: pass i64 parameters)
-    let notes = app_state.db.get_notes_paginated(offset, limit).await?;
+    let db = app_state.db.clone();
+    let notes = run_with_timeout("note_list", async move { db.get_notes_paginated(offset, limit).await }).await?;
+    
+    Ok(notes)
+}
+
+/// Lists notes matching state filters, tags, sort and pagination in one call
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - Pagination parameter validation (limits: max 1000, max offset 100k)
+/// - Tag name validation
+/// - Single parameterized query; no filter value is interpolated into SQL
+#[tauri::command]
+pub async fn query_notes(
+    filter: NoteFilter,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<Note>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("query_notes");
+    
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "query_notes"
+    )?;
+    
+    if filter.offset < 0 {
+        return Err(AppError::Validation {
+            field: "offset".to_string(),
+            message: "Offset cannot be negative".to_string(),
+        }.into());
+    }
+    validate_pagination_secure(filter.offset as usize, filter.limit.max(0) as usize)?;
+    for tag in &filter.tags {
+        SecurityValidator::validate_tag(tag)?;
+    }
+    
+    log_security_event(
+        "NOTE_QUERY",
+        "IPC",
+        true,
+        &format!("Querying {} notes at offset {} with {} tag filters", filter.limit, filter.offset, filter.tags.len())
+    );
+    
+    let db = app_state.db.clone();
+    let notes = run_with_timeout("note_list", async move { db.query_notes(&filter).await }).await?;
     
     Ok(notes)
 }
 
+/// Retrieves a page of note previews: metadata plus the first ~200 characters of content
+/// 
+/// Cheaper than `get_notes_paginated` for long notes, whose full bodies are never loaded.
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - Pagination parameter validation (limits: max 1000, max offset 100k)
+/// - Performance monitoring
+#[tauri::command]
+pub async fn get_note_previews(
+    limit: i64,
+    offset: i64,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<NotePreview>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_note_previews");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_note_previews"
+    )?;
+    
+    validate_pagination_secure(offset as usize, limit as usize)?;
+    
+    let db = app_state.db.clone();
+    let previews = run_with_timeout("note_list", async move { db.get_note_previews(offset, limit).await }).await?;
+    
+    Ok(previews)
+}
+
 /// Updates an existing note with security validation (Fixed: correct method signature)
 /// 
 /// Security features preserved:
@@ -154,18 +547,22 @@ pub async fn get_notes_paginated(
 /// - ID validation for the note being updated
 /// - Frequency limit enforcement
 /// - Performance monitoring
+/// - Optional `correlation_id` recorded as the operation id in metrics
 #[tauri::command]
 pub async fn update_note(
     id: i64,      // Fixed: separate id parameter
     content: String,  // Fixed: separate content parameter
+    correlation_id: Option<String>,
+    app_handle: AppHandle,
     app_state: State<'_, AppState>,
 ) -> Result<Note, ApiError> {
     let _tracker = CommandPerformanceTracker::new("update_note");
     
     // Validate IPC operation with required capabilities
-    let context = validate_ipc_operation(
+    let context = validate_ipc_operation_correlated(
         &app_state.security_validator,
-        vec![OperationCapability::WriteNotes]
+        "update_note",
+        correlation_id.as_deref()
     )?;
     
     // Validate note ID
@@ -183,30 +580,40 @@ pub async fn update_note(
     );
     
     // Update note using database service (Fixed: pass id and content separately)
-    let updated_note = app_state.db.update_note_content(id, content).await?;
+    let updated_note = update_note_and_notify(app_state.db.clone(), &app_handle, &context, id, content).await?;
     
     Ok(updated_note)
 }
 
-/// Deletes a note with security validation
+/// Moves a note to the trash with security validation
+/// 
+/// Trashed notes are hidden from listings and search, can be brought back with
+/// `restore_note`, and are purged after `notes.trash_retention_days`.
+/// 
+/// Returns an undo token, also sent in a `note-deleted` event, that `undo_delete`
+/// accepts for `UNDO_DELETE_TTL_SECS` seconds.
 /// 
 /// Security features preserved:
 /// - IPC operation context validation with DeleteNotes capability
 /// - ID validation (positive integers, reasonable bounds)
 /// - Frequency limit enforcement
 /// - Performance monitoring
+/// - Optional `correlation_id` recorded as the operation id in metrics
 /// - Audit logging for delete operations
 #[tauri::command]
 pub async fn delete_note(
     id: i64,
+    correlation_id: Option<String>,
+    app_handle: AppHandle,
     app_state: State<'_, AppState>,
-) -> Result<(), ApiError> {
+) -> Result<UndoToken, ApiError> {
     let _tracker = CommandPerformanceTracker::new("delete_note");
     
     // Validate IPC operation with required capabilities
-    let _context = validate_ipc_operation(
+    let context = validate_ipc_operation_correlated(
         &app_state.security_validator,
-        vec![OperationCapability::DeleteNotes]
+        "delete_note",
+        correlation_id.as_deref()
     )?;
     
     // Validate ID parameter
@@ -220,68 +627,1464 @@ pub async fn delete_note(
         &format!("Deleting note with ID {}", id)
     );
     
-    // Delete note from database
-    app_state.db.delete_note(id).await?;
+    // Soft-delete: the note stays restorable until the trash is purged
+    let db = app_state.db.clone();
+    let undo = run_with_timeout_in(&context, "note_crud", async move { db.trash_note_with_undo(id).await }).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Deleted, vec![id]);
+    if let Err(e) = app_handle.emit_event(NOTE_DELETED_EVENT, serde_json::json!(undo)) {
+        log::warn!("Failed to emit {}: {}", NOTE_DELETED_EVENT, e);
+    }
     
-    Ok(())
+    Ok(undo)
 }
 
-#[cfg(test)]
-mod tests {
+/// Restores a note trashed by `delete_note` using the undo token it returned
+/// 
+/// Tokens are single-use and expire after `UNDO_DELETE_TTL_SECS` seconds; after that
+/// the note stays in the trash and `restore_note` brings it back.
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - Token length validation
+/// - Audit logging for undo operations
+#[tauri::command]
+pub async fn undo_delete(
+    token: String,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<Note, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("undo_delete");
     
-    use crate::database::DbService;
-    use crate::validation::SecurityValidator;
-    use crate::search::SearchService;
-    use crate::settings::SettingsService;
-    // Removed unused imports for services that require Tauri runtime
-    use std::sync::Arc;
-    use tempfile::NamedTempFile;
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "undo_delete"
+    )?;
     
-    // Test-specific AppState that only includes components we can test
-    struct TestAppState {
-        pub db: Arc<DbService>,
-        pub search: Arc<SearchService>, 
-        pub settings: Arc<SettingsService>,
-        pub security_validator: Arc<SecurityValidator>,
+    if token.is_empty() || token.len() > 64 {
+        return Err(AppError::Validation {
+            field: "token".to_string(),
+            message: "Invalid undo token".to_string(),
+        }.into());
     }
     
-    async fn create_test_app_state() -> TestAppState {
-        let temp_file = NamedTempFile::new().unwrap();
-        let db_path = temp_file.path().to_string_lossy().to_string();
-        
-        let db_service = Arc::new(DbService::new(&db_path).unwrap());
-        let security_validator = Arc::new(SecurityValidator::new());
-        let search_service = Arc::new(SearchService::new(db_service.clone()));
-        let settings_service = Arc::new(SettingsService::new(db_service.clone()));
-        
-        // For testing, we'll create a minimal AppState with placeholder services
-        // that don't require Tauri runtime initialization
-        TestAppState {
-            db: db_service,
-            search: search_service,
-            settings: settings_service,
-            security_validator,
-        }
-    }
+    log_security_event("NOTE_UNDO_DELETE", "IPC", true, "Undoing a note deletion");
+    
+    let db = app_state.db.clone();
+    let note = run_with_timeout("note_crud", async move { db.undo_delete(&token).await }).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Created, vec![note.id]);
+    
+    Ok(note)
+}
 
-    #[tokio::test]
-    async fn test_create_note_command() {
-        let app_state = create_test_app_state().await;
-        // For now, test the underlying database directly since State wrapping is complex
-        let result = app_state.db.create_note("Test content".to_string()).await;
-        
-        // This test would normally pass with proper security setup
-        assert!(result.is_ok() || result.is_err()); // Either outcome is fine for compilation test
-    }
+/// Restores a note from the trash
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - ID validation; notes not in the trash are reported as not found
+#[tauri::command]
+pub async fn restore_note(
+    id: i64,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<(), ApiError> {
+    let _tracker = CommandPerformanceTracker::new("restore_note");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "restore_note"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    log_security_event("NOTE_RESTORE", "IPC", true, &format!("Restoring note {} from trash", id));
+    
+    let db = app_state.db.clone();
+    run_with_timeout("note_crud", async move { db.restore_note(id).await }).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Created, vec![id]);
+    
+    Ok(())
+}
 
-    #[tokio::test]
-    async fn test_update_note_command() {
-        let app_state = create_test_app_state().await;
-        // Create a note first
-        let created = app_state.db.create_note("Initial content".to_string()).await;
-        if let Ok(note) = created {
-            let result = app_state.db.update_note_content(note.id, "Updated content".to_string()).await;
-            assert!(result.is_ok() || result.is_err()); // Either outcome is fine for compilation test
-        }
+/// Restores every note in the trash, or only those trashed since a timestamp
+/// 
+/// Nicknames taken by another note in the meantime are de-conflicted with a counter.
+/// Returns the ids of the restored notes.
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - `since` must be an RFC 3339 timestamp
+#[tauri::command]
+pub async fn restore_all_trash(
+    since: Option<String>,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<i64>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("restore_all_trash");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "restore_all_trash"
+    )?;
+    
+    log_security_event(
+        "NOTE_RESTORE_ALL",
+        "IPC",
+        true,
+        &format!("Restoring trashed notes (since: {})", since.as_deref().unwrap_or("any time"))
+    );
+    
+    let db = app_state.db.clone();
+    let restored = run_with_timeout("note_crud", async move {
+        db.restore_all_trash(since.as_deref()).await
+    }).await?;
+    if !restored.is_empty() {
+        emit_notes_changed(&app_handle, NoteChangeKind::Created, restored.clone());
     }
-}
\ No newline at end of file
+    
+    Ok(restored)
+}
+
+/// Attaches a reference to an external file to a note
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - ID validation; the path is rejected if empty, too long or containing traversal
+/// - Only the path is stored; the referenced file is never read or copied
+#[tauri::command]
+pub async fn add_attachment(
+    note_id: i64,
+    path: String,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<NoteAttachment, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("add_attachment");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "add_attachment"
+    )?;
+    
+    validate_id_secure(note_id)?;
+    
+    log_security_event("NOTE_ATTACHMENT_ADD", "IPC", true, &format!("Adding attachment to note {}", note_id));
+    
+    let db = app_state.db.clone();
+    let attachment = run_with_timeout("note_crud", async move { db.add_attachment(note_id, &path).await }).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Updated, vec![note_id]);
+    
+    Ok(attachment)
+}
+
+/// Removes an attachment reference from its note
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - ID validation for the attachment
+#[tauri::command]
+pub async fn remove_attachment(
+    id: i64,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<(), ApiError> {
+    let _tracker = CommandPerformanceTracker::new("remove_attachment");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "remove_attachment"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    log_security_event("NOTE_ATTACHMENT_REMOVE", "IPC", true, &format!("Removing attachment {}", id));
+    
+    let db = app_state.db.clone();
+    let removed = run_with_timeout("note_crud", async move { db.remove_attachment(id).await }).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Updated, vec![removed.note_id]);
+    
+    Ok(())
+}
+
+/// Lists a note's attachments in the order they were added
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - ID validation for the note
+#[tauri::command]
+pub async fn list_attachments(
+    note_id: i64,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<NoteAttachment>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("list_attachments");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "list_attachments"
+    )?;
+    
+    validate_id_secure(note_id)?;
+    
+    let db = app_state.db.clone();
+    let attachments = run_with_timeout("note_crud", async move { db.list_attachments(note_id).await }).await?;
+    
+    Ok(attachments)
+}
+
+/// Tags a note
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - ID validation; the tag must be a single word of bounded length
+#[tauri::command]
+pub async fn add_note_tag(
+    note_id: i64,
+    tag: String,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<(), ApiError> {
+    let _tracker = CommandPerformanceTracker::new("add_note_tag");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "add_note_tag"
+    )?;
+    
+    validate_id_secure(note_id)?;
+    
+    let db = app_state.db.clone();
+    run_with_timeout("note_crud", async move { db.add_note_tag(note_id, &tag).await }).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Updated, vec![note_id]);
+    
+    Ok(())
+}
+
+/// Lists the tags on a note
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - ID validation for the note
+#[tauri::command]
+pub async fn get_note_tags(
+    note_id: i64,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<String>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_note_tags");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_note_tags"
+    )?;
+    
+    validate_id_secure(note_id)?;
+    
+    let db = app_state.db.clone();
+    let tags = run_with_timeout("note_crud", async move { db.get_note_tags(note_id).await }).await?;
+    
+    Ok(tags)
+}
+
+/// Renames a tag on every note, merging it into `new` where that tag already exists
+/// 
+/// Returns the number of notes that carried `old`.
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - Both tag names are validated before anything is changed
+/// - The rename runs in a single transaction
+#[tauri::command]
+pub async fn rename_tag(
+    old: String,
+    new: String,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<usize, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("rename_tag");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "rename_tag"
+    )?;
+    
+    SecurityValidator::validate_tag(&old)?;
+    SecurityValidator::validate_tag(&new)?;
+    
+    log_security_event("TAG_RENAME", "IPC", true, "Renaming tag across all notes");
+    
+    let db = app_state.db.clone();
+    let affected = run_with_timeout("note_crud", async move { db.rename_tag(&old, &new).await }).await?;
+    if !affected.is_empty() {
+        emit_notes_changed(&app_handle, NoteChangeKind::Updated, affected.clone());
+    }
+    
+    Ok(affected.len())
+}
+
+/// Pins or unpins a note, honouring the `notes.max_pinned` cap
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - ID validation for the note being pinned
+#[tauri::command]
+pub async fn set_note_pinned(
+    id: i64,
+    pinned: bool,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<Note, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("set_note_pinned");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "set_note_pinned"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    log_security_event(
+        "NOTE_PIN",
+        "IPC",
+        true,
+        &format!("{} note {}", if pinned { "Pinning" } else { "Unpinning" }, id)
+    );
+    
+    let db = app_state.db.clone();
+    let note = run_with_timeout("note_crud", async move { db.set_note_pinned(id, pinned).await }).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Pinned, vec![id]);
+    
+    Ok(note)
+}
+
+/// Replaces text across the notes matching a full-text query
+/// 
+/// `find` is literal unless `regex` is true. With `dry_run` the per-note counts
+/// are reported without writing; otherwise every changed note is updated and
+/// re-indexed in one transaction. Encrypted, locked and concurrently edited notes
+/// are reported as skipped.
+/// 
+/// Security features:
+/// - IPC operation context validation with Search and WriteNotes capabilities
+/// - Search query validation
+/// - Find/replace length limits, regex size limit and a cap on affected notes
+/// - Replaced content goes through note content validation before anything is written
+#[tauri::command]
+pub async fn find_and_replace(
+    query: String,
+    find: String,
+    replace: String,
+    regex: Option<bool>,
+    dry_run: bool,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<NoteReplacement>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("find_and_replace");
+    
+    let context = validate_ipc_operation(
+        &app_state.security_validator,
+        "find_and_replace"
+    )?;
+    
+    validate_search_query_secure(&app_state.security_validator, &query, &context)?;
+    
+    log_security_event(
+        "NOTE_FIND_REPLACE",
+        "IPC",
+        true,
+        &format!("Find and replace over matching notes (dry run: {})", dry_run)
+    );
+    
+    let search = app_state.search.clone();
+    let regex = regex.unwrap_or(false);
+    let changes = run_with_timeout("note_crud", async move {
+        search.find_and_replace(&query, &find, &replace, regex, dry_run).await
+    }).await?;
+    
+    if !dry_run && !changes.is_empty() {
+        let updated = changes.iter().filter(|change| change.skipped.is_none()).map(|change| change.note_id).collect();
+        emit_notes_changed(&app_handle, NoteChangeKind::Updated, updated);
+    }
+    
+    Ok(changes)
+}
+
+/// Flips a note's pin state in one round trip, returning the new state
+/// 
+/// Only `is_pinned` and `updated_at` are written; the content is never sent or rewritten.
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - ID validation for the note being toggled
+#[tauri::command]
+pub async fn toggle_pin(
+    id: i64,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<bool, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("toggle_pin");
+    
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "toggle_pin"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    log_security_event("NOTE_PIN_TOGGLE", "IPC", true, &format!("Toggling pin on note {}", id));
+    
+    let db = app_state.db.clone();
+    let pinned = run_with_timeout("note_crud", async move { db.toggle_note_pinned(id).await }).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Pinned, vec![id]);
+    
+    Ok(pinned)
+}
+
+/// Pins the notes opened at least `notes.auto_pin_threshold` times
+/// 
+/// Runs the auto-pin rule now, whether or not `notes.auto_pin_enabled` turns on
+/// the background task. Honours the `notes.max_pinned` cap and never unpins notes.
+/// Returns the ids of the notes that were pinned.
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+#[tauri::command]
+pub async fn apply_auto_pin(
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<i64>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("apply_auto_pin");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "apply_auto_pin"
+    )?;
+    
+    log_security_event(
+        "NOTE_AUTO_PIN",
+        "IPC",
+        true,
+        "Applying auto-pin rule"
+    );
+    
+    let db = app_state.db.clone();
+    let pinned = run_with_timeout("note_crud", async move { db.apply_auto_pin().await }).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Pinned, pinned.clone());
+    
+    Ok(pinned)
+}
+
+/// Converts a note between PlainText and Markdown
+/// 
+/// `rewrite_content` (default false) escapes Markdown syntax when converting to
+/// Markdown and strips formatting when converting to PlainText; otherwise only the
+/// format changes. Converting to the current format is a no-op.
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - ID validation; rewritten content is validated before it is saved
+#[tauri::command]
+pub async fn convert_note_format(
+    id: i64,
+    to: NoteFormat,
+    rewrite_content: Option<bool>,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<Note, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("convert_note_format");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "convert_note_format"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    log_security_event("NOTE_FORMAT_CONVERT", "IPC", true, &format!("Converting note {} to {}", id, to));
+    
+    let note = convert_format(app_state.db.clone(), id, to, rewrite_content.unwrap_or(false)).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Updated, vec![id]);
+    
+    Ok(note)
+}
+
+/// Copies a note to the system clipboard
+/// 
+/// `format` selects `raw`, `markdown` or `rendered_text`; the latter strips
+/// Markdown formatting server-side so the clipboard receives plain text.
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - ID validation; a missing note is reported as not found
+#[tauri::command]
+pub async fn copy_note_to_clipboard(
+    id: i64,
+    format: ClipboardFormat,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<(), ApiError> {
+    let _tracker = CommandPerformanceTracker::new("copy_note_to_clipboard");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "copy_note_to_clipboard"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    log_security_event(
+        "NOTE_CLIPBOARD_COPY",
+        "IPC",
+        true,
+        &format!("Copying note {} to clipboard as {:?}", id, format)
+    );
+    
+    copy_note(app_state.db.clone(), &app_handle, id, format).await?;
+    
+    Ok(())
+}
+
+/// Diffs two versions of a note line by line
+/// 
+/// Hunks carry context/added/removed markers with old and new line numbers.
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - ID validation; both versions must exist for the note
+#[tauri::command]
+pub async fn diff_note_versions(
+    id: i64,
+    from_version: i64,
+    to_version: i64,
+    app_state: State<'_, AppState>,
+) -> Result<NoteDiff, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("diff_note_versions");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "diff_note_versions"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    let db = app_state.db.clone();
+    let diff = run_with_timeout("note_crud", diff_versions(db, id, from_version, to_version)).await?;
+    
+    Ok(diff)
+}
+
+/// Returns the most frequent words of a note with their counts
+/// 
+/// Words are lowercased, `search.stopwords` and bare numbers are left out, and
+/// Markdown notes are counted from their plain text. Read-only.
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - ID validation and `top_n` capped at `MAX_WORD_FREQUENCY_TERMS`
+#[tauri::command]
+pub async fn note_word_frequency(
+    id: i64,
+    top_n: usize,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<TermCount>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("note_word_frequency");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "note_word_frequency"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    let search = app_state.search.clone();
+    let terms = run_with_timeout("note_crud", async move { search.note_word_frequency(id, top_n).await }).await?;
+    
+    Ok(terms)
+}
+
+/// Resolves the `[[nickname]]` links in a note to the notes they name
+/// 
+/// Each target is reported as resolved (with its note id), missing, ambiguous
+/// (with the candidate ids) or invalid; unresolvable links are not an error.
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - ID validation; link targets are validated as nicknames
+#[tauri::command]
+pub async fn resolve_links(
+    id: i64,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<NoteLink>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("resolve_links");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "resolve_links"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    let db = app_state.db.clone();
+    let links = run_with_timeout("note_crud", async move { db.resolve_links(id).await }).await?;
+    
+    Ok(links)
+}
+
+/// Returns the notes whose `[[nickname]]` links name this note
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - ID validation for the linked note
+#[tauri::command]
+pub async fn get_backlinks(
+    id: i64,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<Note>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_backlinks");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_backlinks"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    let db = app_state.db.clone();
+    let notes = run_with_timeout("note_crud", async move { db.get_backlinks(id).await }).await?;
+    
+    Ok(notes)
+}
+
+/// Archives or unarchives a note
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - ID validation for the note being archived
+#[tauri::command]
+pub async fn set_note_archived(
+    id: i64,
+    archived: bool,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<(), ApiError> {
+    let _tracker = CommandPerformanceTracker::new("set_note_archived");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "set_note_archived"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    log_security_event(
+        "NOTE_ARCHIVE",
+        "IPC",
+        true,
+        &format!("{} note {}", if archived { "Archiving" } else { "Unarchiving" }, id)
+    );
+    
+    let db = app_state.db.clone();
+    run_with_timeout("note_crud", async move { db.set_note_archived(id, archived).await }).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Archived, vec![id]);
+    
+    Ok(())
+}
+
+/// Locks or unlocks a note against edits
+/// 
+/// Locked notes reject content, nickname and format changes. Quick capture still
+/// appends to a locked daily note unless `notes.capture_appends_to_locked` is off.
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - ID validation for the note being locked
+#[tauri::command]
+pub async fn set_note_locked(
+    id: i64,
+    locked: bool,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<(), ApiError> {
+    let _tracker = CommandPerformanceTracker::new("set_note_locked");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "set_note_locked"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    log_security_event(
+        "NOTE_LOCK",
+        "IPC",
+        true,
+        &format!("{} note {}", if locked { "Locking" } else { "Unlocking" }, id)
+    );
+    
+    let db = app_state.db.clone();
+    run_with_timeout("note_crud", async move { db.set_note_locked(id, locked).await }).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Locked, vec![id]);
+    
+    Ok(())
+}
+
+/// Sets the manual (drag-and-drop) order of notes
+/// 
+/// Listed notes take over the positions they held among themselves, so a partial
+/// list leaves every other note where it was. List with the `manual` sort.
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - ID validation for every listed note
+#[tauri::command]
+pub async fn reorder_notes(
+    ordered_ids: Vec<i64>,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<(), ApiError> {
+    let _tracker = CommandPerformanceTracker::new("reorder_notes");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "reorder_notes"
+    )?;
+    
+    for id in &ordered_ids {
+        validate_id_secure(*id)?;
+    }
+    
+    let db = app_state.db.clone();
+    let ids = ordered_ids.clone();
+    run_with_timeout("note_crud", async move { db.reorder_notes(&ids).await }).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Reordered, ordered_ids);
+    
+    Ok(())
+}
+
+/// Marks a note encrypted or plaintext
+/// 
+/// Encrypted notes are left out of search and read as a placeholder in listings;
+/// `get_note` decrypts them while encryption is unlocked.
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - ID validation for the note being converted
+/// - Requires encryption to be unlocked with `unlock_encryption`
+#[tauri::command]
+pub async fn set_note_encrypted(
+    id: i64,
+    encrypted: bool,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<Note, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("set_note_encrypted");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "set_note_encrypted"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    log_security_event(
+        "NOTE_ENCRYPTION",
+        "IPC",
+        true,
+        &format!("{} note {}", if encrypted { "Encrypting" } else { "Decrypting" }, id)
+    );
+    
+    let db = app_state.db.clone();
+    let note = run_with_timeout("note_crud", async move { db.set_note_encrypted(id, encrypted).await }).await?;
+    emit_notes_changed(&app_handle, NoteChangeKind::Updated, vec![id]);
+    
+    Ok(note)
+}
+
+/// Unlocks encrypted notes for this session; the first unlock sets the passphrase
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - The passphrase is never logged; the key is kept in memory only
+/// - Failed unlocks are audit-logged
+#[tauri::command]
+pub async fn unlock_encryption(
+    passphrase: String,
+    app_state: State<'_, AppState>,
+) -> Result<(), ApiError> {
+    let _tracker = CommandPerformanceTracker::new("unlock_encryption");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "unlock_encryption"
+    )?;
+    
+    let db = app_state.db.clone();
+    let result = run_with_timeout("note_crud", async move { db.unlock_encryption(&passphrase).await }).await;
+    
+    log_security_event(
+        "ENCRYPTION_UNLOCK",
+        "IPC",
+        result.is_ok(),
+        if result.is_ok() { "Encrypted notes unlocked" } else { "Unlock of encrypted notes failed" }
+    );
+    
+    Ok(result?)
+}
+
+/// Locks encrypted notes again, forgetting the session's key
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+#[tauri::command]
+pub async fn lock_encryption(
+    app_state: State<'_, AppState>,
+) -> Result<(), ApiError> {
+    let _tracker = CommandPerformanceTracker::new("lock_encryption");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "lock_encryption"
+    )?;
+    
+    app_state.db.lock_encryption();
+    log_security_event("ENCRYPTION_LOCK", "IPC", true, "Encrypted notes locked");
+    
+    Ok(())
+}
+
+/// Returns note totals by state (total, pinned, favorite, archived, trashed)
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - Read-only; computed by a single aggregate query without loading notes
+#[tauri::command]
+pub async fn get_note_counts(
+    app_state: State<'_, AppState>
+) -> Result<NoteCounts, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_note_counts");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_note_counts"
+    )?;
+    
+    let db = app_state.db.clone();
+    let counts = run_with_timeout("note_list", async move { db.get_note_counts().await }).await?;
+    
+    Ok(counts)
+}
+
+/// Exports the notes matching a search query, one file per note
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes and Search capabilities
+/// - Search query validation; an empty query is rejected rather than exporting everything
+/// - Destination must be an existing directory; each file path is validated to stay
+///   inside it and to use an allowed export extension
+/// - Notes failing content validation are skipped and listed in the manifest
+/// 
+/// Passing an `operation_id` lets the export be stopped with `cancel_operation`;
+/// it then fails with `CANCELLED`, keeping the files written so far.
+/// 
+/// JSON exports can be limited to `fields` (any of id, nickname, content, tags,
+/// created_at, updated_at, pinned, format); other keys are left out entirely.
+#[tauri::command]
+pub async fn export_query_results(
+    query: String,
+    dir: String,
+    format: ExportFormat,
+    fields: Option<Vec<String>>,
+    operation_id: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<ExportManifest, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("export_query_results");
+    
+    // Validate IPC operation with required capabilities
+    let context = validate_ipc_operation_correlated(
+        &app_state.security_validator,
+        "export_query_results",
+        operation_id.as_deref()
+    )?;
+    
+    validate_search_query_secure(&app_state.security_validator, &query, &context)?;
+    if query.trim().is_empty() {
+        return Err(AppError::Validation {
+            field: "query".to_string(),
+            message: "Export query cannot be empty".to_string(),
+        }.into());
+    }
+    let fields = match &fields {
+        Some(_) if format != ExportFormat::Json => {
+            return Err(AppError::Validation {
+                field: "fields".to_string(),
+                message: "Export fields can only be selected for JSON exports".to_string(),
+            }.into());
+        }
+        Some(names) => Some(export::parse_export_fields(names)?),
+        None => None,
+    };
+    let directory = export::validate_export_directory(&dir)?;
+    let allowed_extensions = app_state.settings.allowed_export_extensions().await?;
+    
+    let notes = app_state.search.search_notes(&query).await?;
+    let mut tags = HashMap::new();
+    if fields.as_ref().is_some_and(|fields| fields.contains(&export::ExportField::Tags)) {
+        for note in &notes {
+            tags.insert(note.id, app_state.db.get_note_tags(note.id).await?);
+        }
+    }
+    
+    log_security_event(
+        "NOTE_EXPORT_QUERY",
+        "IPC",
+        true,
+        &format!("Exporting {} notes matching search query as {:?}", notes.len(), format)
+    );
+    
+    let mut manifest = run_cancellable_in(&context, "note_export", move |cancellation| async move {
+        match fields {
+            Some(fields) => export::export_notes_json_fields(&notes, &tags, &fields, &directory, &allowed_extensions, Some(&cancellation)),
+            None => export::export_notes_cancellable(&notes, &directory, format, &allowed_extensions, Some(&cancellation)),
+        }
+    }).await?;
+    manifest.query = Some(query);
+    
+    Ok(manifest)
+}
+
+/// Previews what exporting a note would write, without touching the filesystem
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability only;
+///   nothing is written, so FileExport is not required
+/// - ID validation
+/// - Note content validation, as an export would apply
+#[tauri::command]
+pub async fn preview_export(
+    id: i64,
+    format: ExportFormat,
+    app_state: State<'_, AppState>,
+) -> Result<ExportPreview, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("preview_export");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "preview_export"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    let db = app_state.db.clone();
+    let note = run_with_timeout("note_crud", async move { db.get_note(id).await }).await?
+        .ok_or(AppError::NotFound { id })?;
+    
+    Ok(export::preview_export(&note, format)?)
+}
+
+/// Exports a note as a standalone HTML file with inlined styles
+/// 
+/// Markdown notes are rendered to HTML; plain text notes are escaped inside a
+/// `<pre>` block. Returns the path that was written.
+/// 
+/// Security features:
+/// - IPC operation context validation with FileExport and ReadNotes capabilities
+/// - ID validation and note content validation before rendering
+/// - Destination directory must exist; the file must use the `.html` extension
+///   and be allowed by the export extension list
+/// - Rendered output is sanitized according to `security.sanitize_mode`;
+///   active content is audit-logged in every mode
+#[tauri::command]
+pub async fn export_note_html(
+    id: i64,
+    path: String,
+    app_state: State<'_, AppState>,
+) -> Result<String, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("export_note_html");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "export_note_html"
+    )?;
+    
+    validate_id_secure(id)?;
+    let allowed_extensions = app_state.settings.allowed_export_extensions().await?;
+    let sanitize_mode = app_state.settings.sanitize_mode().await?;
+    
+    let db = app_state.db.clone();
+    let note = run_with_timeout("note_crud", async move { db.get_note(id).await }).await?
+        .ok_or(AppError::NotFound { id })?;
+    
+    log_security_event(
+        "NOTE_EXPORT_HTML",
+        "IPC",
+        true,
+        &format!("Exporting note {} as HTML", id)
+    );
+    
+    let (written, flagged) = export::export_note_html_with_mode(&note, &path, &allowed_extensions, sanitize_mode)?;
+    if flagged {
+        log_security_event(
+            "ACTIVE_CONTENT_IN_EXPORT",
+            "IPC",
+            true,
+            &format!("Note {} exported with active content ({:?} mode)", id, sanitize_mode)
+        );
+    }
+    
+    Ok(written.to_string_lossy().to_string())
+}
+
+/// Creates a note from the contents of a local file
+/// 
+/// Importing is opt-in: `import.base_directory` must name the directory files may
+/// be read from. `path` may be relative to it, absolute, or a `file://` URL.
+/// 
+/// `created_at` and `updated_at` (RFC 3339) preserve the original note's timestamps;
+/// when only one is given it is used for both, and without either the note is dated now.
+/// 
+/// `conflict_strategy` (default `duplicate`) decides what happens when an existing
+/// note matches the file, by content hash or by nickname per `match_key`.
+/// `newer_wins` compares `updated_at`, so a file imported without timestamps
+/// always overwrites. The summary counts the file's resolution.
+/// 
+/// Security features:
+/// - IPC operation context validation with FileExport and WriteNotes capabilities
+/// - Extension allow-list shared with exports, traversal and size checks
+/// - Symlinks are resolved and must stay inside the import directory
+/// - Note content validation before the note is created
+#[tauri::command]
+pub async fn create_note_from_file(
+    path: String,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+    conflict_strategy: Option<ConflictStrategy>,
+    match_key: Option<ImportMatchKey>,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<ImportSummary, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("create_note_from_file");
+    
+    // Validate IPC operation with required capabilities
+    let context = validate_ipc_operation(
+        &app_state.security_validator,
+        "create_note_from_file"
+    )?;
+    
+    let base_dir = app_state.settings.get_setting(IMPORT_BASE_DIR_SETTING_KEY).await?
+        .filter(|dir| !dir.trim().is_empty())
+        .ok_or_else(|| AppError::Validation {
+            field: IMPORT_BASE_DIR_SETTING_KEY.to_string(),
+            message: "Importing from files is disabled; set an import directory first".to_string(),
+        })?;
+    let allowed_extensions = app_state.settings.allowed_export_extensions().await?;
+    let imported = import::read_import_file(&path, std::path::Path::new(&base_dir), &allowed_extensions)?;
+    
+    // Validate note content with security context
+    validate_note_content_secure(&app_state.security_validator, &imported.content, &context)?;
+    
+    log_security_event(
+        "NOTE_CREATE_FROM_FILE",
+        "IPC",
+        true,
+        &format!("Creating note from a {} byte file", imported.content.len())
+    );
+    
+    let db = app_state.db.clone();
+    let timestamps = match (created_at, updated_at) {
+        (Some(created_at), Some(updated_at)) => Some((created_at, updated_at)),
+        (Some(timestamp), None) | (None, Some(timestamp)) => Some((timestamp.clone(), timestamp)),
+        (None, None) => None,
+    };
+    let strategy = conflict_strategy.unwrap_or_default();
+    let match_key = match_key.unwrap_or_default();
+    let summary = run_with_timeout("note_crud", async move {
+        let matcher = import::ImportMatcher::load(&db, match_key).await?;
+        let existing = matcher.find(&imported.content, imported.nickname.as_deref()).cloned();
+        let incoming_updated_at = timestamps.as_ref()
+            .map(|(_, updated_at)| updated_at.clone())
+            .unwrap_or_else(crate::database::current_timestamp);
+        let resolution = import::resolve_conflict(strategy, existing.as_ref(), &incoming_updated_at)?;
+        
+        let note = match (resolution, existing) {
+            (ImportResolution::Skip, _) => None,
+            (ImportResolution::Overwrite, Some(existing)) => {
+                let incoming = Note { content: imported.content, nickname: imported.nickname, ..existing.clone() };
+                Some(import::overwrite_note(&db, &existing, &incoming).await?)
+            }
+            _ => Some(match timestamps {
+                Some((created_at, updated_at)) => {
+                    db.create_note_with_timestamps(imported.content, &created_at, &updated_at, imported.nickname).await?
+                }
+                None => db.create_note_with_nickname(imported.content, imported.nickname, false).await?,
+            }),
+        };
+        let mut summary = ImportSummary::default();
+        summary.record(resolution, note);
+        Ok::<_, AppError>(summary)
+    }).await?;
+    
+    if let Some(note) = summary.notes.first() {
+        let kind = if summary.overwritten > 0 { NoteChangeKind::Updated } else { NoteChangeKind::Created };
+        emit_notes_changed(&app_handle, kind, vec![note.id]);
+    }
+    
+    Ok(summary)
+}
+
+/// Stages a draft of a note's content for backend-managed auto-save
+/// 
+/// Staged drafts are flushed to the note periodically (see `editor.draft_flush_interval`)
+/// and survive a crash, to be offered for recovery via `get_pending_drafts`.
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - Note content validation, repeated before the draft is flushed
+/// - ID validation for the note being drafted
+#[tauri::command]
+pub async fn stage_draft(
+    id: i64,
+    content: String,
+    app_state: State<'_, AppState>,
+) -> Result<NoteDraft, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("stage_draft");
+    
+    // Validate IPC operation with required capabilities
+    let context = validate_ipc_operation(
+        &app_state.security_validator,
+        "stage_draft"
+    )?;
+    
+    // Validate note ID and draft content
+    validate_id_secure(id)?;
+    validate_note_content_secure(&app_state.security_validator, &content, &context)?;
+    
+    log_security_event(
+        "NOTE_DRAFT_STAGE",
+        "IPC",
+        true,
+        &format!("Staging draft for note {} with {} characters", id, content.len())
+    );
+    
+    let db = app_state.db.clone();
+    let draft = run_with_timeout("note_crud", async move { db.stage_draft(id, content).await }).await?;
+    
+    Ok(draft)
+}
+
+/// Lists staged drafts that have not been flushed, including those left by a crash
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+#[tauri::command]
+pub async fn get_pending_drafts(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<NoteDraft>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_pending_drafts");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_pending_drafts"
+    )?;
+    
+    log_security_event(
+        "NOTE_DRAFTS_LIST",
+        "IPC",
+        true,
+        "Retrieving pending drafts"
+    );
+    
+    let db = app_state.db.clone();
+    let drafts = run_with_timeout("note_list", async move { db.get_pending_drafts().await }).await?;
+    
+    Ok(drafts)
+}
+
+/// Discards a staged draft, e.g. after the user declines recovery
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - ID validation for the drafted note
+#[tauri::command]
+pub async fn discard_draft(
+    id: i64,
+    app_state: State<'_, AppState>,
+) -> Result<(), ApiError> {
+    let _tracker = CommandPerformanceTracker::new("discard_draft");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "discard_draft"
+    )?;
+    
+    validate_id_secure(id)?;
+    
+    log_security_event(
+        "NOTE_DRAFT_DISCARD",
+        "IPC",
+        true,
+        &format!("Discarding draft for note {}", id)
+    );
+    
+    let db = app_state.db.clone();
+    run_with_timeout("note_crud", async move { db.discard_draft(id).await }).await?;
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    
+    use crate::database::DbService;
+    use crate::validation::SecurityValidator;
+    use crate::search::SearchService;
+    use crate::settings::SettingsService;
+    // Removed unused imports for services that require Tauri runtime
+    use std::sync::Arc;
+    use tempfile::NamedTempFile;
+    
+    // Test-specific AppState that only includes components we can test
+    struct TestAppState {
+        pub db: Arc<DbService>,
+        pub search: Arc<SearchService>, 
+        pub settings: Arc<SettingsService>,
+        pub security_validator: Arc<SecurityValidator>,
+    }
+    
+    async fn create_test_app_state() -> TestAppState {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = temp_file.path().to_string_lossy().to_string();
+        
+        let db_service = Arc::new(DbService::new(&db_path).unwrap());
+        let security_validator = Arc::new(SecurityValidator::new());
+        let search_service = Arc::new(SearchService::new(db_service.clone()));
+        let settings_service = Arc::new(SettingsService::new(db_service.clone()));
+        
+        // For testing, we'll create a minimal AppState with placeholder services
+        // that don't require Tauri runtime initialization
+        TestAppState {
+            db: db_service,
+            search: search_service,
+            settings: settings_service,
+            security_validator,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_note_command() {
+        let app_state = create_test_app_state().await;
+        // For now, test the underlying database directly since State wrapping is complex
+        let result = app_state.db.create_note("Test content".to_string()).await;
+        
+        // This test would normally pass with proper security setup
+        assert!(result.is_ok() || result.is_err()); // Either outcome is fine for compilation test
+    }
+
+    #[tokio::test]
+    async fn test_update_note_command() {
+        let app_state = create_test_app_state().await;
+        // Create a note first
+        let created = app_state.db.create_note("Initial content".to_string()).await;
+        if let Ok(note) = created {
+            let result = app_state.db.update_note_content(note.id, "Updated content".to_string()).await;
+            assert!(result.is_ok() || result.is_err()); // Either outcome is fine for compilation test
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_emits_single_notes_changed_event() {
+        use super::{update_note_and_notify, NOTES_CHANGED_EVENT};
+        use crate::testing::mocks::MockEventEmitter;
+        
+        let app_state = create_test_app_state().await;
+        let note = app_state.db.create_note("Initial content".to_string()).await.unwrap();
+        
+        let emitter = MockEventEmitter::new();
+        let context = crate::validation::OperationContext::new_ipc(vec![crate::validation::OperationCapability::WriteNotes]);
+        let updated = update_note_and_notify(app_state.db.clone(), &emitter, &context, note.id, "Updated content".to_string())
+            .await
+            .unwrap();
+        assert_eq!(updated.content, "Updated content");
+        
+        let events = emitter.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, NOTES_CHANGED_EVENT);
+        assert_eq!(events[0].1["kind"], "updated");
+        assert_eq!(events[0].1["ids"], serde_json::json!([note.id]));
+        
+        // A failed update emits nothing
+        emitter.clear();
+        assert!(update_note_and_notify(app_state.db.clone(), &emitter, &context, 9999, "Missing".to_string()).await.is_err());
+        assert!(emitter.events().is_empty());
+    }
+
+    #[test]
+    fn test_render_for_clipboard_formats() {
+        use super::{render_for_clipboard, ClipboardFormat};
+        use crate::models::{Note, NoteFormat};
+        
+        let mut note = Note {
+            id: 1,
+            content: "# Title with **bold** text".to_string(),
+            format: NoteFormat::Markdown,
+            nickname: None,
+            path: "/".to_string(),
+            is_favorite: false,
+            is_encrypted: false,
+            external_id: String::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        assert_eq!(render_for_clipboard(&note, ClipboardFormat::Raw), note.content);
+        assert_eq!(render_for_clipboard(&note, ClipboardFormat::Markdown), note.content);
+        assert_eq!(render_for_clipboard(&note, ClipboardFormat::RenderedText), "Title with bold text");
+        
+        // Plain text notes are never reinterpreted as Markdown
+        note.format = NoteFormat::PlainText;
+        assert_eq!(render_for_clipboard(&note, ClipboardFormat::RenderedText), note.content);
+    }
+
+    #[tokio::test]
+    async fn test_soft_char_limit_flags_without_blocking_save() {
+        use super::note_details;
+        use crate::database::SOFT_CHAR_LIMIT_SETTING_KEY;
+        
+        let app_state = create_test_app_state().await;
+        let note = app_state.db.create_note("short".to_string()).await.unwrap();
+        
+        // No limit configured
+        assert!(!note_details(app_state.db.clone(), note.id).await.unwrap().unwrap().over_soft_limit);
+        
+        app_state.db.set_setting(SOFT_CHAR_LIMIT_SETTING_KEY, "10").await.unwrap();
+        assert!(!note_details(app_state.db.clone(), note.id).await.unwrap().unwrap().over_soft_limit);
+        
+        // Content above the limit still saves and is only flagged
+        let long_content = "this note is well over ten characters".to_string();
+        app_state.db.update_note_content(note.id, long_content.clone()).await.unwrap();
+        let details = note_details(app_state.db.clone(), note.id).await.unwrap().unwrap();
+        assert!(details.over_soft_limit);
+        assert_eq!(details.note.content, long_content);
+        
+        // Limits count characters, not bytes
+        app_state.db.update_note_content(note.id, "ééééééééé".to_string()).await.unwrap();
+        assert!(!note_details(app_state.db.clone(), note.id).await.unwrap().unwrap().over_soft_limit);
+        
+        assert!(note_details(app_state.db.clone(), 9999).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_convert_note_format_both_directions() {
+        use super::convert_format;
+        use crate::error::AppError;
+        use crate::models::NoteFormat;
+        
+        let app_state = create_test_app_state().await;
+        let note = app_state.db.create_note("Use *stars* and snake_case".to_string()).await.unwrap();
+        assert_eq!(note.format, NoteFormat::PlainText);
+        
+        // PlainText -> Markdown escapes syntax so the text renders unchanged
+        let markdown = convert_format(app_state.db.clone(), note.id, NoteFormat::Markdown, true).await.unwrap();
+        assert_eq!(markdown.format, NoteFormat::Markdown);
+        assert_eq!(markdown.content, "Use \\*stars\\* and snake\\_case");
+        
+        // Markdown -> PlainText strips formatting and keeps the note searchable
+        app_state.db.update_note_content(note.id, "# Heading with **bold** words".to_string()).await.unwrap();
+        let plain = convert_format(app_state.db.clone(), note.id, NoteFormat::PlainText, true).await.unwrap();
+        assert_eq!(plain.format, NoteFormat::PlainText);
+        assert_eq!(plain.content, "Heading with bold words");
+        let stored = app_state.db.get_note(note.id).await.unwrap().unwrap();
+        assert_eq!(stored, plain);
+        assert_eq!(app_state.db.search_notes_paginated("heading", 0, 10).await.unwrap().1, 1);
+        
+        // Without rewriting only the format changes
+        let flagged = convert_format(app_state.db.clone(), note.id, NoteFormat::Markdown, false).await.unwrap();
+        assert_eq!(flagged.format, NoteFormat::Markdown);
+        assert_eq!(flagged.content, "Heading with bold words");
+        
+        assert!(matches!(
+            convert_format(app_state.db.clone(), 9999, NoteFormat::Markdown, true).await,
+            Err(AppError::NotFound { id: 9999 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_copy_note_writes_clipboard_and_rejects_missing() {
+        use super::{copy_note, ClipboardFormat};
+        use crate::error::AppError;
+        use crate::traits::clipboard::ClipboardWriter;
+        use std::sync::Mutex;
+        
+        #[derive(Default)]
+        struct RecordingClipboard(Mutex<Vec<String>>);
+        
+        impl ClipboardWriter for RecordingClipboard {
+            fn write_text(&self, text: &str) -> Result<(), AppError> {
+                self.0.lock().unwrap().push(text.to_string());
+                Ok(())
+            }
+        }
+        
+        let app_state = create_test_app_state().await;
+        let note = app_state.db.create_note("Copy me".to_string()).await.unwrap();
+        let clipboard = RecordingClipboard::default();
+        
+        let copied = copy_note(app_state.db.clone(), &clipboard, note.id, ClipboardFormat::Raw).await.unwrap();
+        assert_eq!(copied, "Copy me");
+        assert_eq!(*clipboard.0.lock().unwrap(), vec!["Copy me".to_string()]);
+        
+        let missing = copy_note(app_state.db.clone(), &clipboard, 9999, ClipboardFormat::Raw).await;
+        assert!(matches!(missing, Err(AppError::NotFound { id: 9999 })));
+        assert_eq!(clipboard.0.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_diff_note_versions() {
+        use super::diff_versions;
+        use crate::diff::DiffLineKind;
+        use crate::error::AppError;
+        
+        let app_state = create_test_app_state().await;
+        let note = app_state.db.create_note("First draft".to_string()).await.unwrap();
+        app_state.db.update_note_content(note.id, "Second draft".to_string()).await.unwrap();
+        
+        let versions = app_state.db.get_note_versions(note.id).await.unwrap();
+        assert_eq!(versions.iter().map(|v| v.version).collect::<Vec<_>>(), vec![1, 2]);
+        
+        let diff = diff_versions(app_state.db.clone(), note.id, 1, 2).await.unwrap();
+        assert_eq!(diff.hunks.len(), 1);
+        let lines: Vec<_> = diff.hunks[0].lines.iter().map(|line| (line.kind, line.text.as_str())).collect();
+        assert_eq!(lines, vec![(DiffLineKind::Removed, "First draft"), (DiffLineKind::Added, "Second draft")]);
+        
+        // Same version on both sides has nothing to show
+        assert!(diff_versions(app_state.db.clone(), note.id, 2, 2).await.unwrap().hunks.is_empty());
+        
+        assert!(matches!(
+            diff_versions(app_state.db.clone(), note.id, 1, 3).await,
+            Err(AppError::Validation { ref field, .. }) if field == "to_version"
+        ));
+        assert!(matches!(diff_versions(app_state.db.clone(), 9999, 1, 2).await, Err(AppError::NotFound { id: 9999 })));
+    }
+}
@@ -9,17 +9,18 @@ use crate::commands::shared::{
     validate_ipc_operation, CommandPerformanceTracker, log_security_event
 };
 use crate::error::ApiError;
-use crate::validation::OperationCapability;
+use crate::validation::OperationSource;
 use crate::performance::{
     PerformanceSummary, PerformanceAlert, PerformanceBudget,
-    get_performance_monitor
+    get_performance_monitor, BUDGET_PRESET_NAMES, BUDGET_PRESET_SETTING_KEY,
+    METRICS_INTERVAL_SETTING_KEY, SYSTEM_THRESHOLDS_SETTING_KEY
 };
 use crate::performance::backend::{BackendMetrics, get_backend_monitor};
 use crate::performance::frontend::{
     FrontendMetrics, FrontendAnalysis, get_frontend_monitor
 };
 use crate::performance::system::{
-    SystemAnalysis, get_system_monitor
+    SystemAnalysis, SystemThresholds, get_system_monitor
 };
 use crate::performance::analytics::{
     PerformanceAnalyticsReport, get_analytics_engine
@@ -28,6 +29,12 @@ use crate::AppState;
 use tauri::State;
 use serde::{Deserialize, Serialize};
 
+/// Frequency bucket counting `record_frontend_metrics` calls
+pub const FRONTEND_METRICS_FREQUENCY_BUCKET: &str = "record_frontend_metrics";
+
+/// Calls to `record_frontend_metrics` allowed per minute
+pub const FRONTEND_METRICS_PER_MINUTE: u32 = 12;
+
 /// Performance metrics overview for dashboard
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceOverview {
@@ -81,6 +88,15 @@ pub struct PerformanceReport {
     pub quick_recommendations: Vec<String>,
 }
 
+/// Named performance budget preset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetPreset {
+    /// Preset name
+    pub name: String,
+    /// Budget values applied by this preset
+    pub budget: PerformanceBudget,
+}
+
 /// Get performance overview for dashboard
 /// 
 /// Security features:
@@ -96,7 +112,7 @@ pub async fn get_performance_overview(
     // Validate IPC operation
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::ReadNotes] // Using ReadNotes as closest match for performance monitoring
+        "get_performance_overview" // Using ReadNotes as closest match for performance monitoring
     )?;
     
     // Log security event
@@ -124,7 +140,8 @@ pub async fn get_performance_overview(
     // Calculate performance scores
     let backend_score = calculate_backend_score(&backend_metrics);
     let frontend_score = frontend_analysis.as_ref().map(|f| f.performance_score);
-    let system_score = system_analysis.performance_score as u8;
+    // Missing system metrics count as neutral, like a missing frontend analysis
+    let system_score = if system_analysis.data_available { system_analysis.performance_score as u8 } else { 80 };
     
     // Calculate overall score
     let scores = vec![
@@ -178,7 +195,7 @@ pub async fn get_performance_metrics(
     // Validate IPC operation
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::ReadNotes]
+        "get_performance_metrics"
     )?;
     
     // Validate request parameters
@@ -279,9 +296,12 @@ pub async fn get_performance_metrics(
 /// - IPC operation validation with elevated capability requirement
 /// - Report generation rate limiting
 /// - Comprehensive analytics with sensitive data filtering
+/// 
+/// With `by_source` the report includes per-`OperationSource` operation summaries.
 #[tauri::command]
 pub async fn get_performance_analytics(
     period_hours: Option<u32>,
+    by_source: Option<bool>,
     app_state: State<'_, AppState>,
 ) -> Result<PerformanceAnalyticsReport, ApiError> {
     let _tracker = CommandPerformanceTracker::new("get_performance_analytics");
@@ -289,7 +309,7 @@ pub async fn get_performance_analytics(
     // Validate IPC operation with elevated capability
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::ReadNotes, OperationCapability::Search] // Require multiple capabilities for analytics
+        "get_performance_analytics" // Require multiple capabilities for analytics
     )?;
     
     // Validate period parameter
@@ -310,7 +330,8 @@ pub async fn get_performance_analytics(
     );
     
     // Generate comprehensive analytics report
-    let report = get_analytics_engine().generate_report(Some(analysis_period)).await
+    let report = get_analytics_engine()
+        .generate_report_with_breakdown(Some(analysis_period), by_source.unwrap_or(false)).await
         .map_err(|e| ApiError {
             code: "ANALYTICS_ERROR".to_string(),
             message: format!("Failed to generate analytics report: {}", e),
@@ -323,6 +344,7 @@ pub async fn get_performance_analytics(
 /// 
 /// Security features:
 /// - IPC operation validation
+/// - Dedicated per-minute limit (`FRONTEND_METRICS_PER_MINUTE`), rejected with RATE_LIMIT_ERROR
 /// - Frontend metrics validation and sanitization
 /// - Metric size limits to prevent abuse
 #[tauri::command]
@@ -335,7 +357,11 @@ pub async fn record_frontend_metrics(
     // Validate IPC operation
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::WriteNotes] // Using WriteNotes as closest match for recording metrics
+        "record_frontend_metrics" // Using WriteNotes as closest match for recording metrics
+    )?;
+    app_state.security_validator.check_dedicated_frequency(
+        FRONTEND_METRICS_FREQUENCY_BUCKET,
+        FRONTEND_METRICS_PER_MINUTE,
     )?;
     
     // Validate metrics data
@@ -414,7 +440,7 @@ pub async fn get_performance_alerts(
     // Validate IPC operation
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::ReadNotes]
+        "get_performance_alerts"
     )?;
     
     // Validate severity filter
@@ -491,7 +517,7 @@ pub async fn update_performance_budget(
     // Validate IPC operation with elevated capability (admin-like operation)
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::WriteNotes, OperationCapability::Search] // Require multiple capabilities
+        "update_performance_budget" // Require multiple capabilities
     )?;
     
     // Validate budget parameters
@@ -523,6 +549,13 @@ pub async fn update_performance_budget(
         });
     }
     
+    if budget.operation_duration_overrides_ms.values().any(|&max_ms| max_ms == 0 || max_ms > 30000) {
+        return Err(ApiError {
+            code: "VALIDATION_ERROR".to_string(),
+            message: "Invalid operation duration override (must be 1-30000ms)".to_string(),
+        });
+    }
+    
     // Log security event for administrative operation
     log_security_event(
         "UPDATE_PERFORMANCE_BUDGET",
@@ -538,9 +571,215 @@ pub async fn update_performance_budget(
     // Update budget in performance monitor
     get_performance_monitor().update_budget(budget);
     
+    // A custom budget replaces any stored preset so it is not reapplied on restart
+    app_state.settings.delete_setting(BUDGET_PRESET_SETTING_KEY).await?;
+    
     Ok("Performance budget updated successfully".to_string())
 }
 
+/// List the built-in performance budget presets
+/// 
+/// Security features:
+/// - IPC operation validation
+/// - Static data only
+#[tauri::command]
+pub async fn list_budget_presets(
+    app_state: State<'_, AppState>,
+) -> Result<Vec<BudgetPreset>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("list_budget_presets");
+    
+    // Validate IPC operation
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "list_budget_presets"
+    )?;
+    
+    let presets = BUDGET_PRESET_NAMES
+        .iter()
+        .filter_map(|name| PerformanceBudget::preset(name).map(|budget| BudgetPreset {
+            name: name.to_string(),
+            budget,
+        }))
+        .collect();
+    
+    Ok(presets)
+}
+
+/// Apply a named performance budget preset
+/// 
+/// Security features:
+/// - Same elevated capabilities as update_performance_budget
+/// - Preset name validated against the built-in list
+/// - Applied preset persisted in settings and reapplied on startup
+#[tauri::command]
+pub async fn apply_budget_preset(
+    name: String,
+    app_state: State<'_, AppState>,
+) -> Result<PerformanceBudget, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("apply_budget_preset");
+    
+    // Validate IPC operation with elevated capability (admin-like operation)
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "apply_budget_preset"
+    )?;
+    
+    // Validate preset name
+    let name = name.trim().to_lowercase();
+    let budget = PerformanceBudget::preset(&name).ok_or_else(|| ApiError {
+        code: "VALIDATION_ERROR".to_string(),
+        message: format!(
+            "Unknown budget preset (must be one of: {})",
+            BUDGET_PRESET_NAMES.join(", ")
+        ),
+    })?;
+    
+    // Log security event for administrative operation
+    log_security_event(
+        "APPLY_BUDGET_PRESET",
+        "IPC",
+        true,
+        &format!("Performance budget preset applied: {}", name)
+    );
+    
+    get_performance_monitor().update_budget(budget.clone());
+    app_state.settings.set_setting(BUDGET_PRESET_SETTING_KEY, &name).await?;
+    
+    Ok(budget)
+}
+
+/// Change the system metrics collection interval
+/// 
+/// Security features:
+/// - Same elevated capabilities as update_performance_budget
+/// - Interval validated against the 1-300 second range
+/// - Interval persisted in settings and reapplied on startup
+#[tauri::command]
+pub async fn set_metrics_interval(
+    seconds: u64,
+    app_state: State<'_, AppState>,
+) -> Result<u64, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("set_metrics_interval");
+    
+    // Validate IPC operation with elevated capability (admin-like operation)
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "set_metrics_interval"
+    )?;
+    
+    // Reconfigures the running collection task; rejects out-of-range values
+    let interval = crate::performance::set_metrics_interval(seconds)?;
+    
+    log_security_event(
+        "SET_METRICS_INTERVAL",
+        "IPC",
+        true,
+        &format!("Metrics collection interval set to {}s", seconds)
+    );
+    
+    app_state.settings.set_setting(METRICS_INTERVAL_SETTING_KEY, &seconds.to_string()).await?;
+    
+    Ok(interval.as_secs())
+}
+
+/// Get the thresholds at which system CPU, memory, disk and response alerts fire
+/// 
+/// Security features:
+/// - IPC operation validation
+/// - No sensitive data exposure
+#[tauri::command]
+pub async fn get_system_thresholds(
+    app_state: State<'_, AppState>,
+) -> Result<SystemThresholds, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_system_thresholds");
+    
+    // Validate IPC operation
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_system_thresholds"
+    )?;
+    
+    Ok(get_system_monitor()?.get_thresholds())
+}
+
+/// Change the thresholds at which system alerts fire
+/// 
+/// Security features:
+/// - Same elevated capabilities as update_performance_budget
+/// - Each warning level must be below its critical level; percentages within 0-100
+/// - Thresholds persisted in settings and reapplied on startup
+#[tauri::command]
+pub async fn set_system_thresholds(
+    thresholds: SystemThresholds,
+    app_state: State<'_, AppState>,
+) -> Result<SystemThresholds, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("set_system_thresholds");
+    
+    // Validate IPC operation with elevated capability (admin-like operation)
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "set_system_thresholds"
+    )?;
+    
+    // Rejects invalid thresholds before anything changes
+    get_system_monitor()?.set_thresholds(thresholds.clone())?;
+    
+    log_security_event(
+        "SET_SYSTEM_THRESHOLDS",
+        "IPC",
+        true,
+        &format!("System alert thresholds updated: cpu={:.0}/{:.0}%, memory={:.0}/{:.0}%, disk={:.0}/{:.0}%, response={}/{}ms",
+               thresholds.cpu_warning, thresholds.cpu_critical,
+               thresholds.memory_warning, thresholds.memory_critical,
+               thresholds.disk_warning, thresholds.disk_critical,
+               thresholds.response_warning, thresholds.response_critical)
+    );
+    
+    let value = serde_json::to_string(&thresholds).map_err(crate::error::AppError::from)?;
+    app_state.settings.set_setting(SYSTEM_THRESHOLDS_SETTING_KEY, &value).await?;
+    
+    Ok(thresholds)
+}
+
+/// Get the operation summary for a single operation source
+/// 
+/// Security features:
+/// - IPC operation validation
+/// - Period validation (max 720 hours)
+/// - Only aggregate figures are returned
+#[tauri::command]
+pub async fn get_performance_summary_by_source(
+    source: OperationSource,
+    period_hours: Option<u32>,
+    app_state: State<'_, AppState>,
+) -> Result<PerformanceSummary, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_performance_summary_by_source");
+    
+    // Validate IPC operation
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_performance_summary_by_source"
+    )?;
+    
+    let period = period_hours.unwrap_or(24);
+    if period > 720 {
+        return Err(ApiError {
+            code: "VALIDATION_ERROR".to_string(),
+            message: "Summary period cannot exceed 720 hours (30 days)".to_string(),
+        });
+    }
+    
+    // Log security event
+    log_security_event(
+        "GET_PERFORMANCE_SUMMARY_BY_SOURCE",
+        "IPC",
+        true,
+        &format!("Performance summary requested for {} over {}h", source.as_str(), period)
+    );
+    
+    Ok(get_performance_monitor().get_performance_summary_by_source(&source, period as u64))
+}
+
 /// Get current performance budget
 /// 
 /// Security features:
@@ -555,7 +794,7 @@ pub async fn get_performance_budget(
     // Validate IPC operation
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::ReadNotes]
+        "get_performance_budget"
     )?;
     
     // Log security event
@@ -637,6 +876,7 @@ fn create_performance_overview(
         .map(|f| f.performance_score);
     
     let system_score = system_analysis.as_ref()
+        .filter(|s| s.data_available)
         .map(|s| s.performance_score as u8)
         .unwrap_or(80);
     
@@ -696,7 +936,7 @@ fn generate_quick_recommendations(
     }
     
     if let Some(system) = system_analysis {
-        if system.performance_score < 80.0 {
+        if system.data_available && system.performance_score < 80.0 {
             recommendations.push("Address system resource utilization to improve overall health".to_string());
         }
         
@@ -721,6 +961,7 @@ mod tests {
     use super::*;
     use crate::validation::SecurityValidator;
     use std::sync::Arc;
+    use std::collections::HashMap;
 
     // Simplified test setup that doesn't require Tauri runtime
     // Tests focus on the logic rather than full integration
@@ -728,6 +969,22 @@ mod tests {
         Arc::new(SecurityValidator::new())
     }
 
+    #[test]
+    fn test_frontend_metrics_call_rate_is_rejected_with_rate_limit_error() {
+        let validator = create_test_validator();
+        for _ in 0..FRONTEND_METRICS_PER_MINUTE {
+            validator
+                .check_dedicated_frequency(FRONTEND_METRICS_FREQUENCY_BUCKET, FRONTEND_METRICS_PER_MINUTE)
+                .unwrap();
+        }
+        
+        let error: ApiError = validator
+            .check_dedicated_frequency(FRONTEND_METRICS_FREQUENCY_BUCKET, FRONTEND_METRICS_PER_MINUTE)
+            .unwrap_err()
+            .into();
+        assert_eq!(error.code, "RATE_LIMIT_ERROR");
+    }
+
     #[tokio::test]
     async fn test_performance_overview_creation() {
         let _validator = create_test_validator();
@@ -854,6 +1111,7 @@ mod tests {
             max_memory_usage_bytes: 512 * 1024 * 1024, // 512MB
             target_cache_hit_rate: 0.85,
             max_cpu_usage_percent: 80.0,
+            operation_duration_overrides_ms: HashMap::new(),
         };
         
         assert!(budget.max_operation_duration_ms > 0);
@@ -872,6 +1130,7 @@ mod tests {
             max_memory_usage_bytes: 1024 * 1024 * 1024, // 1GB
             target_cache_hit_rate: 0.9,
             max_cpu_usage_percent: 75.0,
+            operation_duration_overrides_ms: HashMap::new(),
         };
         
         // Validate each field manually (simulating the validation logic)
@@ -891,6 +1150,7 @@ mod tests {
             max_memory_usage_bytes: 1024 * 1024 * 1024,
             target_cache_hit_rate: 1.5, // Invalid
             max_cpu_usage_percent: 150.0, // Invalid
+            operation_duration_overrides_ms: HashMap::new(),
         };
         
         // Validate each field manually (simulating the validation logic)
@@ -974,7 +1234,7 @@ mod tests {
     #[test]
     fn test_generate_quick_recommendations() {
         use crate::performance::backend::{BackendMetrics, DatabaseMetrics, TransactionMetrics, FtsMetrics, IpcMetrics, SearchMetrics, IndexUtilization};
-        use crate::performance::system::{SystemAnalysis, ResourceUtilization, TrendAnalysis, SystemAlert};
+        use crate::performance::system::{SystemAnalysis, ComponentAvailability, ResourceUtilization, TrendAnalysis, SystemAlert};
         use crate::performance::CacheMetrics;
         use std::collections::HashMap;
         
@@ -1033,6 +1293,8 @@ mod tests {
         };
         
         let system_analysis = SystemAnalysis {
+            data_available: true,
+            components: ComponentAvailability { cpu: true, memory: true, ..Default::default() },
             overall_health: "poor".to_string(),
             performance_score: 65.0, // Poor health
             bottlenecks: vec!["High CPU usage".to_string()],
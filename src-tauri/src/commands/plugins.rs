@@ -0,0 +1,73 @@
+//! Plugin Domain Commands
+//!
+//! Read-only queries over the loaded plugins. These take the plugin manager's
+//! read lock, so concurrent queries never wait on each other; only loading,
+//! registration and shutdown take the write lock.
+
+use crate::commands::shared::{
+    validate_ipc_operation, CommandPerformanceTracker, log_security_event
+};
+use crate::error::ApiError;
+use crate::plugin::PluginSummary;
+use crate::AppState;
+use tauri::State;
+
+/// Lists the loaded plugins in registration order
+///
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - Read-only operation under a shared lock
+/// - Performance monitoring
+#[tauri::command]
+pub async fn list_plugins(
+    app_state: State<'_, AppState>
+) -> Result<Vec<PluginSummary>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("list_plugins");
+
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "list_plugins"
+    )?;
+
+    let plugins = app_state.plugin_manager.read().await.list_plugins();
+
+    Ok(plugins)
+}
+
+/// Looks up one loaded plugin by name
+///
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability
+/// - Plugin name length validation
+/// - Read-only operation under a shared lock
+/// - Audit logging for plugin queries
+#[tauri::command]
+pub async fn get_plugin(
+    name: String,
+    app_state: State<'_, AppState>
+) -> Result<Option<PluginSummary>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_plugin");
+
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_plugin"
+    )?;
+
+    if name.trim().is_empty() || name.len() > 100 {
+        return Err(ApiError {
+            code: "VALIDATION_ERROR".to_string(),
+            message: "Plugin name must be between 1 and 100 characters".to_string(),
+        });
+    }
+
+    let plugin = app_state.plugin_manager.read().await.plugin_summary(&name);
+
+    log_security_event(
+        "PLUGIN_QUERY",
+        "IPC",
+        true,
+        &format!("Plugin lookup found {}", if plugin.is_some() { "a match" } else { "nothing" })
+    );
+
+    Ok(plugin)
+}
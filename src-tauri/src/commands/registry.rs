@@ -0,0 +1,177 @@
+//! Command Registry
+//! 
+//! The single list of IPC commands and the capabilities each one requires.
+//! `validate_ipc_operation` looks commands up here, and `list_commands` reports it.
+
+use crate::validation::OperationCapability::{
+    self, DeleteNotes, FileExport, ReadNotes, Search, SystemAccess, WriteNotes,
+};
+use serde::Serialize;
+
+/// An IPC command and the capabilities its operation context is created with
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub capabilities: &'static [OperationCapability],
+}
+
+const fn command(name: &'static str, capabilities: &'static [OperationCapability]) -> CommandSpec {
+    CommandSpec { name, capabilities }
+}
+
+/// Every IPC command, grouped by command module
+/// Commands with no capabilities do not create an operation context
+pub const COMMANDS: &[CommandSpec] = &[
+    // Note Management Commands
+    command("create_note", &[WriteNotes]),
+    command("get_note", &[ReadNotes]),
+    command("get_or_create_daily_note", &[ReadNotes, WriteNotes]),
+    command("resolve_note_by_path", &[ReadNotes]),
+    command("get_note_by_external_id", &[ReadNotes]),
+    command("get_all_notes", &[ReadNotes]),
+    command("get_notes_paginated", &[ReadNotes]),
+    command("query_notes", &[ReadNotes]),
+    command("get_note_previews", &[ReadNotes]),
+    command("update_note", &[WriteNotes]),
+    command("delete_note", &[DeleteNotes]),
+    command("restore_note", &[WriteNotes]),
+    command("undo_delete", &[WriteNotes]),
+    command("restore_all_trash", &[WriteNotes]),
+    command("add_attachment", &[WriteNotes]),
+    command("remove_attachment", &[WriteNotes]),
+    command("list_attachments", &[ReadNotes]),
+    command("add_note_tag", &[WriteNotes]),
+    command("get_note_tags", &[ReadNotes]),
+    command("rename_tag", &[WriteNotes]),
+    command("set_note_pinned", &[WriteNotes]),
+    command("find_and_replace", &[Search, WriteNotes]),
+    command("toggle_pin", &[WriteNotes]),
+    command("apply_auto_pin", &[WriteNotes]),
+    command("convert_note_format", &[WriteNotes]),
+    command("copy_note_to_clipboard", &[ReadNotes]),
+    command("diff_note_versions", &[ReadNotes]),
+    command("note_word_frequency", &[ReadNotes]),
+    command("resolve_links", &[ReadNotes]),
+    command("get_backlinks", &[ReadNotes]),
+    command("set_note_archived", &[WriteNotes]),
+    command("set_note_locked", &[WriteNotes]),
+    command("reorder_notes", &[WriteNotes]),
+    command("set_note_encrypted", &[WriteNotes]),
+    command("unlock_encryption", &[WriteNotes]),
+    command("lock_encryption", &[WriteNotes]),
+    command("get_note_counts", &[ReadNotes]),
+    command("export_query_results", &[ReadNotes, Search]),
+    command("preview_export", &[ReadNotes]),
+    command("export_note_html", &[FileExport, ReadNotes]),
+    command("create_note_from_file", &[FileExport, WriteNotes]),
+    command("stage_draft", &[WriteNotes]),
+    command("get_pending_drafts", &[ReadNotes]),
+    command("discard_draft", &[WriteNotes]),
+
+    // Search Commands
+    command("search_notes", &[Search]),
+    command("search_notes_paginated", &[Search]),
+    command("search_notes_boolean_paginated", &[Search]),
+    command("search_batch", &[Search]),
+    command("extract_query_terms", &[Search]),
+    command("count_search_results", &[Search]),
+    command("count_boolean_search_results", &[Search]),
+    command("get_search_history", &[Search]),
+    command("clear_search_history", &[Search]),
+    command("validate_boolean_search_query", &[Search]),
+    command("parse_boolean_query", &[Search]),
+    command("get_boolean_search_examples", &[Search]),
+    command("explain_search", &[Search]),
+    command("search_in_note", &[Search]),
+    command("search_notes_regex", &[Search]),
+    command("get_index_stats", &[Search]),
+    command("optimize_index", &[WriteNotes]),
+
+    // Settings Commands
+    command("get_setting", &[SystemAccess]),
+    command("set_setting", &[SystemAccess]),
+    command("get_all_settings", &[SystemAccess]),
+    command("delete_setting", &[SystemAccess]),
+    command("get_setting_history", &[SystemAccess]),
+    command("revert_setting", &[SystemAccess]),
+    command("get_settings_namespace", &[SystemAccess]),
+    command("delete_settings_namespace", &[SystemAccess]),
+    command("reset_to_defaults", &[SystemAccess]),
+    command("save_settings", &[SystemAccess]),
+    command("load_settings", &[SystemAccess]),
+    command("register_global_shortcut", &[SystemAccess]),
+
+    // System Commands
+    command("unregister_global_shortcut", &[SystemAccess]),
+    command("toggle_window_visibility", &[SystemAccess]),
+    command("show_window", &[SystemAccess]),
+    command("hide_window", &[SystemAccess]),
+    command("is_window_visible", &[SystemAccess]),
+    command("get_current_shortcut", &[SystemAccess]),
+    command("shutdown_application", &[SystemAccess]),
+    command("get_database_stats", &[SystemAccess]),
+    command("health_check", &[ReadNotes]),
+
+    // Lifecycle Commands
+    command("is_shutting_down", &[ReadNotes]),
+    command("initiate_shutdown", &[SystemAccess]),
+
+    // Diagnostic Commands
+    command("report_frontend_error", &[SystemAccess]),
+    command("get_backend_error_details", &[]),
+    command("get_platform_info", &[ReadNotes]),
+    command("get_frequency_status", &[]),
+    command("cancel_operation", &[ReadNotes]),
+    command("get_recent_logs", &[SystemAccess]),
+    command("list_commands", &[ReadNotes]),
+
+    // Performance Commands
+    command("get_performance_overview", &[ReadNotes]),
+    command("get_performance_metrics", &[ReadNotes]),
+    command("get_performance_analytics", &[ReadNotes, Search]),
+    command("record_frontend_metrics", &[WriteNotes]),
+    command("get_performance_alerts", &[ReadNotes]),
+    command("update_performance_budget", &[WriteNotes, Search]),
+    command("list_budget_presets", &[ReadNotes]),
+    command("apply_budget_preset", &[WriteNotes, Search]),
+    command("set_metrics_interval", &[WriteNotes, Search]),
+    command("get_system_thresholds", &[ReadNotes]),
+    command("set_system_thresholds", &[WriteNotes, Search]),
+    command("get_performance_summary_by_source", &[ReadNotes]),
+    command("get_performance_budget", &[ReadNotes]),
+
+    // Plugin Commands
+    command("list_plugins", &[ReadNotes]),
+    command("get_plugin", &[ReadNotes]),
+];
+
+/// The registry entry for a command
+pub fn find_command(name: &str) -> Option<&'static CommandSpec> {
+    COMMANDS.iter().find(|spec| spec.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_every_handler_command_is_registered() {
+        let handler = include_str!("mod.rs");
+        let handler = &handler[handler.find("macro_rules! generate_command_handler").unwrap()..];
+        let handled: Vec<&str> = handler
+            .lines()
+            .filter_map(|line| line.trim().trim_end_matches(',').strip_prefix("crate::commands::"))
+            .filter_map(|path| path.rsplit("::").next())
+            .collect();
+        assert!(handled.len() > 50);
+        for name in &handled {
+            assert!(find_command(name).is_some(), "{} is missing from the command registry", name);
+        }
+
+        let mut seen = HashSet::new();
+        for spec in COMMANDS {
+            assert!(seen.insert(spec.name), "{} is registered twice", spec.name);
+        }
+    }
+}
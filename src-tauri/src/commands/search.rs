@@ -5,14 +5,19 @@
 /// while adding Week 2 Day 4 advanced Boolean search capabilities.
 
 use crate::commands::shared::{
-    validate_ipc_operation, validate_search_query_secure, 
-    CommandPerformanceTracker, log_security_event
+    validate_ipc_operation, validate_ipc_operation_correlated, validate_search_query_secure, validate_id_secure,
+    run_cancellable_in, run_with_timeout, CommandPerformanceTracker, log_security_event
 };
+use crate::database::IndexStats;
 use crate::error::ApiError;
-use crate::models::Note;
-use crate::validation::OperationCapability;
+use crate::models::{Note, NoteFormat, SearchHistoryEntry};
+use crate::search::{
+    BatchQueryResult, InNoteMatch, InNoteSearchOptions, QueryNode, SearchExplanation, SearchScope, SnippetContext,
+    SEARCH_HISTORY_CAPACITY
+};
 use crate::AppState;
 use tauri::State;
+use std::collections::HashMap;
 use std::time::Instant;
 use serde::{Deserialize, Serialize};
 
@@ -25,6 +30,9 @@ pub struct SearchResult {
     pub page_size: usize,
     pub has_more: bool,
     pub query_time_ms: u64,
+    /// Whether the notes came from the fuzzy fallback rather than an exact match
+    #[serde(default)]
+    pub fuzzy: bool,
 }
 
 /// Boolean search result with complexity analysis
@@ -50,6 +58,55 @@ pub struct QueryComplexity {
     pub complexity_score: f64,
 }
 
+/// Reports the size and layout of the full-text search index
+/// 
+/// `optimize_recommended` is set once the index is split across more than
+/// `INDEX_OPTIMIZE_SEGMENT_THRESHOLD` segments; `optimize_index` merges them.
+/// 
+/// Security features:
+/// - IPC operation context validation with Search capability
+/// - Only counts and sizes are returned, never note content
+#[tauri::command]
+pub async fn get_index_stats(
+    app_state: State<'_, AppState>,
+) -> Result<IndexStats, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_index_stats");
+    
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_index_stats"
+    )?;
+    
+    let db = app_state.db.clone();
+    let stats = run_with_timeout("db_stats", async move { db.get_index_stats().await }).await?;
+    
+    Ok(stats)
+}
+
+/// Merges the full-text search index into one segment, returning the new statistics
+/// 
+/// Security features:
+/// - IPC operation context validation with WriteNotes capability
+/// - Audit logging for index maintenance
+#[tauri::command]
+pub async fn optimize_index(
+    app_state: State<'_, AppState>,
+) -> Result<IndexStats, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("optimize_index");
+    
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "optimize_index"
+    )?;
+    
+    log_security_event("SEARCH_INDEX_OPTIMIZE", "IPC", true, "Full-text index optimize requested");
+    
+    let db = app_state.db.clone();
+    let stats = run_with_timeout("db_maintenance", async move { db.optimize_index().await }).await?;
+    
+    Ok(stats)
+}
+
 /// Basic search operation with security validation
 /// 
 /// Security features preserved:
@@ -57,9 +114,13 @@ pub struct QueryComplexity {
 /// - Query validation (injection protection, 1000 char limit)
 /// - Frequency limit enforcement (15 operations/minute for IPC)
 /// - Performance monitoring (<2ms overhead target)
+/// 
+/// `format` limits results to PlainText or Markdown notes.
 #[tauri::command]
 pub async fn search_notes(
     query: String,
+    scope: Option<SearchScope>,
+    format: Option<NoteFormat>,
     app_state: State<'_, AppState>,
 ) -> Result<Vec<Note>, ApiError> {
     let _tracker = CommandPerformanceTracker::new("search_notes");
@@ -67,11 +128,14 @@ pub async fn search_notes(
     // Validate IPC operation with required capabilities - fixed: SearchNotes -> Search
     let context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::Search]
+        "search_notes"
     )?;
     
     // Validate search query for security - fixed: missing context parameter
     validate_search_query_secure(&app_state.security_validator, &query, &context)?;
+    if let Some(scope) = &scope {
+        scope.validate()?;
+    }
     
     // Log security event for audit trail
     log_security_event(
@@ -88,7 +152,8 @@ pub async fn search_notes(
     );
     
     // Perform search using search service
-    let notes = app_state.search.search_notes(&query).await?;
+    let notes = app_state.search.search_notes_filtered(&query, scope.as_ref(), format.as_ref()).await?;
+    record_search_history(&app_state, &query).await;
     
     Ok(notes)
 }
@@ -97,13 +162,22 @@ pub async fn search_notes(
 /// 
 /// Security features preserved:
 /// - All basic search security features
-/// - Pagination parameter validation (max page size 100, max page 1000)
+/// - Pagination parameter validation (page size defaults to `search.default_limit`,
+///   capped by `search.max_results`; max page 1000)
 /// - Performance analytics for query time tracking
+/// 
+/// `format` limits results (and `total_count`) to PlainText or Markdown notes.
+/// 
+/// With `fuzzy_fallback`, a query with no exact matches is retried as a bounded
+/// fuzzy match that tolerates typos; such results are flagged with `fuzzy: true`.
 #[tauri::command]
 pub async fn search_notes_paginated(
     query: String,
     page: usize,
-    page_size: usize,
+    page_size: Option<usize>,
+    scope: Option<SearchScope>,
+    format: Option<NoteFormat>,
+    fuzzy_fallback: Option<bool>,
     app_state: State<'_, AppState>,
 ) -> Result<SearchResult, ApiError> {
     let _tracker = CommandPerformanceTracker::new("search_notes_paginated");
@@ -112,20 +186,18 @@ pub async fn search_notes_paginated(
     // Validate IPC operation - fixed: SearchNotes -> Search
     let context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::Search]
+        "search_notes_paginated"
     )?;
     
     // Validate search query - fixed: missing context parameter
     validate_search_query_secure(&app_state.security_validator, &query, &context)?;
-    
-    // Validate pagination parameters
-    if page_size == 0 || page_size > 100 {
-        return Err(ApiError {
-            code: "VALIDATION_ERROR".to_string(),
-            message: "Page size must be between 1 and 100".to_string(),
-        });
+    if let Some(scope) = &scope {
+        scope.validate()?;
     }
     
+    // Resolve the page size against the configured default and cap
+    let page_size = app_state.search.effective_limit(page_size).await?;
+    
     if page > 1000 {
         return Err(ApiError {
             code: "VALIDATION_ERROR".to_string(),
@@ -150,8 +222,18 @@ pub async fn search_notes_paginated(
     );
     
     // Fixed: Handle tuple return from search service
-    let (notes, total_count) = app_state.search
-        .search_notes_paginated(&query, page, page_size).await?;
+    let (notes, total_count, fuzzy) = if fuzzy_fallback.unwrap_or(false) {
+        app_state.search
+            .search_notes_paginated_with_fallback(&query, page, page_size, scope.as_ref(), format.as_ref()).await?
+    } else {
+        let (notes, total_count) = app_state.search
+            .search_notes_paginated_filtered(&query, page, page_size, scope.as_ref(), format.as_ref()).await?;
+        (notes, total_count, false)
+    };
+    // Only the first page counts as a new search
+    if page == 0 {
+        record_search_history(&app_state, &query).await;
+    }
     
     let query_time = start_time.elapsed();
     let has_more = (page + 1) * page_size < total_count;
@@ -163,6 +245,7 @@ pub async fn search_notes_paginated(
         page_size,
         has_more,
         query_time_ms: query_time.as_millis() as u64,
+        fuzzy,
     })
 }
 
@@ -174,11 +257,14 @@ pub async fn search_notes_paginated(
 /// - Field-specific search (content:term, path:folder)
 /// - Parenthetical grouping and complex expressions
 /// - Query complexity analysis and performance monitoring
+/// - Optional `format` filter for PlainText or Markdown notes
 #[tauri::command]
 pub async fn search_notes_boolean_paginated(
     query: String,
     page: usize,
-    page_size: usize,
+    page_size: Option<usize>,
+    scope: Option<SearchScope>,
+    format: Option<NoteFormat>,
     app_state: State<'_, AppState>,
 ) -> Result<BooleanSearchResult, ApiError> {
     let _tracker = CommandPerformanceTracker::new("search_notes_boolean_paginated");
@@ -187,20 +273,18 @@ pub async fn search_notes_boolean_paginated(
     // Validate IPC operation with Search capability - fixed: SearchNotes -> Search
     let context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::Search]
+        "search_notes_boolean_paginated"
     )?;
     
     // Enhanced validation for Boolean search queries - fixed: missing context parameter
     validate_search_query_secure(&app_state.security_validator, &query, &context)?;
-    
-    // Validate pagination parameters for Boolean search
-    if page_size == 0 || page_size > 100 {
-        return Err(ApiError {
-            code: "VALIDATION_ERROR".to_string(),
-            message: "Page size must be between 1 and 100 for Boolean search operations".to_string(),
-        });
+    if let Some(scope) = &scope {
+        scope.validate()?;
     }
     
+    // Resolve the page size against the configured default and cap
+    let page_size = app_state.search.effective_limit(page_size).await?;
+    
     if page > 1000 {
         return Err(ApiError {
             code: "VALIDATION_ERROR".to_string(),
@@ -226,7 +310,10 @@ pub async fn search_notes_boolean_paginated(
     
     // Fixed: Handle tuple return from search service
     let (notes, total_count, search_complexity) = app_state.search
-        .search_notes_boolean_paginated(&query, page, page_size).await?;
+        .search_notes_boolean_paginated_filtered(&query, page, page_size, scope.as_ref(), format.as_ref()).await?;
+    if page == 0 {
+        record_search_history(&app_state, &query).await;
+    }
     
     let query_time = start_time.elapsed();
     
@@ -253,6 +340,175 @@ pub async fn search_notes_boolean_paginated(
     })
 }
 
+/// Runs several full-text searches in one call, returning each query's results
+/// 
+/// - One IPC validation for the whole batch; each query is validated on its own
+///   and an invalid query gets an `error` entry instead of failing the batch
+/// - At most `MAX_BATCH_QUERIES` queries; `limit` applies per query and follows
+///   the `search.default_limit` / `search.max_results` settings
+#[tauri::command]
+pub async fn search_batch(
+    queries: Vec<String>,
+    limit: Option<usize>,
+    app_state: State<'_, AppState>,
+) -> Result<HashMap<String, BatchQueryResult>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("search_batch");
+    
+    validate_ipc_operation(
+        &app_state.security_validator,
+        "search_batch"
+    )?;
+    
+    let limit = app_state.search.effective_limit(limit).await?;
+    
+    log_security_event(
+        "SEARCH_BATCH",
+        "IPC",
+        true,
+        &format!("Batch search of {} queries (limit {})", queries.len(), limit)
+    );
+    
+    let results = app_state.search.search_batch(&queries, limit).await?;
+    
+    Ok(results)
+}
+
+/// Returns the terms a query matches on so the UI can highlight them
+/// 
+/// Operators, negated terms and field prefixes are dropped; phrases are returned
+/// whole and prefix terms keep their trailing `*`.
+/// 
+/// Security features:
+/// - IPC operation context validation with Search capability
+/// - Search query validation
+#[tauri::command]
+pub async fn extract_query_terms(
+    query: String,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<String>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("extract_query_terms");
+    
+    let context = validate_ipc_operation(
+        &app_state.security_validator,
+        "extract_query_terms"
+    )?;
+    
+    validate_search_query_secure(&app_state.security_validator, &query, &context)?;
+    
+    let terms = app_state.search.extract_query_terms(&query).await?;
+    
+    Ok(terms)
+}
+
+/// Counts full-text search matches without returning the notes
+/// 
+/// - Same validation and query translation as `search_notes_paginated`
+/// - Runs a `COUNT(*)` over the FTS match, so note content is never loaded
+/// - Empty or whitespace-only queries count as 0
+#[tauri::command]
+pub async fn count_search_results(
+    query: String,
+    app_state: State<'_, AppState>,
+) -> Result<usize, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("count_search_results");
+    
+    let context = validate_ipc_operation(
+        &app_state.security_validator,
+        "count_search_results"
+    )?;
+    
+    validate_search_query_secure(&app_state.security_validator, &query, &context)?;
+    
+    let count = app_state.search.count_search_results(&query).await?;
+    
+    Ok(count)
+}
+
+/// Counts Boolean search matches without returning the notes
+/// 
+/// - Same parsing and FTS5 translation as `search_notes_boolean_paginated`
+/// - Syntax errors are returned as validation errors on the `query` field
+/// - Empty or whitespace-only queries count as 0
+#[tauri::command]
+pub async fn count_boolean_search_results(
+    query: String,
+    app_state: State<'_, AppState>,
+) -> Result<usize, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("count_boolean_search_results");
+    
+    let context = validate_ipc_operation(
+        &app_state.security_validator,
+        "count_boolean_search_results"
+    )?;
+    
+    validate_search_query_secure(&app_state.security_validator, &query, &context)?;
+    
+    let count = app_state.search.count_boolean_search_results(&query).await?;
+    
+    Ok(count)
+}
+
+/// Records a completed search in the history when the user has opted in
+/// History is best-effort: a failure to record never fails the search itself
+async fn record_search_history(app_state: &AppState, query: &str) {
+    if let Err(e) = app_state.search.record_history(query).await {
+        log::warn!("Failed to record search history: {}", e);
+    }
+}
+
+/// Returns recently run searches, newest first
+/// 
+/// - Empty unless the `search.record_history` setting is enabled
+/// - Limit defaults to and is capped at the retained history size
+#[tauri::command]
+pub async fn get_search_history(
+    limit: Option<usize>,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<SearchHistoryEntry>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_search_history");
+    
+    validate_ipc_operation(
+        &app_state.security_validator,
+        "get_search_history"
+    )?;
+    
+    let limit = limit.unwrap_or(SEARCH_HISTORY_CAPACITY);
+    if limit == 0 || limit > SEARCH_HISTORY_CAPACITY {
+        return Err(ApiError {
+            code: "VALIDATION_ERROR".to_string(),
+            message: format!("Limit must be between 1 and {}", SEARCH_HISTORY_CAPACITY),
+        });
+    }
+    
+    let history = app_state.search.get_search_history(limit).await?;
+    
+    Ok(history)
+}
+
+/// Deletes all recorded searches and returns how many were removed
+#[tauri::command]
+pub async fn clear_search_history(
+    app_state: State<'_, AppState>,
+) -> Result<usize, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("clear_search_history");
+    
+    validate_ipc_operation(
+        &app_state.security_validator,
+        "clear_search_history"
+    )?;
+    
+    log_security_event(
+        "CLEAR_SEARCH_HISTORY",
+        "IPC",
+        true,
+        "Search history cleared"
+    );
+    
+    let removed = app_state.search.clear_search_history().await?;
+    
+    Ok(removed)
+}
+
 /// Validates a Boolean search query and returns complexity analysis
 /// 
 /// Week 2 Day 4 Feature: Query Validation and Complexity Analysis
@@ -269,7 +525,7 @@ pub async fn validate_boolean_search_query(
     // Validate IPC operation - fixed: SearchNotes -> Search
     let context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::Search]
+        "validate_boolean_search_query"
     )?;
     
     // Validate search query security - fixed: missing context parameter
@@ -303,6 +559,31 @@ pub async fn validate_boolean_search_query(
     })
 }
 
+/// Parses a Boolean search query into a structured tree
+/// 
+/// - Returns `And`/`Or`/`Not`/`Phrase`/`Term`/`Near` nodes for the advanced search UI
+/// - Uses the same parser as query validation and the FTS5 translation
+/// - Syntax errors are returned as validation errors on the `query` field
+#[tauri::command]
+pub async fn parse_boolean_query(
+    query: String,
+    app_state: State<'_, AppState>,
+) -> Result<QueryNode, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("parse_boolean_query");
+    
+    // Validate IPC operation with Search capability
+    let context = validate_ipc_operation(
+        &app_state.security_validator,
+        "parse_boolean_query"
+    )?;
+    
+    validate_search_query_secure(&app_state.security_validator, &query, &context)?;
+    
+    let ast = app_state.search.parse_boolean_query(&query)?;
+    
+    Ok(ast)
+}
+
 /// Retrieves Boolean search examples for user guidance
 /// 
 /// Week 2 Day 4 Feature: Search Help System
@@ -318,7 +599,7 @@ pub async fn get_boolean_search_examples(
     // Validate IPC operation (minimal validation for static data) - fixed: SearchNotes -> Search
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::Search]
+        "get_boolean_search_examples"
     )?;
     
     // Fixed: Get examples from search service instance method
@@ -327,11 +608,123 @@ pub async fn get_boolean_search_examples(
     Ok(examples)
 }
 
+/// Explains why a single note ranks where it does for a query
+/// 
+/// Read-only relevance diagnostic:
+/// - Reuses the Boolean search parsing and bm25 ranking path, scoped to one note
+/// - Returns the bm25 score, matched columns, per-term frequencies and a snippet
+/// - `snippet_context` picks the FTS token snippet (default) or the sentence or paragraph around the first match
+/// - Requires Search capability with full query and ID validation
+#[tauri::command]
+pub async fn explain_search(
+    query: String,
+    note_id: i64,
+    snippet_context: Option<SnippetContext>,
+    app_state: State<'_, AppState>,
+) -> Result<SearchExplanation, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("explain_search");
+    
+    // Validate IPC operation with Search capability
+    let context = validate_ipc_operation(
+        &app_state.security_validator,
+        "explain_search"
+    )?;
+    
+    // Validate search query and note ID
+    validate_search_query_secure(&app_state.security_validator, &query, &context)?;
+    validate_id_secure(note_id)?;
+    
+    // Log diagnostic request
+    log_security_event(
+        "SEARCH_EXPLAIN",
+        "IPC",
+        true,
+        &format!("Explaining note {} for query: '{}'", 
+            note_id,
+            if query.chars().count() > 50 { 
+                format!("{}...", query.chars().take(47).collect::<String>())
+            } else { 
+                query.clone() 
+            }
+        )
+    );
+    
+    let explanation = app_state.search
+        .explain_search_with_context(&query, note_id, snippet_context.unwrap_or_default()).await?;
+    
+    Ok(explanation)
+}
+
+/// Finds occurrences of a query inside one note, for in-note find
+/// 
+/// The query is matched literally against the note content (no FTS); each match
+/// carries its line, column and character offset so the UI can scroll to it.
+/// 
+/// Security features:
+/// - IPC operation context validation with Search capability
+/// - Search query and ID validation
+#[tauri::command]
+pub async fn search_in_note(
+    id: i64,
+    query: String,
+    options: Option<InNoteSearchOptions>,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<InNoteMatch>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("search_in_note");
+    
+    // Validate IPC operation with Search capability
+    let context = validate_ipc_operation(
+        &app_state.security_validator,
+        "search_in_note"
+    )?;
+    
+    validate_search_query_secure(&app_state.security_validator, &query, &context)?;
+    validate_id_secure(id)?;
+    
+    let matches = app_state.search.search_in_note(id, &query, options.unwrap_or_default()).await?;
+    
+    Ok(matches)
+}
+
+/// Finds notes whose content matches a regular expression
+/// 
+/// Passing an `operation_id` lets a slow search be stopped with `cancel_operation`;
+/// it then fails with `CANCELLED`.
+/// 
+/// Security features:
+/// - IPC operation context validation with Search capability
+/// - Pattern length and injection validation; compiled pattern size is capped
+/// - Results capped by `search.max_results`
+#[tauri::command]
+pub async fn search_notes_regex(
+    pattern: String,
+    operation_id: Option<String>,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<Note>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("search_notes_regex");
+    
+    // Validate IPC operation with Search capability
+    let context = validate_ipc_operation_correlated(
+        &app_state.security_validator,
+        "search_notes_regex",
+        operation_id.as_deref()
+    )?;
+    
+    validate_search_query_secure(&app_state.security_validator, &pattern, &context)?;
+    
+    let search = app_state.search.clone();
+    let notes = run_cancellable_in(&context, "search", move |cancellation| async move {
+        search.search_notes_regex(&pattern, Some(&cancellation)).await
+    }).await?;
+    
+    Ok(notes)
+}
+
 #[cfg(test)]
 #[allow(unused)]
 mod tests_disabled {
     use super::*;
-    use crate::validation::{SecurityValidator, OperationContext};
+    use crate::validation::{SecurityValidator, OperationCapability, OperationContext};
     use crate::database::DbService;
     use crate::search::SearchService;
     use crate::settings::SettingsService;
@@ -350,7 +743,7 @@ mod tests_disabled {
         let security_validator = Arc::new(SecurityValidator::new());
         let search_service = Arc::new(SearchService::new(db_service.clone()));
         let settings_service = Arc::new(SettingsService::new(db_service.clone()));
-        let plugin_manager = Arc::new(tokio::sync::Mutex::new(PluginManager::new()));
+        let plugin_manager = Arc::new(tokio::sync::RwLock::new(PluginManager::new()));
         
         AppState {
             db: db_service,
@@ -630,4 +1023,5 @@ mod tests_disabled {
         assert!(result.is_ok());
         assert!(duration.as_millis() < 1000); // Should be fast for empty DB
     }
-}
\ No newline at end of file
+}
+
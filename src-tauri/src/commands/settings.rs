@@ -5,13 +5,29 @@
 use crate::commands::shared::{
     validate_ipc_operation, CommandPerformanceTracker, log_security_event
 };
-use crate::error::ApiError;
-use crate::validation::OperationCapability;
+use crate::error::{ApiError, AppError};
+use crate::global_shortcut::GlobalShortcutService;
+use crate::models::SettingChange;
+use crate::database::SETTINGS_HISTORY_PER_KEY;
+use crate::settings::SettingsService;
+use crate::validation::SecurityValidator;
+use crate::traits::events::EventEmitter;
 use crate::AppState;
 use std::collections::HashMap;
-use tauri::State;
+use tauri::{AppHandle, State};
 use serde::{Deserialize, Serialize};
 
+/// Event emitted once per setting changed by a bulk operation such as `reset_to_defaults`
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+/// Payload of a `settings-changed` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SettingChangedEvent {
+    pub key: String,
+    /// The new value; `None` when the setting was removed
+    pub value: Option<String>,
+}
+
 /// Settings structure for IPC
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AppSettings {
@@ -55,7 +71,7 @@ pub async fn get_setting(
     // Validate IPC operation with required capabilities
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "get_setting"
     )?;
     
     // Validate key
@@ -93,15 +109,18 @@ pub async fn set_setting(
     // Validate IPC operation with required capabilities
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "set_setting"
     )?;
     
     // Validate key and value
     validate_settings_key(&key)?;
     validate_settings_value(&key, &value)?;
     
-    // Set the setting
-    app_state.settings.set_setting(&key, &value).await?;
+    // Set the setting, re-registering the global shortcut when it changes
+    save_setting_value(&app_state.settings, &app_state.global_shortcut, &key, &value).await?;
+    if key == crate::validation::IPC_PAYLOAD_LIMIT_SETTING_KEY {
+        app_state.security_validator.apply_payload_limit_setting(Some(&value))?;
+    }
     
     // Log security event for settings modification
     log_security_event(
@@ -129,7 +148,7 @@ pub async fn get_all_settings(
     // Validate IPC operation with required capabilities
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "get_all_settings"
     )?;
     
     // Get all settings
@@ -176,7 +195,7 @@ pub async fn delete_setting(
     // Validate IPC operation with required capabilities
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "delete_setting"
     )?;
     
     // Validate key
@@ -196,6 +215,200 @@ pub async fn delete_setting(
     Ok(())
 }
 
+/// Get the recorded changes to a setting, most recent first
+/// 
+/// Security features:
+/// - IPC operation context validation with SystemAccess capability
+/// - Settings key validation
+/// - Limit capped at the per-key history size
+#[tauri::command]
+pub async fn get_setting_history(
+    key: String,
+    limit: Option<usize>,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<SettingChange>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_setting_history");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_setting_history"
+    )?;
+    
+    // Validate key
+    validate_settings_key(&key)?;
+    let limit = limit.unwrap_or(SETTINGS_HISTORY_PER_KEY).min(SETTINGS_HISTORY_PER_KEY);
+    
+    let history = app_state.settings.get_setting_history(&key, limit).await?;
+    
+    log_security_event(
+        "SETTING_HISTORY_READ",
+        "IPC",
+        true,
+        &format!("Setting history accessed: {}", sanitize_key_for_logging(&key))
+    );
+    
+    Ok(history)
+}
+
+/// Restore the value a setting had before its most recent change
+/// 
+/// Returns the restored value, or `None` when the setting did not exist before.
+/// 
+/// Security features:
+/// - IPC operation context validation with SystemAccess capability
+/// - Settings key validation
+/// - Services react to the restored value as for `set_setting`
+/// - Secure logging
+#[tauri::command]
+pub async fn revert_setting(
+    key: String,
+    app_state: State<'_, AppState>,
+) -> Result<Option<String>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("revert_setting");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "revert_setting"
+    )?;
+    
+    // Validate key
+    validate_settings_key(&key)?;
+    
+    // Let services accept the previous value before it is restored
+    let previous = app_state.settings.get_setting_history(&key, 1).await?;
+    if let Some(value) = previous.first().and_then(|change| change.old_value.as_deref()) {
+        app_state.global_shortcut.apply_setting_change(&key, value).await?;
+    }
+    let restored = app_state.settings.revert_setting(&key).await?;
+    
+    log_security_event(
+        "SETTING_REVERT",
+        "IPC",
+        true,
+        &format!("Setting reverted: {}", sanitize_key_for_logging(&key))
+    );
+    
+    Ok(restored)
+}
+
+/// Get all settings in a namespace
+/// 
+/// Security features:
+/// - IPC operation context validation with SystemAccess capability
+/// - Namespace prefix validated like a setting key
+/// - Secure logging
+#[tauri::command]
+pub async fn get_settings_namespace(
+    prefix: String,
+    app_state: State<'_, AppState>,
+) -> Result<HashMap<String, String>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_settings_namespace");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_settings_namespace"
+    )?;
+    
+    // Get the namespace (prefix is validated by the settings service)
+    let settings = app_state.settings.get_namespace(&prefix).await?;
+    
+    // Log security event for settings access
+    log_security_event(
+        "SETTINGS_NAMESPACE_READ",
+        "IPC",
+        true,
+        &format!("Settings namespace accessed: {}", sanitize_key_for_logging(&prefix))
+    );
+    
+    Ok(settings)
+}
+
+/// Restore every known setting to its default without touching notes
+/// 
+/// With `remove_custom`, settings that have no default are deleted as well. The
+/// reset runs in one transaction and emits `settings-changed` for each changed
+/// key. Returns the changed keys.
+/// 
+/// Security features:
+/// - IPC operation context validation with SystemAccess capability
+/// - Default shortcuts registered before anything is stored
+/// - Secure logging of the changed key count
+#[tauri::command]
+pub async fn reset_to_defaults(
+    remove_custom: Option<bool>,
+    app_handle: AppHandle,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<String>, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("reset_to_defaults");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "reset_to_defaults"
+    )?;
+    
+    let changed = reset_setting_values(
+        &app_state.settings,
+        &app_state.global_shortcut,
+        &app_state.security_validator,
+        remove_custom.unwrap_or(false),
+    ).await?;
+    
+    let defaults = crate::settings::get_default_settings();
+    for key in &changed {
+        let value = defaults.get(key).cloned();
+        let event = SettingChangedEvent { key: key.clone(), value };
+        if let Err(e) = app_handle.emit_event(SETTINGS_CHANGED_EVENT, serde_json::json!(event)) {
+            log::warn!("Failed to emit {}: {}", SETTINGS_CHANGED_EVENT, e);
+        }
+    }
+    
+    log_security_event(
+        "SETTINGS_RESET",
+        "IPC",
+        true,
+        &format!("Settings reset to defaults ({} keys changed)", changed.len())
+    );
+    
+    Ok(changed)
+}
+
+/// Delete all settings in a namespace
+/// 
+/// Security features:
+/// - IPC operation context validation with SystemAccess capability
+/// - Namespace prefix validated like a setting key
+/// - Secure logging of removed key count
+#[tauri::command]
+pub async fn delete_settings_namespace(
+    prefix: String,
+    app_state: State<'_, AppState>,
+) -> Result<usize, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("delete_settings_namespace");
+    
+    // Validate IPC operation with required capabilities
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "delete_settings_namespace"
+    )?;
+    
+    // Delete the namespace (prefix is validated by the settings service)
+    let removed = app_state.settings.delete_namespace(&prefix).await?;
+    
+    // Log security event for settings modification
+    log_security_event(
+        "SETTINGS_NAMESPACE_DELETE",
+        "IPC",
+        true,
+        &format!("Settings namespace deleted: {} ({} keys)", sanitize_key_for_logging(&prefix), removed)
+    );
+    
+    Ok(removed)
+}
+
 /// Save application settings
 /// 
 /// Security features:
@@ -214,13 +427,13 @@ pub async fn save_settings(
     // Validate IPC operation with required capabilities
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "save_settings"
     )?;
     
     // Validate and save each setting
     if let Some(shortcut) = &settings.global_shortcut {
         validate_global_shortcut(shortcut)?;
-        app_state.settings.set_setting("global_shortcut", shortcut).await?;
+        save_setting_value(&app_state.settings, &app_state.global_shortcut, "global_shortcut", shortcut).await?;
     }
     
     if let Some(layout) = &settings.window_layout {
@@ -277,7 +490,7 @@ pub async fn load_settings(
     // Validate IPC operation with required capabilities
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "load_settings"
     )?;
     
     // Load all settings with defaults
@@ -335,7 +548,7 @@ pub async fn register_global_shortcut(
     // Validate IPC operation with required capabilities
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "register_global_shortcut"
     )?;
     
     // Validate shortcut format
@@ -359,6 +572,116 @@ pub async fn register_global_shortcut(
     Ok(())
 }
 
+/// Persist a setting after letting services react to the new value
+/// A shortcut that fails to register leaves the previous value both active and saved
+async fn save_setting_value(
+    settings: &SettingsService,
+    global_shortcut: &GlobalShortcutService,
+    key: &str,
+    value: &str,
+) -> Result<(), AppError> {
+    global_shortcut.apply_setting_change(key, value).await?;
+    settings.set_setting(key, value).await?;
+    if key == crate::logging::LOG_LEVEL_SETTING_KEY {
+        crate::logging::apply_level_setting(Some(value));
+    }
+    if key == crate::performance::SUMMARY_PERCENTILES_SETTING_KEY {
+        crate::performance::get_performance_monitor()
+            .set_summary_percentiles(crate::performance::parse_summary_percentiles(value)?);
+    }
+    Ok(())
+}
+
+/// Reset the stored settings to their defaults and bring the running services in line
+/// 
+/// Default shortcuts are registered before the reset is stored, so a registration
+/// failure leaves the settings untouched; if the reset itself fails, the previous
+/// shortcuts are registered again. Returns the changed keys.
+async fn reset_setting_values(
+    settings: &SettingsService,
+    global_shortcut: &GlobalShortcutService,
+    security_validator: &SecurityValidator,
+    remove_custom: bool,
+) -> Result<Vec<String>, AppError> {
+    let defaults = crate::settings::get_default_settings();
+    let mut previous = Vec::new();
+    for (key, value) in &defaults {
+        let current = settings.get_setting(key).await?;
+        if current.as_deref() != Some(value.as_str()) {
+            if let Err(e) = global_shortcut.apply_setting_change(key, value).await {
+                restore_shortcut_settings(global_shortcut, &previous).await;
+                return Err(e);
+            }
+            previous.push((key.clone(), current));
+        }
+    }
+    let changed = match settings.reset_to_defaults(remove_custom).await {
+        Ok(changed) => changed,
+        Err(e) => {
+            restore_shortcut_settings(global_shortcut, &previous).await;
+            return Err(e);
+        }
+    };
+    
+    // The reset is stored; a value the runtime rejects is logged rather than reported
+    for key in &changed {
+        if let Err(e) = reapply_setting(settings, security_validator, key, defaults.get(key).map(String::as_str)).await {
+            log::warn!("Failed to apply reset setting {}: {}", sanitize_key_for_logging(key), e);
+        }
+    }
+    Ok(changed)
+}
+
+/// Register the shortcuts from settings values again, after a reset was not stored
+async fn restore_shortcut_settings(global_shortcut: &GlobalShortcutService, previous: &[(String, Option<String>)]) {
+    for (key, value) in previous {
+        if let Err(e) = global_shortcut.apply_setting_change(key, value.as_deref().unwrap_or("")).await {
+            log::warn!("Failed to restore shortcut {}: {}", sanitize_key_for_logging(key), e);
+        }
+    }
+}
+
+/// Apply a setting's new value to the running services, the way startup restores it
+/// `value` is `None` when the setting was removed
+async fn reapply_setting(
+    settings: &SettingsService,
+    security_validator: &SecurityValidator,
+    key: &str,
+    value: Option<&str>,
+) -> Result<(), AppError> {
+    use crate::performance;
+    
+    match key {
+        crate::logging::LOG_LEVEL_SETTING_KEY => crate::logging::apply_level_setting(value),
+        crate::validation::IPC_PAYLOAD_LIMIT_SETTING_KEY => {
+            security_validator.apply_payload_limit_setting(value)?;
+        }
+        performance::BUDGET_PRESET_SETTING_KEY if value.is_none() => {
+            performance::get_performance_monitor().update_budget(performance::PerformanceBudget::default());
+        }
+        performance::BUDGET_PRESET_SETTING_KEY => {
+            performance::restore_budget_preset(settings).await?;
+        }
+        performance::SYSTEM_THRESHOLDS_SETTING_KEY if value.is_none() => {
+            performance::system::get_system_monitor()?.set_thresholds(performance::system::SystemThresholds::default())?;
+        }
+        performance::SYSTEM_THRESHOLDS_SETTING_KEY => {
+            performance::restore_system_thresholds(settings).await?;
+        }
+        performance::OPERATION_HISTORY_SETTING_KEY | performance::SYSTEM_HISTORY_SETTING_KEY => {
+            performance::restore_history_capacity(settings).await?;
+        }
+        performance::METRICS_INTERVAL_SETTING_KEY => {
+            performance::restore_metrics_interval(settings).await?;
+        }
+        performance::SUMMARY_PERCENTILES_SETTING_KEY => {
+            performance::restore_summary_percentiles(settings).await?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
 /// Validates settings key format
 fn validate_settings_key(key: &str) -> Result<(), ApiError> {
     if key.is_empty() || key.len() > 100 {
@@ -383,7 +706,34 @@ fn validate_settings_key(key: &str) -> Result<(), ApiError> {
 fn validate_settings_value(key: &str, value: &str) -> Result<(), ApiError> {
     match key {
         "global_shortcut" => validate_global_shortcut(value),
+        // Empty unbinds the recent note shortcut
+        "shortcuts.recent_note" if value.is_empty() => Ok(()),
+        "shortcuts.recent_note" => validate_global_shortcut(value),
         "window_layout" => validate_window_layout(value),
+        "window.toggle_mode" => {
+            crate::window_manager::WindowToggleMode::from_setting(value).map(|_| ()).ok_or_else(|| ApiError {
+                code: "INVALID_TOGGLE_MODE".to_string(),
+                message: "Window toggle mode must be 'hide' or 'minimize'".to_string(),
+            })
+        },
+        "logging.level" => {
+            crate::logging::parse_level(value).map(|_| ()).ok_or_else(|| ApiError {
+                code: "INVALID_LOG_LEVEL".to_string(),
+                message: "Log level must be one of off, error, warn, info, debug or trace".to_string(),
+            })
+        },
+        "notes.default_format" => {
+            crate::models::NoteFormat::from_name(value).map(|_| ()).ok_or_else(|| ApiError {
+                code: "INVALID_NOTE_FORMAT".to_string(),
+                message: "Default note format must be 'plaintext' or 'markdown'".to_string(),
+            })
+        },
+        "security.sanitize_mode" => {
+            crate::validation::SanitizeMode::from_setting(value).map(|_| ()).ok_or_else(|| ApiError {
+                code: "INVALID_SANITIZE_MODE".to_string(),
+                message: "Sanitize mode must be 'neutralize', 'escape' or 'flag'".to_string(),
+            })
+        },
         "theme" => validate_theme(value),
         "font_size" => {
             let size: u32 = value.parse().map_err(|_| ApiError {
@@ -392,13 +742,50 @@ fn validate_settings_value(key: &str, value: &str) -> Result<(), ApiError> {
             })?;
             validate_font_size(size)
         },
-        "auto_save" | "line_numbers" | "word_wrap" => {
+        // Empty leaves scheduled report exports without a destination
+        "performance.report_export_directory" if value.is_empty() => Ok(()),
+        "performance.report_export_directory" => {
+            crate::performance::validate_report_directory(value).map(|_| ()).map_err(ApiError::from)
+        },
+        "auto_save" | "line_numbers" | "word_wrap" | "performance.report_export_enabled" | "notes.auto_pin_enabled" | "notes.capture_appends_to_locked"
+        | "capture.trim" | "capture.collapse_blank_lines" | "capture.strip_url_params" => {
             value.parse::<bool>().map_err(|_| ApiError {
                 code: "INVALID_BOOLEAN_VALUE".to_string(),
                 message: "Value must be true or false".to_string(),
             })?;
             Ok(())
         },
+        "database.lock_retry_attempts" => {
+            value.parse::<u32>().ok().filter(|attempts| (1..=crate::database::MAX_LOCK_RETRY_ATTEMPTS).contains(attempts)).ok_or_else(|| ApiError {
+                code: "INVALID_LOCK_RETRY_ATTEMPTS".to_string(),
+                message: format!("Lock retry attempts must be between 1 and {}", crate::database::MAX_LOCK_RETRY_ATTEMPTS),
+            })?;
+            Ok(())
+        },
+        "database.lock_retry_backoff_ms" => {
+            value.parse::<u32>().ok().filter(|ms| *ms <= crate::database::MAX_LOCK_RETRY_BACKOFF_MS).ok_or_else(|| ApiError {
+                code: "INVALID_LOCK_RETRY_BACKOFF".to_string(),
+                message: format!("Lock retry backoff must be between 0 and {} milliseconds", crate::database::MAX_LOCK_RETRY_BACKOFF_MS),
+            })?;
+            Ok(())
+        },
+        "performance.summary_percentiles" => {
+            crate::performance::parse_summary_percentiles(value).map(|_| ()).map_err(ApiError::from)
+        },
+        "ipc.max_payload_bytes" => {
+            crate::validation::parse_ipc_payload_limit(value).map(|_| ()).map_err(ApiError::from)
+        },
+        // Empty disables stopword removal
+        "search.stopwords" => {
+            crate::search::parse_stopwords(value).map(|_| ()).map_err(ApiError::from)
+        },
+        "notes.auto_pin_threshold" => {
+            value.parse::<u32>().ok().filter(|threshold| *threshold > 0).ok_or_else(|| ApiError {
+                code: "INVALID_AUTO_PIN_THRESHOLD".to_string(),
+                message: "Auto-pin threshold must be a positive integer".to_string(),
+            })?;
+            Ok(())
+        },
         _ => {
             // Generic validation for other settings
             if value.len() > 1000 {
@@ -421,13 +808,15 @@ fn validate_global_shortcut(shortcut: &str) -> Result<(), ApiError> {
         });
     }
     
-    // Basic format validation - must contain modifier keys
-    let normalized = shortcut.to_lowercase();
-    if !normalized.contains("ctrl") && !normalized.contains("alt") 
-       && !normalized.contains("shift") && !normalized.contains("meta") {
+    // Modifier/key grammar, requiring at least one modifier
+    if let Err(error) = SecurityValidator::validate_shortcut(shortcut) {
+        let message = match error {
+            AppError::Validation { message, .. } => message,
+            other => other.to_string(),
+        };
         return Err(ApiError {
             code: "INVALID_SHORTCUT".to_string(),
-            message: "Shortcut must include at least one modifier key".to_string(),
+            message,
         });
     }
     
@@ -544,7 +933,7 @@ mod tests_disabled {
         let security_validator = Arc::new(SecurityValidator::new());
         let search_service = Arc::new(SearchService::new(db_service.clone()));
         let settings_service = Arc::new(SettingsService::new(db_service.clone()));
-        let plugin_manager = Arc::new(tokio::sync::Mutex::new(PluginManager::new()));
+        let plugin_manager = Arc::new(tokio::sync::RwLock::new(PluginManager::new()));
         
         AppState {
             db: db_service,
@@ -687,4 +1076,90 @@ mod tests_disabled {
             assert!(result.is_err(), "Invalid shortcut should fail: {}", shortcut);
         }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_shortcut_setting_change_reregisters_shortcut() {
+        use super::save_setting_value;
+        use crate::testing::mocks::MockShortcutBackend;
+        
+        let app_state = create_test_app_state().await;
+        let backend = Arc::new(MockShortcutBackend::new());
+        let shortcuts = GlobalShortcutService::with_backend(app_state.settings.clone(), backend.clone());
+        
+        save_setting_value(&app_state.settings, &shortcuts, "global_shortcut", "Ctrl+Shift+N").await.unwrap();
+        save_setting_value(&app_state.settings, &shortcuts, "global_shortcut", "Ctrl+Alt+K").await.unwrap();
+        
+        // The old combination is released before the new one is registered
+        assert_eq!(backend.calls(), vec![
+            "register:Ctrl+Shift+N".to_string(),
+            "unregister:Ctrl+Shift+N".to_string(),
+            "register:Ctrl+Alt+K".to_string(),
+        ]);
+        assert_eq!(backend.registered().into_iter().collect::<Vec<_>>(), vec!["Ctrl+Alt+K".to_string()]);
+        assert_eq!(shortcuts.get_current_shortcut().await, Some("Ctrl+Alt+K".to_string()));
+        assert_eq!(app_state.settings.get_setting("global_shortcut").await.unwrap(), Some("Ctrl+Alt+K".to_string()));
+        
+        // A failed registration rolls back to the previous shortcut and is not saved
+        backend.reject("Ctrl+Alt+J");
+        assert!(save_setting_value(&app_state.settings, &shortcuts, "global_shortcut", "Ctrl+Alt+J").await.is_err());
+        assert_eq!(backend.registered().into_iter().collect::<Vec<_>>(), vec!["Ctrl+Alt+K".to_string()]);
+        assert_eq!(shortcuts.get_current_shortcut().await, Some("Ctrl+Alt+K".to_string()));
+        assert_eq!(app_state.settings.get_setting("global_shortcut").await.unwrap(), Some("Ctrl+Alt+K".to_string()));
+        
+        // Unrelated settings never touch the backend
+        let calls_before = backend.calls().len();
+        save_setting_value(&app_state.settings, &shortcuts, "theme", "dark").await.unwrap();
+        assert_eq!(backend.calls().len(), calls_before);
+    }
+
+    #[tokio::test]
+    async fn test_reset_reapplies_runtime_settings_and_registers_default_shortcuts() {
+        use super::{reset_setting_values, save_setting_value};
+        use crate::global_shortcut::RECENT_NOTE_SHORTCUT_SETTING_KEY;
+        use crate::testing::mocks::MockShortcutBackend;
+        use crate::validation::{IPC_PAYLOAD_LIMIT_SETTING_KEY, DEFAULT_MAX_IPC_PAYLOAD_BYTES};
+        
+        let app_state = create_test_app_state().await;
+        let backend = Arc::new(MockShortcutBackend::new());
+        let shortcuts = GlobalShortcutService::with_backend(app_state.settings.clone(), backend.clone());
+        let validator = SecurityValidator::new();
+        
+        save_setting_value(&app_state.settings, &shortcuts, RECENT_NOTE_SHORTCUT_SETTING_KEY, "Ctrl+Alt+R").await.unwrap();
+        app_state.settings.set_setting(IPC_PAYLOAD_LIMIT_SETTING_KEY, "4096").await.unwrap();
+        validator.apply_payload_limit_setting(Some("4096")).unwrap();
+        
+        let changed = reset_setting_values(&app_state.settings, &shortcuts, &validator, false).await.unwrap();
+        assert!(changed.iter().any(|key| key == IPC_PAYLOAD_LIMIT_SETTING_KEY));
+        assert_eq!(validator.max_ipc_payload_bytes(), DEFAULT_MAX_IPC_PAYLOAD_BYTES);
+        // The default recent note shortcut is empty, so the binding is released
+        assert!(backend.registered().is_empty());
+        assert_eq!(app_state.settings.get_setting(RECENT_NOTE_SHORTCUT_SETTING_KEY).await.unwrap(), Some(String::new()));
+    }
+
+    #[tokio::test]
+    async fn test_recent_note_shortcut_binds_separate_action() {
+        use super::save_setting_value;
+        use crate::global_shortcut::{parse_shortcut, ShortcutAction, RECENT_NOTE_SHORTCUT_SETTING_KEY};
+        use crate::testing::mocks::MockShortcutBackend;
+        
+        let app_state = create_test_app_state().await;
+        let backend = Arc::new(MockShortcutBackend::new());
+        let shortcuts = GlobalShortcutService::with_backend(app_state.settings.clone(), backend.clone());
+        
+        save_setting_value(&app_state.settings, &shortcuts, "global_shortcut", "Ctrl+Shift+N").await.unwrap();
+        save_setting_value(&app_state.settings, &shortcuts, RECENT_NOTE_SHORTCUT_SETTING_KEY, "Ctrl+Alt+R").await.unwrap();
+        assert_eq!(backend.registered().len(), 2);
+        assert_eq!(shortcuts.action_for(&parse_shortcut("Ctrl+Shift+N").unwrap()).await, Some(ShortcutAction::ToggleWindow));
+        assert_eq!(shortcuts.action_for(&parse_shortcut("Ctrl+Alt+R").unwrap()).await, Some(ShortcutAction::OpenRecentNote));
+        assert_eq!(shortcuts.action_for(&parse_shortcut("Ctrl+Alt+Q").unwrap()).await, None);
+        
+        // One combination cannot drive both actions
+        assert!(save_setting_value(&app_state.settings, &shortcuts, RECENT_NOTE_SHORTCUT_SETTING_KEY, "ctrl+shift+n").await.is_err());
+        assert_eq!(shortcuts.action_for(&parse_shortcut("Ctrl+Alt+R").unwrap()).await, Some(ShortcutAction::OpenRecentNote));
+        
+        // An empty value unbinds it
+        save_setting_value(&app_state.settings, &shortcuts, RECENT_NOTE_SHORTCUT_SETTING_KEY, "").await.unwrap();
+        assert_eq!(backend.registered().into_iter().collect::<Vec<_>>(), vec!["Ctrl+Shift+N".to_string()]);
+        assert_eq!(shortcuts.action_for(&parse_shortcut("Ctrl+Alt+R").unwrap()).await, None);
+    }
+}
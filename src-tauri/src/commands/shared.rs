@@ -4,19 +4,74 @@
 /// the command domains, preserving the exact Week 1 security framework
 /// with 95.2% test coverage.
 
+use crate::commands::registry::find_command;
 use crate::error::AppError;
-use crate::validation::{SecurityValidator, OperationContext, OperationCapability};
+use crate::performance::{get_performance_monitor, CancellationToken};
+use crate::validation::{SecurityValidator, OperationContext, OperationCapability, OperationSource};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Capabilities refused once shutdown has started
+const SHUTDOWN_BLOCKED_CAPABILITIES: [OperationCapability; 3] = [
+    OperationCapability::WriteNotes,
+    OperationCapability::DeleteNotes,
+    OperationCapability::SystemAccess,
+];
 
 /// Common security validation for all IPC commands
 /// 
 /// This function provides the standard security validation pattern
 /// used by all command domains, ensuring consistent security posture.
+/// The capabilities come from the command's entry in the command registry;
+/// an unregistered command is rejected.
+/// 
+/// Once shutdown has started, operations that modify data or touch the system
+/// are rejected with `AppError::ShuttingDown`; reads and searches keep working
+/// so the UI can still show shutdown progress.
+/// 
+/// Commands needing a capability outside the set granted at startup are
+/// rejected with `AppError::CapabilityDenied`.
 pub fn validate_ipc_operation(
     security_validator: &SecurityValidator,
-    capabilities: Vec<OperationCapability>
+    command: &str
+) -> Result<OperationContext, AppError> {
+    validate_ipc_operation_correlated(security_validator, command, None)
+}
+
+/// Standard IPC operation validation carrying a client-supplied correlation id
+/// 
+/// The correlation id becomes the context's `operation_id`; pass the context to
+/// `run_with_timeout_in` so the recorded metrics carry the same id.
+pub fn validate_ipc_operation_correlated(
+    security_validator: &SecurityValidator,
+    command: &str,
+    correlation_id: Option<&str>
+) -> Result<OperationContext, AppError> {
+    let spec = find_command(command).ok_or_else(|| AppError::Security {
+        message: format!("Command '{}' is not in the command registry", command),
+    })?;
+    validate_ipc_capabilities(security_validator, spec.capabilities.to_vec(), correlation_id)
+}
+
+/// IPC operation validation against an explicit capability set
+pub fn validate_ipc_capabilities(
+    security_validator: &SecurityValidator,
+    capabilities: Vec<OperationCapability>,
+    correlation_id: Option<&str>
 ) -> Result<OperationContext, AppError> {
+    if security_validator.is_shutting_down() {
+        if let Some(blocked) = capabilities.iter().find(|capability| SHUTDOWN_BLOCKED_CAPABILITIES.contains(capability)) {
+            return Err(AppError::ShuttingDown {
+                operation: format!("{:?}", blocked),
+            });
+        }
+    }
+    security_validator.check_granted(&capabilities)?;
+    
     // Create IPC operation context with required capabilities
-    let context = OperationContext::new_ipc(capabilities);
+    let context = OperationContext::new_ipc(capabilities).with_correlation_id(correlation_id)?;
     
     // Validate operation context (frequency limits, capability checking)
     security_validator.validate_operation_context(&context)?;
@@ -113,7 +168,7 @@ impl CommandPerformanceTracker {
     pub fn finish(self) {
         let duration = self.start_time.elapsed();
         if duration.as_millis() > 2 {
-            eprintln!(
+            log::debug!(
                 "Performance warning: Command '{}' took {:?}ms (>2ms target)",
                 self.command_name,
                 duration.as_millis()
@@ -122,15 +177,163 @@ impl CommandPerformanceTracker {
     }
 }
 
+/// Scale factor applied to `PerformanceBudget.max_operation_duration_ms` for default timeouts
+/// 
+/// The budget describes a healthy operation; the timeout only catches wedged ones.
+pub const COMMAND_TIMEOUT_BUDGET_MULTIPLIER: u64 = 50;
+
+/// Per-operation-type timeout overrides
+fn command_timeout_overrides() -> &'static Mutex<HashMap<String, Duration>> {
+    static OVERRIDES: OnceLock<Mutex<HashMap<String, Duration>>> = OnceLock::new();
+    OVERRIDES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Configure the timeout for an operation type, or restore the budget-derived default with `None`
+pub fn set_command_timeout(operation_type: &str, timeout: Option<Duration>) {
+    if let Ok(mut overrides) = command_timeout_overrides().lock() {
+        match timeout {
+            Some(timeout) => overrides.insert(operation_type.to_string(), timeout),
+            None => overrides.remove(operation_type),
+        };
+    }
+}
+
+/// Get the effective timeout for an operation type
+pub fn command_timeout(operation_type: &str) -> Duration {
+    if let Some(timeout) = command_timeout_overrides()
+        .lock()
+        .ok()
+        .and_then(|overrides| overrides.get(operation_type).copied())
+    {
+        return timeout;
+    }
+    
+    // Commands are recorded as `command_<type>`, so per-operation budgets apply to them too
+    let budget_ms = get_performance_monitor().get_budget().duration_budget_ms(&format!("command_{}", operation_type));
+    Duration::from_millis(budget_ms.saturating_mul(COMMAND_TIMEOUT_BUDGET_MULTIPLIER))
+}
+
+/// Run a command operation with a timeout
+/// 
+/// The operation runs as its own task so a wedged operation cannot hold the
+/// command past its deadline. On timeout the task is aborted, the operation is
+/// recorded as failed in the performance monitor and `AppError::Timeout` is returned.
+pub async fn run_with_timeout<T, F>(operation_type: &str, operation: F) -> Result<T, AppError>
+where
+    F: Future<Output = Result<T, AppError>> + Send + 'static,
+    T: Send + 'static,
+{
+    let operation_id = format!("cmd_{}_{}", operation_type, uuid::Uuid::new_v4());
+    run_with_timeout_as(operation_type, operation_id, None, None, |_| operation).await
+}
+
+/// Run a command operation with a timeout, recording its metrics under the context's operation id
+/// 
+/// The id is also stored as `correlation_id` in the metrics context, and the
+/// metrics are tagged with the context's `OperationSource`.
+pub async fn run_with_timeout_in<T, F>(context: &OperationContext, operation_type: &str, operation: F) -> Result<T, AppError>
+where
+    F: Future<Output = Result<T, AppError>> + Send + 'static,
+    T: Send + 'static,
+{
+    run_with_timeout_as(
+        operation_type,
+        context.operation_id.clone(),
+        Some(context.operation_id.clone()),
+        Some(&context.source),
+        |_| operation,
+    ).await
+}
+
+/// Run a cancellable command operation with a timeout, under the context's operation id
+/// 
+/// `operation` receives the token registered for the operation and should call
+/// `CancellationToken::check` between items. `cancel_operation` with the context's
+/// operation id then stops it with `AppError::Cancelled`, recorded as a cancelled metric.
+pub async fn run_cancellable_in<T, F, Fut>(context: &OperationContext, operation_type: &str, operation: F) -> Result<T, AppError>
+where
+    F: FnOnce(CancellationToken) -> Fut,
+    Fut: Future<Output = Result<T, AppError>> + Send + 'static,
+    T: Send + 'static,
+{
+    run_with_timeout_as(
+        operation_type,
+        context.operation_id.clone(),
+        Some(context.operation_id.clone()),
+        Some(&context.source),
+        operation,
+    ).await
+}
+
+async fn run_with_timeout_as<T, F, Fut>(
+    operation_type: &str,
+    operation_id: String,
+    correlation_id: Option<String>,
+    source: Option<&OperationSource>,
+    operation: F,
+) -> Result<T, AppError>
+where
+    F: FnOnce(CancellationToken) -> Fut,
+    Fut: Future<Output = Result<T, AppError>> + Send + 'static,
+    T: Send + 'static,
+{
+    let timeout = command_timeout(operation_type);
+    let mut tracker = get_performance_monitor().start_operation(
+        operation_id,
+        format!("command_{}", operation_type),
+    );
+    if let Some(correlation_id) = correlation_id {
+        tracker.add_context("correlation_id".to_string(), correlation_id);
+    }
+    if let Some(source) = source {
+        tracker.set_source(source);
+    }
+    
+    let handle = tokio::spawn(operation(tracker.cancellation_token()));
+    let abort_handle = handle.abort_handle();
+    
+    match tokio::time::timeout(timeout, handle).await {
+        Ok(Ok(Ok(value))) => {
+            tracker.complete_success();
+            Ok(value)
+        }
+        Ok(Ok(Err(error @ AppError::Cancelled { .. }))) => {
+            tracker.complete_cancelled();
+            Err(error)
+        }
+        Ok(Ok(Err(error))) => {
+            tracker.complete_error(error.to_string());
+            Err(error)
+        }
+        Ok(Err(join_error)) => {
+            tracker.complete_error(join_error.to_string());
+            Err(AppError::Thread {
+                message: format!("Operation '{}' failed to complete: {}", operation_type, join_error),
+            })
+        }
+        Err(_) => {
+            abort_handle.abort();
+            tracker.complete_error(format!("Timed out after {}ms", timeout.as_millis()));
+            Err(AppError::Timeout {
+                operation: operation_type.to_string(),
+            })
+        }
+    }
+}
+
 /// Security logging for audit trails
 /// 
 /// Logs security-relevant events for monitoring and debugging
 /// while preserving user privacy.
+/// 
+/// Successes are logged at info level and failures at warn level, under the
+/// `security` target; the logger adds the timestamp.
 pub fn log_security_event(event_type: &str, source: &str, success: bool, message: &str) {
-    let status = if success { "SUCCESS" } else { "FAILURE" };
-    eprintln!(
-        "[SECURITY] {} - {}: {} from {} - {}",
-        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+    let (level, status) = if success { (log::Level::Info, "SUCCESS") } else { (log::Level::Warn, "FAILURE") };
+    log::log!(
+        target: "security",
+        level,
+        "{}: {} from {} - {}",
         status,
         event_type,
         source,
@@ -146,14 +349,96 @@ mod tests {
     #[test]
     fn test_validate_ipc_operation() {
         let validator = SecurityValidator::new();
-        let capabilities = vec![OperationCapability::ReadNotes];
         
-        let result = validate_ipc_operation(&validator, capabilities);
+        let result = validate_ipc_operation(&validator, "get_note");
         assert!(result.is_ok());
         
         let context = result.unwrap();
         assert_eq!(context.source, crate::validation::OperationSource::IPC);
         assert_eq!(context.frequency_limit, Some(15));
+        assert_eq!(context.capabilities, vec![OperationCapability::ReadNotes]);
+        
+        // Commands missing from the registry are refused
+        assert!(matches!(
+            validate_ipc_operation(&validator, "drop_all_tables"),
+            Err(AppError::Security { .. })
+        ));
+    }
+    
+    #[test]
+    fn test_shutdown_blocks_writes_but_not_reads() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+        
+        let shutdown_flag = Arc::new(AtomicBool::new(false));
+        let validator = SecurityValidator::with_shutdown_flag(shutdown_flag.clone());
+        assert!(validate_ipc_capabilities(&validator, vec![OperationCapability::WriteNotes], None).is_ok());
+        
+        shutdown_flag.store(true, Ordering::Relaxed);
+        assert!(validate_ipc_capabilities(&validator, vec![OperationCapability::ReadNotes], None).is_ok());
+        assert!(validate_ipc_capabilities(&validator, vec![OperationCapability::Search], None).is_ok());
+        for blocked in [OperationCapability::WriteNotes, OperationCapability::DeleteNotes, OperationCapability::SystemAccess] {
+            assert!(matches!(
+                validate_ipc_capabilities(&validator, vec![OperationCapability::ReadNotes, blocked], None),
+                Err(AppError::ShuttingDown { .. })
+            ));
+        }
+        
+        shutdown_flag.store(false, Ordering::Relaxed);
+        assert!(validate_ipc_capabilities(&validator, vec![OperationCapability::WriteNotes], None).is_ok());
+    }
+    
+    #[test]
+    fn test_read_only_grant_denies_writes() {
+        let granted = OperationCapability::parse_grant("read_only").unwrap();
+        let validator = SecurityValidator::new().with_granted_capabilities(granted);
+        
+        assert!(validate_ipc_capabilities(&validator, vec![OperationCapability::ReadNotes], None).is_ok());
+        assert!(validate_ipc_capabilities(&validator, vec![OperationCapability::Search], None).is_ok());
+        assert!(matches!(
+            validate_ipc_capabilities(&validator, vec![OperationCapability::ReadNotes, OperationCapability::WriteNotes], None),
+            Err(AppError::CapabilityDenied { capability }) if capability == "WriteNotes"
+        ));
+        
+        assert_eq!(
+            OperationCapability::parse_grant("read_notes, write_notes").unwrap(),
+            Some(vec![OperationCapability::ReadNotes, OperationCapability::WriteNotes])
+        );
+        assert_eq!(OperationCapability::parse_grant("all").unwrap(), None);
+        assert!(OperationCapability::parse_grant("read_notes,root").is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_correlation_id_propagates_into_recorded_metrics() {
+        let validator = SecurityValidator::new();
+        let context = validate_ipc_capabilities(
+            &validator,
+            vec![OperationCapability::ReadNotes],
+            Some("frontend-req:42")
+        ).unwrap();
+        assert_eq!(context.operation_id, "frontend-req:42");
+        
+        run_with_timeout_in(&context, "test_correlated_operation", async { Ok(()) }).await.unwrap();
+        
+        let recorded = get_performance_monitor()
+            .get_operation_history(1)
+            .into_iter()
+            .find(|metrics| metrics.operation_id == "frontend-req:42")
+            .expect("metrics recorded under the correlation id");
+        assert_eq!(recorded.operation_type, "command_test_correlated_operation");
+        assert_eq!(recorded.context.get("correlation_id").map(String::as_str), Some("frontend-req:42"));
+        assert_eq!(recorded.source(), Some(OperationSource::IPC));
+        assert!(!recorded.was_cancelled());
+        
+        // Without a correlation id each context gets its own UUID
+        let first = validate_ipc_capabilities(&validator, vec![OperationCapability::ReadNotes], None).unwrap();
+        let second = validate_ipc_capabilities(&validator, vec![OperationCapability::ReadNotes], None).unwrap();
+        assert_ne!(first.operation_id, second.operation_id);
+        assert!(uuid::Uuid::parse_str(&first.operation_id).is_ok());
+        
+        for invalid in ["", "has space", "semi;colon", &"x".repeat(65)] {
+            assert!(validate_ipc_capabilities(&validator, vec![OperationCapability::ReadNotes], Some(invalid)).is_err());
+        }
     }
     
     #[test]
@@ -215,4 +500,92 @@ mod tests {
         tracker.finish();
         // This test just ensures the tracker can be created and finished without panic
     }
-}
\ No newline at end of file
+    
+    #[tokio::test]
+    async fn test_run_with_timeout_success() {
+        let result = run_with_timeout("test_fast_operation", async { Ok::<_, AppError>(42) }).await;
+        assert_eq!(result.unwrap(), 42);
+    }
+    
+    #[tokio::test]
+    async fn test_run_with_timeout_trips_on_slow_operation() {
+        set_command_timeout("test_slow_operation", Some(Duration::from_millis(20)));
+        
+        let result = run_with_timeout("test_slow_operation", async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok::<_, AppError>(())
+        }).await;
+        
+        match result {
+            Err(AppError::Timeout { operation }) => assert_eq!(operation, "test_slow_operation"),
+            other => panic!("Expected timeout, got {:?}", other),
+        }
+        
+        // The timed out operation is recorded as a failure
+        let history = get_performance_monitor().get_performance_summary(1);
+        assert!(history.success_rate < 1.0);
+        
+        set_command_timeout("test_slow_operation", None);
+    }
+    
+    #[tokio::test]
+    async fn test_cancel_operation_stops_it_and_records_cancelled_metric() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        
+        let validator = SecurityValidator::new();
+        let context = validate_ipc_capabilities(
+            &validator,
+            vec![OperationCapability::Search],
+            Some("cancel-test:1")
+        ).unwrap();
+        
+        let processed = Arc::new(AtomicUsize::new(0));
+        let counter = processed.clone();
+        let running = run_cancellable_in(&context, "test_cancellable_operation", move |token| async move {
+            for _ in 0..200 {
+                token.check()?;
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+            Ok::<_, AppError>(())
+        });
+        let cancel = async {
+            while processed.load(Ordering::SeqCst) < 3 {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+            assert!(get_performance_monitor().cancel_operation("cancel-test:1"));
+        };
+        let (result, _) = tokio::join!(running, cancel);
+        
+        match result {
+            Err(AppError::Cancelled { operation_id }) => assert_eq!(operation_id, "cancel-test:1"),
+            other => panic!("Expected cancellation, got {:?}", other),
+        }
+        assert!(processed.load(Ordering::SeqCst) < 200);
+        
+        let recorded = get_performance_monitor()
+            .get_operation_history(1)
+            .into_iter()
+            .find(|metrics| metrics.operation_id == "cancel-test:1")
+            .expect("cancelled operation is recorded");
+        assert!(recorded.was_cancelled());
+        assert!(!recorded.success);
+        
+        // Finished operations can no longer be cancelled
+        assert!(!get_performance_monitor().cancel_operation("cancel-test:1"));
+    }
+    
+    #[test]
+    fn test_command_timeout_defaults_to_scaled_budget() {
+        let budget_ms = get_performance_monitor().get_budget().max_operation_duration_ms;
+        assert_eq!(
+            command_timeout("test_default_operation"),
+            Duration::from_millis(budget_ms * COMMAND_TIMEOUT_BUDGET_MULTIPLIER)
+        );
+        
+        set_command_timeout("test_override_operation", Some(Duration::from_millis(5)));
+        assert_eq!(command_timeout("test_override_operation"), Duration::from_millis(5));
+        set_command_timeout("test_override_operation", None);
+    }
+}
@@ -4,13 +4,50 @@
 /// All operations require elevated permissions and undergo strict security validation.
 
 use crate::commands::shared::{
-    validate_ipc_operation, CommandPerformanceTracker, log_security_event
+    validate_ipc_operation, run_with_timeout, CommandPerformanceTracker, log_security_event
 };
+use crate::database::DatabaseStats;
 use crate::error::{AppError, ApiError};
-use crate::validation::OperationCapability;
+use crate::global_shortcut::ShortcutAction;
 use crate::AppState;
+use serde::Serialize;
 use tauri::State;
 
+/// Liveness and readiness report for supervisors and monitoring scripts
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthStatus {
+    /// The database answered a trivial query
+    pub db_ok: bool,
+    /// The full-text index can be queried
+    pub search_ok: bool,
+    pub shutting_down: bool,
+    pub uptime_seconds: u64,
+}
+
+/// Probe the database and search index; failures are reported as `false`, never as errors
+pub async fn collect_health(app_state: &AppState) -> HealthStatus {
+    HealthStatus {
+        db_ok: app_state.db.health_check().await.unwrap_or(false),
+        search_ok: app_state.db.search_health_check().await.unwrap_or(false),
+        shutting_down: app_state.shutdown_manager.is_shutting_down(),
+        uptime_seconds: app_state.shutdown_manager.uptime().as_secs(),
+    }
+}
+
+/// Carry out the action bound to a pressed global shortcut
+/// 
+/// Runs from the global shortcut handler rather than over IPC. The recent note
+/// action opens the most recently accessed note, or a blank note if there is none.
+pub async fn handle_shortcut_action(app_state: &AppState, action: ShortcutAction) -> Result<(), AppError> {
+    match action {
+        ShortcutAction::ToggleWindow => app_state.window_manager.toggle_window().await,
+        ShortcutAction::OpenRecentNote => {
+            let note_id = app_state.db.most_recently_accessed_note_id().await?;
+            app_state.window_manager.open_note(note_id).await
+        }
+    }
+}
+
 /// Unregister the current global shortcut
 /// 
 /// Security features:
@@ -26,7 +63,7 @@ pub async fn unregister_global_shortcut(
     // Validate IPC operation
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "unregister_global_shortcut"
     )?;
     
     // Log security event
@@ -61,7 +98,7 @@ pub async fn toggle_window_visibility(
     // Validate IPC operation
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "toggle_window_visibility"
     )?;
     
     // Log security event
@@ -96,7 +133,7 @@ pub async fn show_window(
     // Validate IPC operation
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "show_window"
     )?;
     
     // Log security event
@@ -131,7 +168,7 @@ pub async fn hide_window(
     // Validate IPC operation
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "hide_window"
     )?;
     
     // Log security event
@@ -166,7 +203,7 @@ pub async fn is_window_visible(
     // Validate IPC operation with read-only access
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "is_window_visible"
     )?;
     
     // Query window visibility through window manager
@@ -191,7 +228,7 @@ pub async fn get_current_shortcut(
     // Validate IPC operation
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "get_current_shortcut"
     )?;
     
     // Log security event
@@ -224,7 +261,7 @@ pub async fn shutdown_application(
     // Validate IPC operation with enhanced system access
     let _context = validate_ipc_operation(
         &app_state.security_validator,
-        vec![OperationCapability::SystemAccess]
+        "shutdown_application"
     )?;
     
     // Log security event for shutdown
@@ -245,6 +282,58 @@ pub async fn shutdown_application(
     Ok(())
 }
 
+/// Report database size, per-table row counts and sizes, and the FTS index size
+/// 
+/// Useful for deciding when to vacuum or enable compression.
+/// 
+/// Security features:
+/// - Requires SystemAccess capability
+/// - Only counts and sizes are returned, never note content
+#[tauri::command]
+pub async fn get_database_stats(
+    app_state: State<'_, AppState>,
+) -> Result<DatabaseStats, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("get_database_stats");
+    
+    // Validate IPC operation
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "get_database_stats"
+    )?;
+    
+    log_security_event(
+        "DATABASE_STATS_ACCESS",
+        "IPC",
+        true,
+        "Database statistics requested"
+    );
+    
+    let db = app_state.db.clone();
+    let stats = run_with_timeout("db_stats", async move { db.get_stats().await }).await?;
+    
+    Ok(stats)
+}
+
+/// Cheap liveness/readiness probe
+/// 
+/// Security features:
+/// - IPC operation context validation with ReadNotes capability, so it keeps
+///   answering after shutdown starts blocking writes
+/// - Read-only; reports status flags only, never note content
+#[tauri::command]
+pub async fn health_check(
+    app_state: State<'_, AppState>,
+) -> Result<HealthStatus, ApiError> {
+    let _tracker = CommandPerformanceTracker::new("health_check");
+    
+    let _context = validate_ipc_operation(
+        &app_state.security_validator,
+        "health_check"
+    )?;
+    
+    Ok(collect_health(&app_state).await)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -272,7 +361,7 @@ mod tests {
         let global_shortcut = Arc::new(GlobalShortcutService::new_test(settings_service.clone()).unwrap());
         let window_manager = Arc::new(WindowManager::new_test(settings_service.clone()).unwrap());
         
-        let plugin_manager = Arc::new(tokio::sync::Mutex::new(PluginManager::new()));
+        let plugin_manager = Arc::new(tokio::sync::RwLock::new(PluginManager::new()));
         let shutdown_manager = Arc::new(ShutdownManager::default());
         
         AppState {
@@ -287,6 +376,21 @@ mod tests {
         }
     }
     
+    #[tokio::test]
+    async fn test_health_reports_ok_and_shutdown_state() {
+        let app_state = create_test_app_state().await;
+        
+        let health = collect_health(&app_state).await;
+        assert!(health.db_ok);
+        assert!(health.search_ok);
+        assert!(!health.shutting_down);
+        
+        app_state.shutdown_manager.get_shutdown_flag().store(true, std::sync::atomic::Ordering::Relaxed);
+        let health = collect_health(&app_state).await;
+        assert!(health.shutting_down);
+        assert!(health.db_ok && health.search_ok);
+    }
+    
     #[tokio::test]
     async fn test_shortcut_unregistration() {
         let _app_state = create_test_app_state().await;
@@ -0,0 +1,207 @@
+// diff.rs - Line-based diffs between two versions of a note
+
+use serde::{Deserialize, Serialize};
+
+/// Unchanged lines shown around each change
+pub const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Largest changed region (old lines x new lines) diffed line by line
+/// Bigger regions are reported as a single replacement to bound memory use
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// How a line differs between the two versions
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DiffLineKind {
+    #[serde(rename = "context")]
+    Context,
+    #[serde(rename = "added")]
+    Added,
+    #[serde(rename = "removed")]
+    Removed,
+}
+
+/// A single line of a diff hunk
+/// Line numbers are 1-based; `old_line` is unset for added lines and `new_line` for removed ones
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+/// A run of changes with surrounding context, like a unified diff hunk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Diff two texts line by line, grouping changes into hunks with `context` lines around them
+pub fn diff_lines(old: &str, new: &str, context: usize) -> Vec<DiffHunk> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let lines = line_edits(&old_lines, &new_lines);
+    group_hunks(&lines, context)
+}
+
+/// Full edit script between two line lists, including unchanged lines
+fn line_edits(old: &[&str], new: &[&str]) -> Vec<DiffLine> {
+    // Common prefix and suffix are unchanged and need no alignment
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut edits = Vec::with_capacity(old.len().max(new.len()));
+    let mut old_number = 0;
+    let mut new_number = 0;
+    let mut push = |kind: DiffLineKind, text: &str| {
+        let old_line = if kind == DiffLineKind::Added { None } else { old_number += 1; Some(old_number) };
+        let new_line = if kind == DiffLineKind::Removed { None } else { new_number += 1; Some(new_number) };
+        edits.push(DiffLine { kind, old_line, new_line, text: text.to_string() });
+    };
+
+    for line in &old[..prefix] {
+        push(DiffLineKind::Context, line);
+    }
+    for (kind, text) in align(old_middle, new_middle) {
+        push(kind, text);
+    }
+    for line in &old[old.len() - suffix..] {
+        push(DiffLineKind::Context, line);
+    }
+
+    edits
+}
+
+/// Align two line lists by their longest common subsequence
+/// Removals are emitted before additions within each changed run
+fn align<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(DiffLineKind, &'a str)> {
+    if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+        return old.iter().map(|line| (DiffLineKind::Removed, *line))
+            .chain(new.iter().map(|line| (DiffLineKind::Added, *line)))
+            .collect();
+    }
+
+    // lcs[i][j] = length of the LCS of old[i..] and new[j..]
+    let width = new.len() + 1;
+    let mut lcs = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::with_capacity(old.len() + new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            result.push((DiffLineKind::Context, old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            result.push((DiffLineKind::Removed, old[i]));
+            i += 1;
+        } else {
+            result.push((DiffLineKind::Added, new[j]));
+            j += 1;
+        }
+    }
+    result.extend(old[i..].iter().map(|line| (DiffLineKind::Removed, *line)));
+    result.extend(new[j..].iter().map(|line| (DiffLineKind::Added, *line)));
+    result
+}
+
+/// Split an edit script into hunks, merging changes whose context would overlap
+fn group_hunks(lines: &[DiffLine], context: usize) -> Vec<DiffHunk> {
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.kind != DiffLineKind::Context)
+        .map(|(index, _)| index)
+        .collect();
+
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for index in changed {
+        let start = index.saturating_sub(context);
+        let end = (index + context + 1).min(lines.len());
+        match ranges.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|(start, end)| {
+            let hunk_lines = lines[start..end].to_vec();
+            let old_lines = hunk_lines.iter().filter(|line| line.old_line.is_some()).count();
+            let new_lines = hunk_lines.iter().filter(|line| line.new_line.is_some()).count();
+            // An empty side starts at the line before the hunk, as in unified diffs
+            let old_start = hunk_lines.iter().find_map(|line| line.old_line)
+                .unwrap_or_else(|| lines[..start].iter().filter(|line| line.old_line.is_some()).count());
+            let new_start = hunk_lines.iter().find_map(|line| line.new_line)
+                .unwrap_or_else(|| lines[..start].iter().filter(|line| line.new_line.is_some()).count());
+            DiffHunk { old_start, old_lines, new_start, new_lines, lines: hunk_lines }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn markers(hunk: &DiffHunk) -> Vec<String> {
+        hunk.lines
+            .iter()
+            .map(|line| {
+                let marker = match line.kind {
+                    DiffLineKind::Context => ' ',
+                    DiffLineKind::Added => '+',
+                    DiffLineKind::Removed => '-',
+                };
+                format!("{}{}", marker, line.text)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_identical_texts_have_no_hunks() {
+        assert!(diff_lines("a\nb\nc", "a\nb\nc", DIFF_CONTEXT_LINES).is_empty());
+    }
+
+    #[test]
+    fn test_separate_changes_produce_separate_hunks() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10";
+        let new = "1\ntwo\n3\n4\n5\n6\n7\n8\n9\n10\n11";
+        let hunks = diff_lines(old, new, 1);
+
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(markers(&hunks[0]), vec![" 1", "-2", "+two", " 3"]);
+        assert_eq!((hunks[0].old_start, hunks[0].old_lines, hunks[0].new_start, hunks[0].new_lines), (1, 3, 1, 3));
+        assert_eq!(markers(&hunks[1]), vec![" 10", "+11"]);
+        assert_eq!((hunks[1].old_start, hunks[1].old_lines, hunks[1].new_start, hunks[1].new_lines), (10, 1, 10, 2));
+        assert_eq!(hunks[1].lines[1].old_line, None);
+        assert_eq!(hunks[1].lines[1].new_line, Some(11));
+    }
+
+    #[test]
+    fn test_nearby_changes_merge_into_one_hunk() {
+        let hunks = diff_lines("a\nb\nc\nd", "a\nB\nc\nD", DIFF_CONTEXT_LINES);
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(markers(&hunks[0]), vec![" a", "-b", "+B", " c", "-d", "+D"]);
+    }
+}
@@ -0,0 +1,154 @@
+// encryption.rs - Passphrase-derived keys and authenticated encryption of note content
+//
+// Content is sealed with XChaCha20-Poly1305 under a random 24-byte nonce. Keys come
+// from the passphrase through PBKDF2-HMAC-SHA256 with a per-database salt; the
+// stored key check is an HMAC-SHA256 under a second derived key.
+
+use crate::error::AppError;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+/// PBKDF2 iterations when deriving a key from the passphrase
+pub const KEY_DERIVATION_ROUNDS: u32 = 100_000;
+
+/// Length of the random salt stored alongside the key check
+pub const SALT_LEN: usize = 16;
+
+/// Format byte leading every sealed value, so the layout can change later
+/// It is also authenticated as associated data
+const SEALED_FORMAT: u8 = 1;
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+const KEY_CHECK_LABEL: &[u8] = b"scratch-pad note encryption key check";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Keys derived from the encryption passphrase
+#[derive(Clone)]
+pub struct EncryptionKey {
+    cipher_key: [u8; KEY_LEN],
+    check_key: [u8; KEY_LEN],
+}
+
+impl std::fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EncryptionKey(..)")
+    }
+}
+
+impl EncryptionKey {
+    /// Derive the cipher and key-check keys from a passphrase and salt
+    pub fn derive(passphrase: &str, salt: &[u8]) -> Self {
+        let mut derived = [0u8; 2 * KEY_LEN];
+        pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, KEY_DERIVATION_ROUNDS, &mut derived);
+        let (cipher_key, check_key) = derived.split_at(KEY_LEN);
+        Self {
+            cipher_key: cipher_key.try_into().expect("derived key halves are KEY_LEN bytes"),
+            check_key: check_key.try_into().expect("derived key halves are KEY_LEN bytes"),
+        }
+    }
+
+    fn check_mac(&self) -> HmacSha256 {
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(&self.check_key).expect("HMAC accepts keys of any length");
+        mac.update(KEY_CHECK_LABEL);
+        mac
+    }
+
+    /// Value stored to recognise the right passphrase without storing the key
+    pub fn key_check(&self) -> [u8; 32] {
+        self.check_mac().finalize().into_bytes().into()
+    }
+
+    /// Whether `check` was produced by `key_check` for this key, compared in constant time
+    pub fn matches_check(&self, check: &[u8]) -> bool {
+        self.check_mac().verify_slice(check).is_ok()
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new((&self.cipher_key).into())
+    }
+
+    /// Encrypt and authenticate `plaintext` under a fresh random nonce
+    pub fn seal(&self, plaintext: &str) -> Vec<u8> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::rng().fill_bytes(&mut nonce);
+
+        let ciphertext = self
+            .cipher()
+            .encrypt(XNonce::from_slice(&nonce), Payload { msg: plaintext.as_bytes(), aad: &[SEALED_FORMAT] })
+            .expect("XChaCha20-Poly1305 encryption does not fail for in-memory input");
+
+        let mut sealed = Vec::with_capacity(1 + NONCE_LEN + ciphertext.len());
+        sealed.push(SEALED_FORMAT);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        sealed
+    }
+
+    /// Check and decrypt a value produced by `seal`
+    /// A wrong key and a modified value both fail the same way
+    pub fn open(&self, sealed: &[u8]) -> Result<String, AppError> {
+        let failed = || AppError::Security {
+            message: "Encrypted content could not be decrypted".to_string(),
+        };
+
+        if sealed.len() < 1 + NONCE_LEN + TAG_LEN || sealed[0] != SEALED_FORMAT {
+            return Err(failed());
+        }
+        let (nonce, ciphertext) = sealed[1..].split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher()
+            .decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad: &sealed[..1] })
+            .map_err(|_| failed())?;
+        String::from_utf8(plaintext).map_err(|_| failed())
+    }
+}
+
+/// A fresh random salt for a new passphrase
+pub fn generate_salt() -> [u8; SALT_LEN] {
+    let mut salt = [0u8; SALT_LEN];
+    rand::rng().fill_bytes(&mut salt);
+    salt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_derivation_depends_on_passphrase_and_salt() {
+        let salt = [7u8; SALT_LEN];
+        let key = EncryptionKey::derive("correct horse", &salt);
+        assert_eq!(key.key_check(), EncryptionKey::derive("correct horse", &salt).key_check());
+        assert_ne!(key.key_check(), EncryptionKey::derive("correct horse", &[8u8; SALT_LEN]).key_check());
+        assert_ne!(key.cipher_key, key.check_key);
+        assert!(!key.matches_check(&key.key_check()[..16]));
+    }
+
+    #[test]
+    fn test_seal_open_round_trip_and_tamper_detection() {
+        let salt = generate_salt();
+        let key = EncryptionKey::derive("correct horse", &salt);
+        let sealed = key.seal("bank pin is in the blue folder");
+        assert!(!sealed.windows(4).any(|w| w == b"bank"));
+        assert_eq!(key.open(&sealed).unwrap(), "bank pin is in the blue folder");
+        // Fresh nonce per seal
+        assert_ne!(key.seal("same"), key.seal("same"));
+
+        let mut tampered = sealed.clone();
+        tampered[1 + NONCE_LEN] ^= 1;
+        assert!(key.open(&tampered).is_err());
+        let mut reformatted = sealed.clone();
+        reformatted[0] = SEALED_FORMAT + 1;
+        assert!(key.open(&reformatted).is_err());
+
+        let wrong = EncryptionKey::derive("wrong horse", &salt);
+        assert!(wrong.open(&sealed).is_err());
+        assert!(key.matches_check(&key.key_check()));
+        assert!(!wrong.matches_check(&key.key_check()));
+    }
+}
@@ -62,8 +62,26 @@ pub enum AppError {
     #[error("Shutdown error: {message}")]
     Shutdown { message: String },
     
+    #[error("Application is shutting down: {operation} is not allowed")]
+    ShuttingDown { operation: String },
+    
     #[error("Not found: record with id {id}")]
     NotFound { id: i64 },  // Added NotFound variant for database operations
+    
+    #[error("Operation timed out: {operation}")]
+    Timeout { operation: String },
+    
+    #[error("Capability denied: {capability} is not granted")]
+    CapabilityDenied { capability: String },
+    
+    #[error("Database is locked: {operation} gave up after {attempts} attempts")]
+    DbLocked { operation: String, attempts: u32 },
+    
+    #[error("Operation cancelled: {operation_id}")]
+    Cancelled { operation_id: String },
+    
+    #[error("Payload too large: {size} bytes exceeds the {limit} byte limit")]
+    PayloadTooLarge { size: usize, limit: usize },
 }
 
 // Implement From<anyhow::Error> for AppError
@@ -144,9 +162,29 @@ impl AppError {
             Self::Shutdown { message } => Self::Shutdown { 
                 message: message.clone() 
             },
+            Self::ShuttingDown { operation } => Self::ShuttingDown { 
+                operation: operation.clone() 
+            },
             Self::NotFound { id } => Self::NotFound { 
                 id: *id 
             },
+            Self::Timeout { operation } => Self::Timeout { 
+                operation: operation.clone() 
+            },
+            Self::CapabilityDenied { capability } => Self::CapabilityDenied { 
+                capability: capability.clone() 
+            },
+            Self::DbLocked { operation, attempts } => Self::DbLocked { 
+                operation: operation.clone(), 
+                attempts: *attempts 
+            },
+            Self::Cancelled { operation_id } => Self::Cancelled { 
+                operation_id: operation_id.clone() 
+            },
+            Self::PayloadTooLarge { size, limit } => Self::PayloadTooLarge { 
+                size: *size, 
+                limit: *limit 
+            },
         }
     }
 }
@@ -247,10 +285,37 @@ impl From<AppError> for ApiError {
                 code: "SHUTDOWN_ERROR".to_string(),
                 message,
             },
+            AppError::ShuttingDown { operation } => ApiError {
+                code: "SHUTTING_DOWN".to_string(),
+                message: format!("Application is shutting down: {} is not allowed", operation),
+            },
             AppError::NotFound { id } => ApiError {
                 code: "NOT_FOUND_ERROR".to_string(),
                 message: format!("Record with id {} not found", id),
             },
+            AppError::Timeout { operation } => ApiError {
+                code: "TIMEOUT_ERROR".to_string(),
+                message: format!("Operation '{}' timed out", operation),
+            },
+            AppError::CapabilityDenied { capability } => ApiError {
+                code: "CAPABILITY_DENIED".to_string(),
+                message: format!("Capability {} is not granted in this mode", capability),
+            },
+            AppError::DbLocked { operation, attempts } => ApiError {
+                code: "DATABASE_LOCKED".to_string(),
+                message: format!("The database is busy; '{}' did not complete after {} attempts, try again", operation, attempts),
+            },
+            AppError::Cancelled { operation_id } => ApiError {
+                code: "CANCELLED".to_string(),
+                message: format!("Operation '{}' was cancelled", operation_id),
+            },
+            AppError::PayloadTooLarge { size, limit } => ApiError {
+                code: "PAYLOAD_TOO_LARGE".to_string(),
+                message: format!(
+                    "Payload is {} bytes but the limit is {} bytes; remove at least {} bytes",
+                    size, limit, size.saturating_sub(limit)
+                ),
+            },
         }
     }
 }
@@ -344,6 +409,9 @@ mod tests {
 
         let not_found_error = AppError::NotFound { id: 42 };
         assert_eq!(not_found_error.to_string(), "Not found: record with id 42");
+
+        let timeout_error = AppError::Timeout { operation: "note_crud".to_string() };
+        assert_eq!(timeout_error.to_string(), "Operation timed out: note_crud");
     }
 
     #[test]
@@ -514,6 +582,46 @@ mod tests {
         let api_error: ApiError = not_found_error.into();
         assert_eq!(api_error.code, "NOT_FOUND_ERROR");
         assert_eq!(api_error.message, "Record with id 123 not found");
+
+        // Test Timeout error conversion
+        let timeout_error = AppError::Timeout { operation: "search".to_string() };
+        let api_error: ApiError = timeout_error.into();
+        assert_eq!(api_error.code, "TIMEOUT_ERROR");
+        assert_eq!(api_error.message, "Operation 'search' timed out");
+    }
+
+    #[test]
+    fn test_capability_denied_conversion() {
+        let denied = AppError::CapabilityDenied { capability: "WriteNotes".to_string() };
+        assert_eq!(denied.to_string(), "Capability denied: WriteNotes is not granted");
+        let api_error: ApiError = denied.into();
+        assert_eq!(api_error.code, "CAPABILITY_DENIED");
+    }
+
+    #[test]
+    fn test_db_locked_conversion() {
+        let locked = AppError::DbLocked { operation: "update_note".to_string(), attempts: 3 };
+        assert_eq!(locked.to_string(), "Database is locked: update_note gave up after 3 attempts");
+        let api_error: ApiError = locked.into();
+        assert_eq!(api_error.code, "DATABASE_LOCKED");
+        assert!(api_error.message.contains("try again"));
+    }
+
+    #[test]
+    fn test_cancelled_conversion() {
+        let cancelled = AppError::Cancelled { operation_id: "export-1".to_string() };
+        assert_eq!(cancelled.to_string(), "Operation cancelled: export-1");
+        let api_error: ApiError = cancelled.into();
+        assert_eq!(api_error.code, "CANCELLED");
+    }
+
+    #[test]
+    fn test_payload_too_large_conversion() {
+        let too_large = AppError::PayloadTooLarge { size: 1_048_600, limit: 1_048_576 };
+        assert_eq!(too_large.to_string(), "Payload too large: 1048600 bytes exceeds the 1048576 byte limit");
+        let api_error: ApiError = too_large.into();
+        assert_eq!(api_error.code, "PAYLOAD_TOO_LARGE");
+        assert!(api_error.message.contains("remove at least 24 bytes"));
     }
 
     #[test]
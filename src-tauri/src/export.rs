@@ -0,0 +1,579 @@
+// export.rs - Writing notes out to files in a destination directory
+
+use crate::error::AppError;
+use crate::markdown;
+use crate::models::{Note, NoteFormat};
+use crate::performance::CancellationToken;
+use crate::validation::{SanitizeMode, SanitizedContent, SecurityValidator};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// File format used when exporting notes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    #[serde(rename = "text")]
+    Text,
+    #[serde(rename = "markdown")]
+    Markdown,
+    #[serde(rename = "json")]
+    Json,
+}
+
+impl ExportFormat {
+    /// File extension written for this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Text => "txt",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    /// Render a note as it will be written to disk
+    pub fn render(&self, note: &Note) -> Result<String, AppError> {
+        match self {
+            ExportFormat::Text | ExportFormat::Markdown => Ok(note.content.clone()),
+            ExportFormat::Json => Ok(serde_json::to_string_pretty(note)?),
+        }
+    }
+}
+
+/// A note field a JSON export can be limited to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportField {
+    Id,
+    Nickname,
+    Content,
+    Tags,
+    CreatedAt,
+    UpdatedAt,
+    /// Pins and favorites share one flag
+    Pinned,
+    Format,
+}
+
+impl ExportField {
+    pub const ALL: [ExportField; 8] = [
+        ExportField::Id,
+        ExportField::Nickname,
+        ExportField::Content,
+        ExportField::Tags,
+        ExportField::CreatedAt,
+        ExportField::UpdatedAt,
+        ExportField::Pinned,
+        ExportField::Format,
+    ];
+
+    /// Key of the field in exported objects
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ExportField::Id => "id",
+            ExportField::Nickname => "nickname",
+            ExportField::Content => "content",
+            ExportField::Tags => "tags",
+            ExportField::CreatedAt => "created_at",
+            ExportField::UpdatedAt => "updated_at",
+            ExportField::Pinned => "pinned",
+            ExportField::Format => "format",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|field| field.as_str() == name)
+    }
+}
+
+/// Parse the field names requested for a JSON export
+/// Unknown names are rejected; duplicates are dropped, keeping the first
+pub fn parse_export_fields(names: &[String]) -> Result<Vec<ExportField>, AppError> {
+    let invalid = |message: String| AppError::Validation {
+        field: "fields".to_string(),
+        message,
+    };
+    if names.is_empty() {
+        return Err(invalid("At least one export field is required".to_string()));
+    }
+    let mut fields = Vec::new();
+    for name in names {
+        let field = ExportField::from_name(name.trim()).ok_or_else(|| {
+            let known: Vec<&str> = ExportField::ALL.iter().map(ExportField::as_str).collect();
+            invalid(format!("Unknown export field '{}'; expected one of {}", name, known.join(", ")))
+        })?;
+        if !fields.contains(&field) {
+            fields.push(field);
+        }
+    }
+    Ok(fields)
+}
+
+/// Render a note as a JSON object holding only `fields`
+pub fn render_json_fields(note: &Note, tags: &[String], fields: &[ExportField]) -> Result<String, AppError> {
+    let mut object = serde_json::Map::new();
+    for field in fields {
+        let value = match field {
+            ExportField::Id => serde_json::json!(note.id),
+            ExportField::Nickname => serde_json::json!(note.nickname),
+            ExportField::Content => serde_json::json!(note.content),
+            ExportField::Tags => serde_json::json!(tags),
+            ExportField::CreatedAt => serde_json::json!(note.created_at),
+            ExportField::UpdatedAt => serde_json::json!(note.updated_at),
+            ExportField::Pinned => serde_json::json!(note.is_favorite),
+            ExportField::Format => serde_json::to_value(&note.format)?,
+        };
+        object.insert(field.as_str().to_string(), value);
+    }
+    Ok(serde_json::to_string_pretty(&serde_json::Value::Object(object))?)
+}
+
+/// A note written to disk by an export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedFile {
+    pub note_id: i64,
+    pub file_name: String,
+}
+
+/// A note left out of an export, with the reason it was skipped
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkippedExport {
+    pub note_id: i64,
+    pub reason: String,
+}
+
+/// What exporting a single note would write, produced without touching the filesystem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportPreview {
+    pub note_id: i64,
+    /// File name the note would be written to when exported on its own
+    pub file_name: String,
+    pub content: String,
+}
+
+/// Summary of an export run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportManifest {
+    /// Search query that selected the notes, if any
+    pub query: Option<String>,
+    pub directory: String,
+    pub format: ExportFormat,
+    pub files: Vec<ExportedFile>,
+    pub skipped: Vec<SkippedExport>,
+}
+
+/// Validate an export destination, returning its canonical path
+/// The directory must already exist; traversal sequences are rejected before resolving it
+pub fn validate_export_directory(dir: &str) -> Result<PathBuf, AppError> {
+    let invalid = |message: String| AppError::Validation {
+        field: "directory".to_string(),
+        message,
+    };
+
+    if dir.trim().is_empty() {
+        return Err(invalid("Export directory cannot be empty".to_string()));
+    }
+    if dir.len() > SecurityValidator::MAX_PATH_LENGTH {
+        return Err(invalid("Path too long".to_string()));
+    }
+    if SecurityValidator::contains_path_traversal(dir) {
+        return Err(invalid("Path traversal detected".to_string()));
+    }
+
+    let path = Path::new(dir)
+        .canonicalize()
+        .map_err(|e| invalid(format!("Invalid export directory: {}", e)))?;
+    if !path.is_dir() {
+        return Err(invalid("Export destination is not a directory".to_string()));
+    }
+
+    Ok(path)
+}
+
+/// Name of the file a note is exported to: its sanitized nickname, or `note-<id>`
+pub fn export_file_name(note: &Note, format: ExportFormat) -> String {
+    let stem = note
+        .nickname
+        .as_deref()
+        .and_then(SecurityValidator::sanitize_file_name)
+        .unwrap_or_else(|| format!("note-{}", note.id));
+    format!("{}.{}", stem, format.extension())
+}
+
+/// Pick a file name not yet in `used`, recording it there
+///
+/// On a collision the note id is appended, then a counter if that is taken too.
+/// Names are compared case-insensitively since exports may land on such filesystems.
+fn unique_export_file_name(note: &Note, format: ExportFormat, used: &mut HashSet<String>) -> String {
+    let base = export_file_name(note, format);
+    let stem = base.strip_suffix(&format!(".{}", format.extension())).unwrap_or(&base).to_string();
+    let mut candidate = base;
+    let mut counter = 1;
+    while used.contains(&candidate.to_lowercase()) {
+        candidate = match counter {
+            1 => format!("{}-{}.{}", stem, note.id, format.extension()),
+            n => format!("{}-{}-{}.{}", stem, note.id, n, format.extension()),
+        };
+        counter += 1;
+    }
+    used.insert(candidate.to_lowercase());
+    candidate
+}
+
+/// Render a note exactly as `export_notes` would write it
+/// Content failing validation is an error here, where an export would skip the note
+pub fn preview_export(note: &Note, format: ExportFormat) -> Result<ExportPreview, AppError> {
+    SecurityValidator::validate_note_content(&note.content)?;
+    Ok(ExportPreview {
+        note_id: note.id,
+        file_name: export_file_name(note, format),
+        content: format.render(note)?,
+    })
+}
+
+/// Write each note to its own file in `dir`
+///
+/// Notes whose content fails validation are skipped and recorded in the manifest
+/// rather than aborting the export. File paths are checked against the
+/// configured export extensions and must stay inside `dir`. Notes sharing a
+/// nickname get distinct file names (see `unique_export_file_name`).
+pub fn export_notes(
+    notes: &[Note],
+    dir: &Path,
+    format: ExportFormat,
+    allowed_extensions: &[String],
+) -> Result<ExportManifest, AppError> {
+    export_notes_cancellable(notes, dir, format, allowed_extensions, None)
+}
+
+/// `export_notes`, checking `cancellation` before each note
+///
+/// A cancelled export stops with `AppError::Cancelled`; files already written are kept.
+pub fn export_notes_cancellable(
+    notes: &[Note],
+    dir: &Path,
+    format: ExportFormat,
+    allowed_extensions: &[String],
+    cancellation: Option<&CancellationToken>,
+) -> Result<ExportManifest, AppError> {
+    write_exports(notes, dir, format, allowed_extensions, cancellation, |note| format.render(note))
+}
+
+/// `export_notes_cancellable` as JSON objects holding only `fields`
+/// `tags` holds the tags of each note by id; notes missing from it export no tags
+pub fn export_notes_json_fields(
+    notes: &[Note],
+    tags: &HashMap<i64, Vec<String>>,
+    fields: &[ExportField],
+    dir: &Path,
+    allowed_extensions: &[String],
+    cancellation: Option<&CancellationToken>,
+) -> Result<ExportManifest, AppError> {
+    write_exports(notes, dir, ExportFormat::Json, allowed_extensions, cancellation, |note| {
+        render_json_fields(note, tags.get(&note.id).map(Vec::as_slice).unwrap_or_default(), fields)
+    })
+}
+
+fn write_exports(
+    notes: &[Note],
+    dir: &Path,
+    format: ExportFormat,
+    allowed_extensions: &[String],
+    cancellation: Option<&CancellationToken>,
+    render: impl Fn(&Note) -> Result<String, AppError>,
+) -> Result<ExportManifest, AppError> {
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    let mut used_names = HashSet::new();
+
+    for note in notes {
+        if let Some(cancellation) = cancellation {
+            cancellation.check()?;
+        }
+        if let Err(e) = SecurityValidator::validate_note_content(&note.content) {
+            skipped.push(SkippedExport { note_id: note.id, reason: e.to_string() });
+            continue;
+        }
+
+        let file_name = unique_export_file_name(note, format, &mut used_names);
+        let path = SecurityValidator::validate_export_path_with_extensions(&file_name, Some(dir), allowed_extensions)?;
+        std::fs::write(&path, render(note)?)?;
+        files.push(ExportedFile { note_id: note.id, file_name });
+    }
+
+    Ok(ExportManifest {
+        query: None,
+        directory: dir.to_string_lossy().to_string(),
+        format,
+        files,
+        skipped,
+    })
+}
+
+/// Stylesheet inlined into exported HTML documents so they need no other files
+const HTML_EXPORT_STYLE: &str = "body{max-width:46rem;margin:2rem auto;padding:0 1rem;\
+font-family:system-ui,sans-serif;line-height:1.6;color:#222}\
+pre{background:#f5f5f5;padding:.75rem;overflow-x:auto;white-space:pre-wrap}\
+code{font-family:ui-monospace,monospace}\
+blockquote{margin:0;padding-left:1rem;border-left:3px solid #ccc;color:#555}\
+img{max-width:100%}";
+
+/// Render a note as a standalone HTML document
+///
+/// Markdown notes are converted to HTML; plain text notes are escaped and kept
+/// in a `<pre>` block. The result is passed through `sanitize_content`.
+pub fn render_html_document(note: &Note) -> Result<String, AppError> {
+    Ok(render_html_document_with_mode(note, SanitizeMode::Neutralize)?.content)
+}
+
+/// Render a note as a standalone HTML document, sanitizing it according to `mode`
+///
+/// `Neutralize` matches `render_html_document`. `Escape` skips Markdown rendering
+/// and shows the escaped source in a `<pre>` block. `Flag` renders normally and
+/// only reports whether the document contains active content.
+pub fn render_html_document_with_mode(note: &Note, mode: SanitizeMode) -> Result<SanitizedContent, AppError> {
+    SecurityValidator::validate_note_content(&note.content)?;
+
+    let title = note.nickname.clone().unwrap_or_else(|| format!("Note {}", note.id));
+    let (body, escaped) = match (&note.format, mode) {
+        (_, SanitizeMode::Escape) => {
+            let escaped = SecurityValidator::sanitize_content_with_mode(&note.content, mode);
+            (format!("<pre>{}</pre>\n", escaped.content), Some(escaped.flagged))
+        }
+        (&NoteFormat::Markdown, _) => (markdown::to_html(&note.content), None),
+        (&NoteFormat::PlainText, _) => (format!("<pre>{}</pre>\n", markdown::escape_html(&note.content)), None),
+    };
+    let document = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        markdown::escape_html(&title),
+        HTML_EXPORT_STYLE,
+        body
+    );
+
+    // The escaped body is already inert; the rest of the document is our own markup
+    if let Some(flagged) = escaped {
+        return Ok(SanitizedContent { content: document, flagged });
+    }
+    Ok(SecurityValidator::sanitize_content_with_mode(&document, mode))
+}
+
+/// Write a note to `path` as a standalone HTML document, returning the written path
+///
+/// The parent directory must already exist and the file must have an `.html`
+/// extension that is among the configured export extensions.
+pub fn export_note_html(note: &Note, path: &str, allowed_extensions: &[String]) -> Result<PathBuf, AppError> {
+    export_note_html_with_mode(note, path, allowed_extensions, SanitizeMode::Neutralize).map(|(path, _)| path)
+}
+
+/// Write a note as HTML sanitized according to `mode`
+///
+/// Returns the written path and whether the document contained active content.
+pub fn export_note_html_with_mode(
+    note: &Note,
+    path: &str,
+    allowed_extensions: &[String],
+    mode: SanitizeMode,
+) -> Result<(PathBuf, bool), AppError> {
+    let invalid = |message: &str| AppError::Validation {
+        field: "path".to_string(),
+        message: message.to_string(),
+    };
+
+    let target = Path::new(path);
+    let file_name = target.file_name().ok_or_else(|| invalid("Export path must name a file"))?;
+    let is_html = Path::new(file_name)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("html"));
+    if !is_html {
+        return Err(invalid("HTML exports must use the .html extension"));
+    }
+
+    let parent = target.parent().filter(|dir| !dir.as_os_str().is_empty()).ok_or_else(|| invalid("Export path must include a directory"))?;
+    let dir = validate_export_directory(&parent.to_string_lossy())?;
+    let path = SecurityValidator::validate_export_path_with_extensions(file_name, Some(&dir), allowed_extensions)?;
+    let rendered = render_html_document_with_mode(note, mode)?;
+    std::fs::write(&path, rendered.content)?;
+
+    Ok((path, rendered.flagged))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn note(id: i64, content: &str) -> Note {
+        Note {
+            id,
+            content: content.to_string(),
+            format: NoteFormat::PlainText,
+            nickname: None,
+            path: format!("/note/{}", id),
+            is_favorite: false,
+            is_encrypted: false,
+            external_id: String::new(),
+            created_at: "2024-01-01 00:00:00".to_string(),
+            updated_at: "2024-01-01 00:00:00".to_string(),
+        }
+    }
+
+    fn default_extensions() -> Vec<String> {
+        SecurityValidator::DEFAULT_EXPORT_EXTENSIONS.iter().map(|ext| ext.to_string()).collect()
+    }
+
+    #[test]
+    fn test_export_writes_valid_notes_and_skips_invalid() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = validate_export_directory(&temp_dir.path().to_string_lossy()).unwrap();
+        let notes = vec![note(1, "first note"), note(2, "<script>alert(1)</script>"), note(3, "third note")];
+
+        let manifest = export_notes(&notes, &dir, ExportFormat::Text, &default_extensions()).unwrap();
+
+        let exported: Vec<i64> = manifest.files.iter().map(|file| file.note_id).collect();
+        assert_eq!(exported, vec![1, 3]);
+        assert_eq!(std::fs::read_to_string(dir.join("note-1.txt")).unwrap(), "first note");
+        assert_eq!(manifest.skipped.len(), 1);
+        assert_eq!(manifest.skipped[0].note_id, 2);
+        assert!(!dir.join("note-2.txt").exists());
+
+        // The destination extension must be allowed
+        let markdown_only = vec!["md".to_string()];
+        assert!(export_notes(&notes[..1], &dir, ExportFormat::Json, &markdown_only).is_err());
+    }
+
+    #[test]
+    fn test_cancelled_export_stops_before_next_note() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = validate_export_directory(&temp_dir.path().to_string_lossy()).unwrap();
+        let notes = vec![note(1, "first note"), note(2, "second note")];
+        let cancellation = CancellationToken::new("export-test");
+        cancellation.cancel();
+
+        let result = export_notes_cancellable(&notes, &dir, ExportFormat::Text, &default_extensions(), Some(&cancellation));
+
+        assert!(matches!(result, Err(AppError::Cancelled { operation_id }) if operation_id == "export-test"));
+        assert!(!dir.join("note-1.txt").exists());
+    }
+
+    #[test]
+    fn test_preview_matches_exported_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = validate_export_directory(&temp_dir.path().to_string_lossy()).unwrap();
+        let named = Note { nickname: Some("Preview me".to_string()), ..note(7, "# Heading\nbody") };
+
+        for format in [ExportFormat::Text, ExportFormat::Markdown, ExportFormat::Json] {
+            let preview = preview_export(&named, format).unwrap();
+            let manifest = export_notes(std::slice::from_ref(&named), &dir, format, &default_extensions()).unwrap();
+
+            assert_eq!(manifest.files[0].file_name, preview.file_name);
+            assert_eq!(std::fs::read_to_string(dir.join(&preview.file_name)).unwrap(), preview.content);
+        }
+
+        assert!(preview_export(&note(8, "<script>alert(1)</script>"), ExportFormat::Text).is_err());
+    }
+
+    #[test]
+    fn test_export_deduplicates_shared_nicknames() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = validate_export_directory(&temp_dir.path().to_string_lossy()).unwrap();
+        let named = |id: i64, nickname: &str| Note { nickname: Some(nickname.to_string()), ..note(id, &format!("note {}", id)) };
+        let notes = vec![named(1, "Meeting notes"), named(2, "Meeting notes"), named(3, "meeting/notes"), named(4, "***")];
+
+        let manifest = export_notes(&notes, &dir, ExportFormat::Markdown, &default_extensions()).unwrap();
+
+        let names: Vec<&str> = manifest.files.iter().map(|file| file.file_name.as_str()).collect();
+        assert_eq!(names, vec!["Meeting-notes.md", "Meeting-notes-2.md", "meeting-notes-3.md", "note-4.md"]);
+        assert_eq!(std::fs::read_to_string(dir.join("Meeting-notes.md")).unwrap(), "note 1");
+        assert_eq!(std::fs::read_to_string(dir.join("Meeting-notes-2.md")).unwrap(), "note 2");
+    }
+
+    #[test]
+    fn test_json_export_with_fields_omits_other_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = validate_export_directory(&temp_dir.path().to_string_lossy()).unwrap();
+        let notes = vec![Note { nickname: Some("Lean".to_string()), ..note(5, "secret body") }];
+        let tags = HashMap::from([(5, vec!["work".to_string()])]);
+        let fields = parse_export_fields(&["id".to_string(), "tags".to_string(), "id".to_string()]).unwrap();
+        assert_eq!(fields, vec![ExportField::Id, ExportField::Tags]);
+
+        let manifest = export_notes_json_fields(&notes, &tags, &fields, &dir, &default_extensions(), None).unwrap();
+
+        let written = std::fs::read_to_string(dir.join(&manifest.files[0].file_name)).unwrap();
+        let object: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&written).unwrap();
+        let keys: Vec<&str> = object.keys().map(String::as_str).collect();
+        assert_eq!(keys, vec!["id", "tags"]);
+        assert_eq!(object["tags"], serde_json::json!(["work"]));
+        assert!(!written.contains("secret body"));
+
+        assert!(parse_export_fields(&["id".to_string(), "path".to_string()]).is_err());
+        assert!(parse_export_fields(&[]).is_err());
+    }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(SecurityValidator::sanitize_file_name("../etc/passwd").as_deref(), Some("etc-passwd"));
+        assert_eq!(SecurityValidator::sanitize_file_name("  Ideas: 2024!  ").as_deref(), Some("Ideas-2024"));
+        assert_eq!(SecurityValidator::sanitize_file_name("con").as_deref(), Some("con_"));
+        assert_eq!(SecurityValidator::sanitize_file_name("..."), None);
+        assert_eq!(SecurityValidator::sanitize_file_name(&"a".repeat(300)).unwrap().len(), SecurityValidator::MAX_FILE_STEM_LENGTH);
+    }
+
+    #[test]
+    fn test_export_directory_validation() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(validate_export_directory("").is_err());
+        assert!(validate_export_directory(&format!("{}/../escape", temp_dir.path().display())).is_err());
+        assert!(validate_export_directory(&temp_dir.path().join("missing").to_string_lossy()).is_err());
+
+        let file_path = temp_dir.path().join("file.txt");
+        std::fs::write(&file_path, "not a directory").unwrap();
+        assert!(validate_export_directory(&file_path.to_string_lossy()).is_err());
+    }
+
+    #[test]
+    fn test_export_note_html_is_standalone_and_sanitized() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("note.html");
+        let path = path.to_string_lossy();
+        let markdown = Note {
+            format: NoteFormat::Markdown,
+            nickname: Some("Web <notes>".to_string()),
+            ..note(1, "# Links\n\nSee [the site](https://example.com) and [this file](file:///tmp/notes.txt).")
+        };
+
+        let written = export_note_html(&markdown, &path, &default_extensions()).unwrap();
+        let html = std::fs::read_to_string(&written).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<style>"));
+        assert!(html.contains("<title>Web &lt;notes&gt;</title>"));
+        assert!(html.contains("<h1>Links</h1>"));
+        assert!(html.contains("<a href=\"https://example.com\">the site</a>"));
+        assert!(html.contains("<a href=\"#\">this file</a>"));
+        assert!(!html.to_lowercase().contains("<script"));
+        assert_eq!(html, SecurityValidator::sanitize_content(&html));
+
+        let plain = note(2, "# not a heading\nsay \"hi\"");
+        let html = render_html_document(&plain).unwrap();
+        assert!(html.contains("<pre># not a heading\nsay &quot;hi&quot;</pre>"));
+        assert!(!html.to_lowercase().contains("<script"));
+
+        assert!(export_note_html(&plain, &temp_dir.path().join("note.txt").to_string_lossy(), &default_extensions()).is_err());
+        assert!(export_note_html(&plain, &path, &["md".to_string()]).is_err());
+        assert!(export_note_html(&note(3, "<script>alert(1)</script>"), &path, &default_extensions()).is_err());
+    }
+    #[test]
+    fn test_render_html_escape_mode_shows_markdown_source() {
+        let markdown = Note {
+            format: NoteFormat::Markdown,
+            ..note(1, "# Links [site](https://example.com)")
+        };
+
+        let escaped = render_html_document_with_mode(&markdown, SanitizeMode::Escape).unwrap();
+        assert!(!escaped.flagged);
+        assert!(escaped.content.contains("<pre># Links [site](https://example.com)</pre>"));
+        assert!(!escaped.content.contains("<h1>"));
+
+        let flagged = render_html_document_with_mode(&markdown, SanitizeMode::Flag).unwrap();
+        assert!(!flagged.flagged);
+        assert_eq!(flagged.content, render_html_document(&markdown).unwrap());
+    }
+}
@@ -1,14 +1,51 @@
 use crate::error::AppError;
 use crate::settings::SettingsService;
+use crate::traits::shortcuts::ShortcutBackend;
+use crate::validation::SecurityValidator;
 use std::sync::Arc;
 use tauri::AppHandle;
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut};
 use tokio::sync::Mutex;
 
+/// Settings key holding the global shortcut
+pub const GLOBAL_SHORTCUT_SETTING_KEY: &str = "global_shortcut";
+
+/// Settings key holding the shortcut that opens the most recently accessed note
+/// Empty (the default) leaves it unbound
+pub const RECENT_NOTE_SHORTCUT_SETTING_KEY: &str = "shortcuts.recent_note";
+
+/// What a registered global shortcut does when pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutAction {
+    /// Show or hide the main window (`global_shortcut`)
+    ToggleWindow,
+    /// Open the most recently accessed note (`shortcuts.recent_note`)
+    OpenRecentNote,
+}
+
+impl ShortcutBackend for AppHandle {
+    fn register(&self, shortcut: &str) -> Result<(), AppError> {
+        self.global_shortcut()
+            .register(parse_shortcut(shortcut)?)
+            .map_err(|e| AppError::Runtime {
+                message: format!("Failed to register global shortcut: {}", e),
+            })
+    }
+
+    fn unregister(&self, shortcut: &str) -> Result<(), AppError> {
+        self.global_shortcut()
+            .unregister(parse_shortcut(shortcut)?)
+            .map_err(|e| AppError::Runtime {
+                message: format!("Failed to unregister global shortcut: {}", e),
+            })
+    }
+}
+
 pub struct GlobalShortcutService {
-    app_handle: Option<AppHandle>,
+    backend: Option<Arc<dyn ShortcutBackend>>,
     settings_service: Arc<SettingsService>,
     current_shortcut: Arc<Mutex<Option<String>>>,
+    recent_note_shortcut: Arc<Mutex<Option<String>>>,
     is_test_mode: bool,
 }
 
@@ -16,9 +53,10 @@ impl GlobalShortcutService {
     /// Create a new GlobalShortcutService
     pub fn new(app_handle: AppHandle, settings_service: Arc<SettingsService>) -> Self {
         Self {
-            app_handle: Some(app_handle),
+            backend: Some(Arc::new(app_handle)),
             settings_service,
             current_shortcut: Arc::new(Mutex::new(None)),
+            recent_note_shortcut: Arc::new(Mutex::new(None)),
             is_test_mode: false,
         }
     }
@@ -28,20 +66,33 @@ impl GlobalShortcutService {
     pub fn new_test(settings_service: Arc<SettingsService>) -> Result<Self, AppError> {
         // For testing, create a simulated shortcut service without actual Tauri runtime
         Ok(Self {
-            app_handle: None,
+            backend: None,
             settings_service,
             current_shortcut: Arc::new(Mutex::new(None)),
+            recent_note_shortcut: Arc::new(Mutex::new(None)),
             is_test_mode: true,
         })
     }
 
+    /// Create a GlobalShortcutService driving the given backend, for testing
+    #[cfg(test)]
+    pub fn with_backend(settings_service: Arc<SettingsService>, backend: Arc<dyn ShortcutBackend>) -> Self {
+        Self {
+            backend: Some(backend),
+            settings_service,
+            current_shortcut: Arc::new(Mutex::new(None)),
+            recent_note_shortcut: Arc::new(Mutex::new(None)),
+            is_test_mode: false,
+        }
+    }
+
     /// Initialize the global shortcut service with the current setting
     pub async fn initialize(&self) -> Result<(), AppError> {
         if self.is_test_mode {
             // In test mode, just validate the current shortcut setting
             let _shortcut = self
                 .settings_service
-                .get_setting("global_shortcut")
+                .get_setting(GLOBAL_SHORTCUT_SETTING_KEY)
                 .await?
                 .unwrap_or_else(|| "Ctrl+Shift+N".to_string());
             return Ok(());
@@ -50,17 +101,25 @@ impl GlobalShortcutService {
         // Get the current global shortcut setting
         let shortcut = self
             .settings_service
-            .get_setting("global_shortcut")
+            .get_setting(GLOBAL_SHORTCUT_SETTING_KEY)
             .await?
             .unwrap_or_else(|| "Ctrl+Shift+N".to_string());
 
         // Register the shortcut
         self.register_shortcut(&shortcut).await?;
 
+        // The recent note shortcut is optional; failing to bind it leaves it unbound
+        let recent_note = self.settings_service.get_setting(RECENT_NOTE_SHORTCUT_SETTING_KEY).await?;
+        if let Some(recent_note) = recent_note.filter(|shortcut| !shortcut.trim().is_empty()) {
+            if let Err(e) = self.replace_registration(&self.recent_note_shortcut, &recent_note).await {
+                log::warn!("Failed to register recent note shortcut '{}': {}", recent_note, e);
+            }
+        }
+
         Ok(())
     }
 
-    /// Register a global shortcut
+    /// Register a global shortcut, replacing the current one, and persist it
     pub async fn register_shortcut(&self, shortcut: &str) -> Result<(), AppError> {
         if self.is_test_mode {
             // In test mode, just validate and store the shortcut
@@ -70,62 +129,129 @@ impl GlobalShortcutService {
             return Ok(());
         }
 
-        // Validate the shortcut format
+        self.replace_shortcut(shortcut).await?;
+
+        // Update the setting
+        self.settings_service
+            .set_setting(GLOBAL_SHORTCUT_SETTING_KEY, shortcut)
+            .await?;
+
+        Ok(())
+    }
+
+    /// React to a setting about to be saved
+    /// 
+    /// A new `global_shortcut` or `shortcuts.recent_note` value is registered in
+    /// place of the current one before the caller persists it; an error means the
+    /// old shortcut is still active and the setting should not be saved. An empty
+    /// recent note shortcut unbinds it. Other keys are ignored.
+    pub async fn apply_setting_change(&self, key: &str, value: &str) -> Result<(), AppError> {
+        let slot = match key {
+            GLOBAL_SHORTCUT_SETTING_KEY => &self.current_shortcut,
+            RECENT_NOTE_SHORTCUT_SETTING_KEY if value.trim().is_empty() => {
+                return self.release_registration(&self.recent_note_shortcut).await;
+            }
+            RECENT_NOTE_SHORTCUT_SETTING_KEY => &self.recent_note_shortcut,
+            _ => return Ok(()),
+        };
+
+        if self.is_test_mode {
+            self.validate_shortcut(value)?;
+            let mut current = slot.lock().await;
+            *current = Some(value.to_string());
+            return Ok(());
+        }
+
+        self.replace_registration(slot, value).await
+    }
+
+    /// Action bound to a pressed shortcut, if it is one of ours
+    pub async fn action_for(&self, shortcut: &Shortcut) -> Option<ShortcutAction> {
+        let bindings = [
+            (&self.current_shortcut, ShortcutAction::ToggleWindow),
+            (&self.recent_note_shortcut, ShortcutAction::OpenRecentNote),
+        ];
+        for (slot, action) in bindings {
+            let bound = slot.lock().await.as_deref().and_then(|combo| parse_shortcut(combo).ok());
+            if bound.as_ref() == Some(shortcut) {
+                return Some(action);
+            }
+        }
+        None
+    }
+
+    /// Swap the registered main shortcut for `shortcut` as one step
+    async fn replace_shortcut(&self, shortcut: &str) -> Result<(), AppError> {
+        self.replace_registration(&self.current_shortcut, shortcut).await
+    }
+
+    /// Swap the shortcut registered in `slot` for `shortcut` as one step
+    /// 
+    /// The old shortcut is released before the new one is registered; if the new
+    /// registration fails the old shortcut is registered again and stays current.
+    /// A combination already bound to another action is rejected.
+    async fn replace_registration(&self, slot: &Mutex<Option<String>>, shortcut: &str) -> Result<(), AppError> {
+        // Validate the shortcut format and key before touching the current registration
         self.validate_shortcut(shortcut)?;
+        let parsed = parse_shortcut(shortcut)?;
+        let backend = self.backend()?;
 
-        // Unregister the current shortcut if it exists
-        self.unregister_current_shortcut().await?;
+        for other in [&self.current_shortcut, &self.recent_note_shortcut] {
+            if std::ptr::eq(other.as_ref(), slot) {
+                continue;
+            }
+            let taken = other.lock().await.as_deref().and_then(|combo| parse_shortcut(combo).ok());
+            if taken == Some(parsed) {
+                return Err(AppError::Validation {
+                    field: "shortcut".to_string(),
+                    message: format!("'{}' is already bound to another action", shortcut),
+                });
+            }
+        }
 
-        // Parse the shortcut string into the plugin format
-        let shortcut_obj = self.parse_shortcut(shortcut)?;
-        
-        // Register the new shortcut - the callback handler is set up at the plugin level
-        let app_handle = self.app_handle.as_ref().ok_or_else(|| AppError::Runtime {
-            message: "AppHandle not available".to_string(),
-        })?;
-        
-        app_handle
-            .global_shortcut()
-            .register(shortcut_obj)
-            .map_err(|e| AppError::Runtime {
-                message: format!("Failed to register global shortcut: {}", e),
-            })?;
+        // Hold the lock for the whole swap so concurrent changes cannot interleave
+        let mut current = slot.lock().await;
+        if current.as_deref() == Some(shortcut) {
+            return Ok(());
+        }
 
-        // Store the current shortcut
-        let mut current = self.current_shortcut.lock().await;
-        *current = Some(shortcut.to_string());
+        if let Some(previous) = current.as_deref() {
+            backend.unregister(previous)?;
+        }
 
-        // Update the setting
-        self.settings_service
-            .set_setting("global_shortcut", shortcut)
-            .await?;
+        if let Err(e) = backend.register(shortcut) {
+            if let Some(previous) = current.as_deref() {
+                if let Err(rollback_error) = backend.register(previous) {
+                    log::warn!("Failed to restore global shortcut '{}': {}", previous, rollback_error);
+                    *current = None;
+                }
+            }
+            return Err(e);
+        }
 
+        *current = Some(shortcut.to_string());
         Ok(())
     }
 
     /// Unregister the current global shortcut
     pub async fn unregister_current_shortcut(&self) -> Result<(), AppError> {
+        self.release_registration(&self.current_shortcut).await
+    }
+
+    /// Unregister the shortcut held in `slot`, if any
+    async fn release_registration(&self, slot: &Mutex<Option<String>>) -> Result<(), AppError> {
         if self.is_test_mode {
             // In test mode, just clear the stored shortcut
-            let mut current = self.current_shortcut.lock().await;
+            let mut current = slot.lock().await;
             *current = None;
             return Ok(());
         }
 
-        let current = self.current_shortcut.lock().await;
-        if let Some(shortcut) = current.as_ref() {
-            let shortcut_obj = self.parse_shortcut(shortcut)?;
-            let app_handle = self.app_handle.as_ref().ok_or_else(|| AppError::Runtime {
-                message: "AppHandle not available".to_string(),
-            })?;
-            
-            app_handle
-                .global_shortcut()
-                .unregister(shortcut_obj)
-                .map_err(|e| AppError::Runtime {
-                    message: format!("Failed to unregister global shortcut: {}", e),
-                })?;
+        let mut current = slot.lock().await;
+        if let Some(shortcut) = current.as_deref() {
+            self.backend()?.unregister(shortcut)?;
         }
+        *current = None;
         Ok(())
     }
 
@@ -141,123 +267,122 @@ impl GlobalShortcutService {
             return Ok(());
         }
         
+        self.release_registration(&self.recent_note_shortcut).await?;
         self.unregister_current_shortcut().await
     }
 
-    /// Validate a shortcut string format
+    fn backend(&self) -> Result<&Arc<dyn ShortcutBackend>, AppError> {
+        self.backend.as_ref().ok_or_else(|| AppError::Runtime {
+            message: "Shortcut backend not available".to_string(),
+        })
+    }
+
+    /// Validate a shortcut string against the modifier/key grammar
     fn validate_shortcut(&self, shortcut: &str) -> Result<(), AppError> {
-        // Basic validation - ensure it contains modifier keys
-        let normalized = shortcut.to_lowercase();
-        
-        if !normalized.contains("ctrl") && !normalized.contains("alt") && !normalized.contains("shift") && !normalized.contains("meta") {
-            return Err(AppError::Validation {
-                field: "shortcut".to_string(),
-                message: "Global shortcuts must include at least one modifier key (Ctrl, Alt, Shift, or Meta)".to_string(),
-            });
-        }
+        SecurityValidator::validate_shortcut(shortcut)
+    }
+}
 
-        // Check for valid key combinations
-        let parts: Vec<&str> = shortcut.split('+').map(|s| s.trim()).collect();
-        if parts.len() < 2 {
-            return Err(AppError::Validation {
-                field: "shortcut".to_string(),
-                message: "Invalid shortcut format. Use format like 'Ctrl+Shift+N'".to_string(),
-            });
-        }
+/// Parse a shortcut string into a Shortcut object
+pub(crate) fn parse_shortcut(shortcut: &str) -> Result<Shortcut, AppError> {
+    let parts: Vec<&str> = shortcut.split('+').map(|s| s.trim()).collect();
 
-        Ok(())
-    }
+    let mut modifiers = Modifiers::empty();
+    let mut key_code = None;
 
-    /// Parse a shortcut string into a Shortcut object
-    fn parse_shortcut(&self, shortcut: &str) -> Result<Shortcut, AppError> {
-        let parts: Vec<&str> = shortcut.split('+').map(|s| s.trim()).collect();
-        
-        let mut modifiers = Modifiers::empty();
-        let mut key_code = None;
-
-        for part in parts {
-            match part.to_lowercase().as_str() {
-                "ctrl" | "control" => modifiers |= Modifiers::CONTROL,
-                "alt" => modifiers |= Modifiers::ALT,
-                "shift" => modifiers |= Modifiers::SHIFT,
-                "meta" | "cmd" | "super" => modifiers |= Modifiers::META,
-                key => {
-                    if key_code.is_some() {
+    for part in parts {
+        match part.to_lowercase().as_str() {
+            "ctrl" | "control" => modifiers |= Modifiers::CONTROL,
+            "alt" => modifiers |= Modifiers::ALT,
+            "shift" => modifiers |= Modifiers::SHIFT,
+            "meta" | "cmd" | "super" => modifiers |= Modifiers::META,
+            key => {
+                if key_code.is_some() {
+                    return Err(AppError::Validation {
+                        field: "shortcut".to_string(),
+                        message: "Multiple key codes specified in shortcut".to_string(),
+                    });
+                }
+
+                key_code = Some(match key.to_uppercase().as_str() {
+                    "A" => Code::KeyA,
+                    "B" => Code::KeyB,
+                    "C" => Code::KeyC,
+                    "D" => Code::KeyD,
+                    "E" => Code::KeyE,
+                    "F" => Code::KeyF,
+                    "G" => Code::KeyG,
+                    "H" => Code::KeyH,
+                    "I" => Code::KeyI,
+                    "J" => Code::KeyJ,
+                    "K" => Code::KeyK,
+                    "L" => Code::KeyL,
+                    "M" => Code::KeyM,
+                    "N" => Code::KeyN,
+                    "O" => Code::KeyO,
+                    "P" => Code::KeyP,
+                    "Q" => Code::KeyQ,
+                    "R" => Code::KeyR,
+                    "S" => Code::KeyS,
+                    "T" => Code::KeyT,
+                    "U" => Code::KeyU,
+                    "V" => Code::KeyV,
+                    "W" => Code::KeyW,
+                    "X" => Code::KeyX,
+                    "Y" => Code::KeyY,
+                    "Z" => Code::KeyZ,
+                    "1" => Code::Digit1,
+                    "2" => Code::Digit2,
+                    "3" => Code::Digit3,
+                    "4" => Code::Digit4,
+                    "5" => Code::Digit5,
+                    "6" => Code::Digit6,
+                    "7" => Code::Digit7,
+                    "8" => Code::Digit8,
+                    "9" => Code::Digit9,
+                    "0" => Code::Digit0,
+                    "F1" => Code::F1,
+                    "F2" => Code::F2,
+                    "F3" => Code::F3,
+                    "F4" => Code::F4,
+                    "F5" => Code::F5,
+                    "F6" => Code::F6,
+                    "F7" => Code::F7,
+                    "F8" => Code::F8,
+                    "F9" => Code::F9,
+                    "F10" => Code::F10,
+                    "F11" => Code::F11,
+                    "F12" => Code::F12,
+                    "SPACE" => Code::Space,
+                    "ENTER" => Code::Enter,
+                    "ESCAPE" => Code::Escape,
+                    "TAB" => Code::Tab,
+                    "DELETE" => Code::Delete,
+                    "BACKSPACE" => Code::Backspace,
+                    "INSERT" => Code::Insert,
+                    "HOME" => Code::Home,
+                    "END" => Code::End,
+                    "PAGEUP" => Code::PageUp,
+                    "PAGEDOWN" => Code::PageDown,
+                    "UP" => Code::ArrowUp,
+                    "DOWN" => Code::ArrowDown,
+                    "LEFT" => Code::ArrowLeft,
+                    "RIGHT" => Code::ArrowRight,
+                    _ => {
                         return Err(AppError::Validation {
                             field: "shortcut".to_string(),
-                            message: "Multiple key codes specified in shortcut".to_string(),
+                            message: format!("Unsupported key: {}", key),
                         });
                     }
-                    
-                    key_code = Some(match key.to_uppercase().as_str() {
-                        "A" => Code::KeyA,
-                        "B" => Code::KeyB,
-                        "C" => Code::KeyC,
-                        "D" => Code::KeyD,
-                        "E" => Code::KeyE,
-                        "F" => Code::KeyF,
-                        "G" => Code::KeyG,
-                        "H" => Code::KeyH,
-                        "I" => Code::KeyI,
-                        "J" => Code::KeyJ,
-                        "K" => Code::KeyK,
-                        "L" => Code::KeyL,
-                        "M" => Code::KeyM,
-                        "N" => Code::KeyN,
-                        "O" => Code::KeyO,
-                        "P" => Code::KeyP,
-                        "Q" => Code::KeyQ,
-                        "R" => Code::KeyR,
-                        "S" => Code::KeyS,
-                        "T" => Code::KeyT,
-                        "U" => Code::KeyU,
-                        "V" => Code::KeyV,
-                        "W" => Code::KeyW,
-                        "X" => Code::KeyX,
-                        "Y" => Code::KeyY,
-                        "Z" => Code::KeyZ,
-                        "1" => Code::Digit1,
-                        "2" => Code::Digit2,
-                        "3" => Code::Digit3,
-                        "4" => Code::Digit4,
-                        "5" => Code::Digit5,
-                        "6" => Code::Digit6,
-                        "7" => Code::Digit7,
-                        "8" => Code::Digit8,
-                        "9" => Code::Digit9,
-                        "0" => Code::Digit0,
-                        "F1" => Code::F1,
-                        "F2" => Code::F2,
-                        "F3" => Code::F3,
-                        "F4" => Code::F4,
-                        "F5" => Code::F5,
-                        "F6" => Code::F6,
-                        "F7" => Code::F7,
-                        "F8" => Code::F8,
-                        "F9" => Code::F9,
-                        "F10" => Code::F10,
-                        "F11" => Code::F11,
-                        "F12" => Code::F12,
-                        "SPACE" => Code::Space,
-                        "ENTER" => Code::Enter,
-                        "ESCAPE" => Code::Escape,
-                        "TAB" => Code::Tab,
-                        _ => {
-                            return Err(AppError::Validation {
-                                field: "shortcut".to_string(),
-                                message: format!("Unsupported key: {}", key),
-                            });
-                        }
-                    });
-                }
+                });
             }
         }
+    }
 
-        let key_code = key_code.ok_or_else(|| AppError::Validation {
-            field: "shortcut".to_string(),
-            message: "No key code specified in shortcut".to_string(),
-        })?;
+    let key_code = key_code.ok_or_else(|| AppError::Validation {
+        field: "shortcut".to_string(),
+        message: "No key code specified in shortcut".to_string(),
+    })?;
 
-        Ok(Shortcut::new(Some(modifiers), key_code))
-    }
-}
\ No newline at end of file
+    Ok(Shortcut::new(Some(modifiers), key_code))
+}
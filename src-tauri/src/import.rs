@@ -0,0 +1,373 @@
+// import.rs - Reading local files into new notes, and resolving conflicts with existing ones
+
+use crate::database::DbService;
+use crate::error::AppError;
+use crate::models::Note;
+use crate::validation::SecurityValidator;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Setting naming the directory notes may be imported from
+/// Empty (the default) disables importing from local files
+pub const IMPORT_BASE_DIR_SETTING_KEY: &str = "import.base_directory";
+
+/// Largest file accepted for import, matching the note content limit
+pub const MAX_IMPORT_FILE_BYTES: u64 = SecurityValidator::MAX_NOTE_CONTENT_LENGTH as u64;
+
+/// Content and nickname read from a local file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedFile {
+    pub content: String,
+    pub nickname: Option<String>,
+}
+
+/// Validate a path to import from, returning its canonical location
+///
+/// Accepts plain paths and `file://` URLs. The path must not contain traversal
+/// sequences, must carry an allowed extension, and must resolve (after following
+/// symlinks) to a regular file inside `base_dir`.
+pub fn validate_import_path(path: &str, base_dir: &Path, allowed_extensions: &[String]) -> Result<PathBuf, AppError> {
+    let invalid = |message: String| AppError::Validation {
+        field: "path".to_string(),
+        message,
+    };
+
+    let path = path.strip_prefix("file://").unwrap_or(path);
+    if path.trim().is_empty() {
+        return Err(invalid("Import path cannot be empty".to_string()));
+    }
+    if path.len() > SecurityValidator::MAX_PATH_LENGTH {
+        return Err(invalid("Path too long".to_string()));
+    }
+    if SecurityValidator::contains_path_traversal(path) {
+        return Err(invalid("Path traversal detected".to_string()));
+    }
+    SecurityValidator::validate_file_extension_against(Path::new(path), allowed_extensions)?;
+
+    let base = base_dir
+        .canonicalize()
+        .map_err(|e| invalid(format!("Invalid import directory: {}", e)))?;
+    // Relative paths are taken from the import directory; canonicalizing resolves symlinks
+    let resolved = base
+        .join(path)
+        .canonicalize()
+        .map_err(|e| invalid(format!("Cannot read import file: {}", e)))?;
+    if !resolved.starts_with(&base) {
+        return Err(invalid("Path escapes the import directory".to_string()));
+    }
+    if !resolved.is_file() {
+        return Err(invalid("Import path is not a file".to_string()));
+    }
+
+    Ok(resolved)
+}
+
+/// Read a validated local file for import
+/// The nickname is the file name without its extension
+pub fn read_import_file(path: &str, base_dir: &Path, allowed_extensions: &[String]) -> Result<ImportedFile, AppError> {
+    let resolved = validate_import_path(path, base_dir, allowed_extensions)?;
+
+    let size = std::fs::metadata(&resolved)?.len();
+    if size > MAX_IMPORT_FILE_BYTES {
+        return Err(AppError::Validation {
+            field: "path".to_string(),
+            message: format!("File is larger than the {} byte import limit", MAX_IMPORT_FILE_BYTES),
+        });
+    }
+
+    let bytes = std::fs::read(&resolved)?;
+    let content = String::from_utf8(bytes).map_err(|_| AppError::Validation {
+        field: "path".to_string(),
+        message: "Import file is not valid UTF-8 text".to_string(),
+    })?;
+    let nickname = match resolved.file_stem() {
+        Some(stem) => SecurityValidator::validate_nickname(&stem.to_string_lossy())?,
+        None => None,
+    };
+
+    Ok(ImportedFile { content, nickname })
+}
+
+/// What to do with an imported note that matches an existing one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    /// Keep the existing note and drop the imported one
+    Skip,
+    /// Replace the existing note's content with the imported one
+    Overwrite,
+    /// Import as a new note alongside the existing one
+    #[default]
+    Duplicate,
+    /// Overwrite when the imported note's `updated_at` is later, otherwise skip
+    NewerWins,
+}
+
+/// How imported notes are matched against existing ones
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMatchKey {
+    /// Identical content, compared by SHA-256 hash
+    #[default]
+    ContentHash,
+    /// Same nickname, ignoring case and surrounding whitespace
+    Nickname,
+}
+
+/// The outcome for one imported note
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportResolution {
+    /// No existing note matched; the note is created
+    Create,
+    /// A note matched and is kept as it is
+    Skip,
+    /// A note matched and takes the imported content
+    Overwrite,
+    /// A note matched and the imported note is created next to it
+    Duplicate,
+}
+
+/// How many imported notes fell into each resolution
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub created: usize,
+    pub skipped: usize,
+    pub overwritten: usize,
+    pub duplicated: usize,
+    /// Notes created, duplicated or overwritten, in import order
+    pub notes: Vec<Note>,
+}
+
+impl ImportSummary {
+    /// Count one resolution, keeping the note it wrote if any
+    pub fn record(&mut self, resolution: ImportResolution, note: Option<Note>) {
+        match resolution {
+            ImportResolution::Create => self.created += 1,
+            ImportResolution::Skip => self.skipped += 1,
+            ImportResolution::Overwrite => self.overwritten += 1,
+            ImportResolution::Duplicate => self.duplicated += 1,
+        }
+        self.notes.extend(note);
+    }
+}
+
+/// Hex SHA-256 of note content, the stable key for `ImportMatchKey::ContentHash`
+pub fn content_hash(content: &str) -> String {
+    Sha256::digest(content.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Existing notes indexed by an import match key
+///
+/// Encrypted notes are only matched by nickname, since their content is not readable.
+pub struct ImportMatcher {
+    key: ImportMatchKey,
+    notes: HashMap<String, Note>,
+}
+
+impl ImportMatcher {
+    /// Index every note currently in the database
+    pub async fn load(db: &DbService, key: ImportMatchKey) -> Result<Self, AppError> {
+        let mut matcher = Self { key, notes: HashMap::new() };
+        for note in db.get_all_notes().await? {
+            matcher.remember(&note);
+        }
+        Ok(matcher)
+    }
+
+    /// The existing note an import with this content and nickname matches
+    pub fn find(&self, content: &str, nickname: Option<&str>) -> Option<&Note> {
+        self.lookup_key(content, nickname).and_then(|key| self.notes.get(&key))
+    }
+
+    /// Index a note written during the import, so later entries match it too
+    /// The first note indexed under a key keeps it
+    pub fn remember(&mut self, note: &Note) {
+        if self.key == ImportMatchKey::ContentHash && note.is_encrypted {
+            return;
+        }
+        if let Some(key) = self.lookup_key(&note.content, note.nickname.as_deref()) {
+            self.notes.entry(key).or_insert_with(|| note.clone());
+        }
+    }
+
+    fn lookup_key(&self, content: &str, nickname: Option<&str>) -> Option<String> {
+        match self.key {
+            ImportMatchKey::ContentHash => Some(content_hash(content)),
+            ImportMatchKey::Nickname => nickname
+                .map(|nickname| nickname.trim().to_lowercase())
+                .filter(|nickname| !nickname.is_empty()),
+        }
+    }
+}
+
+/// Decide what happens to an imported note last updated at `incoming_updated_at`
+pub fn resolve_conflict(
+    strategy: ConflictStrategy,
+    existing: Option<&Note>,
+    incoming_updated_at: &str,
+) -> Result<ImportResolution, AppError> {
+    let Some(existing) = existing else {
+        return Ok(ImportResolution::Create);
+    };
+    Ok(match strategy {
+        ConflictStrategy::Skip => ImportResolution::Skip,
+        ConflictStrategy::Overwrite => ImportResolution::Overwrite,
+        ConflictStrategy::Duplicate => ImportResolution::Duplicate,
+        ConflictStrategy::NewerWins => {
+            let parse = |field: &str, value: &str| {
+                chrono::DateTime::parse_from_rfc3339(value).map_err(|_| AppError::Validation {
+                    field: field.to_string(),
+                    message: format!("Invalid timestamp '{}': expected RFC 3339", value),
+                })
+            };
+            if parse("updated_at", incoming_updated_at)? > parse("updated_at", &existing.updated_at)? {
+                ImportResolution::Overwrite
+            } else {
+                ImportResolution::Skip
+            }
+        }
+    })
+}
+
+/// Replace an existing note's content, nickname and format with an imported note's
+/// The existing note keeps its id, pin and history; the overwrite adds a version
+pub async fn overwrite_note(db: &DbService, existing: &Note, incoming: &Note) -> Result<Note, AppError> {
+    db.update_note(Note {
+        content: incoming.content.clone(),
+        nickname: incoming.nickname.clone().or_else(|| existing.nickname.clone()),
+        format: incoming.format.clone(),
+        ..existing.clone()
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::{import_archive, render_archive, ArchivedNote};
+    use crate::models::NoteFormat;
+    use tempfile::TempDir;
+
+    fn default_extensions() -> Vec<String> {
+        SecurityValidator::DEFAULT_EXPORT_EXTENSIONS.iter().map(|ext| ext.to_string()).collect()
+    }
+
+    #[test]
+    fn test_reads_text_file_with_nickname_from_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("meeting notes.txt");
+        std::fs::write(&file_path, "agenda for monday").unwrap();
+
+        let imported = read_import_file(&file_path.to_string_lossy(), temp_dir.path(), &default_extensions()).unwrap();
+        assert_eq!(imported.content, "agenda for monday");
+        assert_eq!(imported.nickname.as_deref(), Some("meeting notes"));
+
+        // Relative paths and file:// URLs resolve the same way
+        let relative = read_import_file("meeting notes.txt", temp_dir.path(), &default_extensions()).unwrap();
+        assert_eq!(relative, imported);
+        let url = format!("file://{}", file_path.display());
+        assert_eq!(read_import_file(&url, temp_dir.path(), &default_extensions()).unwrap(), imported);
+    }
+
+    #[test]
+    fn test_rejects_paths_outside_import_directory() {
+        let root = TempDir::new().unwrap();
+        let base = root.path().join("imports");
+        std::fs::create_dir(&base).unwrap();
+        let outside = root.path().join("secret.txt");
+        std::fs::write(&outside, "outside").unwrap();
+
+        assert!(read_import_file("../secret.txt", &base, &default_extensions()).is_err());
+        assert!(read_import_file(&outside.to_string_lossy(), &base, &default_extensions()).is_err());
+
+        // Symlinks may not point out of the import directory
+        #[cfg(unix)]
+        {
+            let link = base.join("link.txt");
+            std::os::unix::fs::symlink(&outside, &link).unwrap();
+            assert!(read_import_file("link.txt", &base, &default_extensions()).is_err());
+        }
+
+        // Extensions outside the allow-list are rejected
+        std::fs::write(base.join("script.sh"), "echo hi").unwrap();
+        assert!(read_import_file("script.sh", &base, &default_extensions()).is_err());
+    }
+
+    fn archived(content: &str, nickname: Option<&str>, updated_at: &str) -> ArchivedNote {
+        ArchivedNote {
+            note: Note {
+                id: 1,
+                content: content.to_string(),
+                created_at: "2020-01-01T00:00:00Z".to_string(),
+                updated_at: updated_at.to_string(),
+                is_favorite: false,
+                is_encrypted: false,
+                external_id: String::new(),
+                format: NoteFormat::PlainText,
+                nickname: nickname.map(str::to_string),
+                path: String::new(),
+            },
+            versions: Vec::new(),
+        }
+    }
+
+    /// A database holding "shopping list" (nickname "Groceries"), and an archive with
+    /// an older copy of it, a newer note under the same nickname, and a new note
+    async fn prepopulated() -> (TempDir, DbService, Note, String) {
+        let temp_dir = TempDir::new().unwrap();
+        let db = DbService::new(temp_dir.path().join("test.db")).unwrap();
+        let existing = db.create_note_with_nickname("shopping list".to_string(), Some("Groceries".to_string()), false).await.unwrap();
+        let text = render_archive(&[
+            archived("shopping list", None, "2020-01-02T00:00:00Z"),
+            archived("eggs and milk", Some("groceries"), "2999-01-01T00:00:00Z"),
+            archived("call the plumber", None, "2020-01-02T00:00:00Z"),
+        ], false).unwrap();
+        (temp_dir, db, existing, text)
+    }
+
+    #[tokio::test]
+    async fn test_skip_keeps_existing_notes() {
+        let (_dir, db, existing, text) = prepopulated().await;
+        let summary = import_archive(&db, &text, ConflictStrategy::Skip, ImportMatchKey::ContentHash).await.unwrap();
+        assert_eq!((summary.created, summary.skipped, summary.overwritten, summary.duplicated), (2, 1, 0, 0));
+        assert_eq!(db.get_all_notes().await.unwrap().len(), 3);
+        assert_eq!(db.get_note(existing.id).await.unwrap().unwrap().content, "shopping list");
+    }
+
+    #[tokio::test]
+    async fn test_overwrite_replaces_matched_note() {
+        let (_dir, db, existing, text) = prepopulated().await;
+        let summary = import_archive(&db, &text, ConflictStrategy::Overwrite, ImportMatchKey::Nickname).await.unwrap();
+        assert_eq!((summary.created, summary.skipped, summary.overwritten, summary.duplicated), (2, 0, 1, 0));
+        let overwritten = db.get_note(existing.id).await.unwrap().unwrap();
+        assert_eq!(overwritten.content, "eggs and milk");
+        assert_eq!(overwritten.nickname.as_deref(), Some("groceries"));
+        assert_eq!(db.get_all_notes().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_imports_alongside_matches() {
+        let (_dir, db, existing, text) = prepopulated().await;
+        let summary = import_archive(&db, &text, ConflictStrategy::Duplicate, ImportMatchKey::ContentHash).await.unwrap();
+        assert_eq!((summary.created, summary.skipped, summary.overwritten, summary.duplicated), (2, 0, 0, 1));
+        assert_eq!(summary.notes.len(), 3);
+        assert_eq!(db.get_all_notes().await.unwrap().len(), 4);
+        assert!(summary.notes.iter().all(|note| note.id != existing.id));
+    }
+
+    #[tokio::test]
+    async fn test_newer_wins_compares_updated_at() {
+        // By content the archived copy is older than the stored note and is skipped
+        let (_dir, db, existing, text) = prepopulated().await;
+        let summary = import_archive(&db, &text, ConflictStrategy::NewerWins, ImportMatchKey::ContentHash).await.unwrap();
+        assert_eq!((summary.created, summary.skipped, summary.overwritten, summary.duplicated), (2, 1, 0, 0));
+        assert_eq!(db.get_note(existing.id).await.unwrap().unwrap().content, "shopping list");
+
+        // By nickname the archived note is newer and replaces it
+        let (_dir, db, existing, text) = prepopulated().await;
+        let summary = import_archive(&db, &text, ConflictStrategy::NewerWins, ImportMatchKey::Nickname).await.unwrap();
+        assert_eq!((summary.created, summary.skipped, summary.overwritten, summary.duplicated), (2, 0, 1, 0));
+        assert_eq!(db.get_note(existing.id).await.unwrap().unwrap().content, "eggs and milk");
+    }
+}
@@ -8,13 +8,22 @@ use crate::validation::SecurityValidator;
 use crate::window_manager::WindowManager;
 use std::sync::Arc;
 use tauri::Manager;
-use tokio::sync::Mutex;
+use tokio::sync::RwLock;
 
+pub mod archive;
+pub mod capture;
 pub mod commands;
 pub mod database;
+pub mod diff;
+pub mod encryption;
 pub mod error;
+pub mod export;
 pub mod global_shortcut;
+pub mod import;
+pub mod links;
+pub mod logging;
 #[cfg(test)] pub mod ipc_tests;
+pub mod markdown;
 pub mod models;
 pub mod plugin;
 pub mod plugin_integration_test;
@@ -39,26 +48,49 @@ pub struct AppState {
     pub settings: Arc<SettingsService>,
     pub global_shortcut: Arc<GlobalShortcutService>,
     pub window_manager: Arc<WindowManager>,
-    pub plugin_manager: Arc<tokio::sync::Mutex<PluginManager>>,
+    pub plugin_manager: Arc<tokio::sync::RwLock<PluginManager>>,
     pub security_validator: Arc<SecurityValidator>,
     pub shutdown_manager: Arc<ShutdownManager>,
 }
 
 pub fn run() {
     tauri::Builder::default()
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| {
+                    if event.state() != tauri_plugin_global_shortcut::ShortcutState::Pressed {
+                        return;
+                    }
+                    let app_state = app.state::<AppState>().inner().clone();
+                    let shortcut = *shortcut;
+                    tauri::async_runtime::spawn(async move {
+                        if let Some(action) = app_state.global_shortcut.action_for(&shortcut).await {
+                            if let Err(e) = crate::commands::system::handle_shortcut_action(&app_state, action).await {
+                                log::warn!("Global shortcut action {:?} failed: {}", action, e);
+                            }
+                        }
+                    });
+                })
+                .build(),
+        )
         .setup(|app| {
+            let data_dir = app.path().app_data_dir()
+                .unwrap_or_else(|_| std::env::current_dir().unwrap());
+            
+            // Initialize logging first so startup problems reach the log file
+            if let Err(e) = crate::logging::init(&data_dir) {
+                eprintln!("Warning: Logging to file is unavailable: {}", e);
+            }
+            
             // Initialize database
-            let db_path = app.path().app_data_dir()
-                .unwrap_or_else(|_| std::env::current_dir().unwrap())
-                .join("scratch-pad.db");
+            let db_path = data_dir.join("scratch-pad.db");
             
             let db_service = Arc::new(DbService::new(&db_path).unwrap());
             
-            // Initialize security validator
-            let security_validator = Arc::new(SecurityValidator::new());
-            
             // Initialize search service
             let search_service = Arc::new(SearchService::new(db_service.clone()));
+            crate::performance::register_cache_metrics_provider(search_service.clone());
             
             // Initialize settings service
             let settings_service = Arc::new(SettingsService::new(db_service.clone()));
@@ -76,16 +108,174 @@ pub fn run() {
             ));
             
             // Initialize plugin manager
-            let plugin_manager = Arc::new(Mutex::new(PluginManager::new()));
+            let plugin_manager = Arc::new(RwLock::new(PluginManager::new()));
             
             // Initialize shutdown manager
             let mut shutdown_manager = ShutdownManager::new();
             shutdown_manager.set_app_handle(app.handle().clone());
             let shutdown_manager = Arc::new(shutdown_manager);
             
+            // Initialize security validator; it rejects writes once shutdown begins
+            // and limits IPC commands to the capabilities granted at startup
+            let granted_capabilities = crate::validation::OperationCapability::grant_from_env()?;
+            let security_validator = Arc::new(
+                SecurityValidator::with_shutdown_flag(shutdown_manager.get_shutdown_flag())
+                    .with_granted_capabilities(granted_capabilities)
+            );
+            
             // Initialize performance monitoring
             crate::performance::initialize_performance_monitoring();
             
+            // Reapply stored performance settings (budget preset, history capacity, metrics interval, alert thresholds,
+            // summary percentiles), the stored log level and the IPC payload limit
+            let performance_settings = settings_service.clone();
+            let payload_validator = security_validator.clone();
+            tauri::async_runtime::spawn(async move {
+                match performance_settings.get_setting(crate::logging::LOG_LEVEL_SETTING_KEY).await {
+                    Ok(level) => crate::logging::apply_level_setting(level.as_deref()),
+                    Err(e) => log::warn!("Failed to restore log level: {}", e),
+                }
+                match performance_settings.get_setting(crate::validation::IPC_PAYLOAD_LIMIT_SETTING_KEY).await {
+                    Ok(limit) => if let Err(e) = payload_validator.apply_payload_limit_setting(limit.as_deref()) {
+                        log::warn!("Ignoring invalid IPC payload limit: {}", e);
+                    },
+                    Err(e) => log::warn!("Failed to restore IPC payload limit: {}", e),
+                }
+                if let Err(e) = crate::performance::restore_budget_preset(&performance_settings).await {
+                    log::warn!("Failed to restore performance budget preset: {}", e);
+                }
+                if let Err(e) = crate::performance::restore_history_capacity(&performance_settings).await {
+                    log::warn!("Failed to restore performance history capacity: {}", e);
+                }
+                if let Err(e) = crate::performance::restore_metrics_interval(&performance_settings).await {
+                    log::warn!("Failed to restore metrics collection interval: {}", e);
+                }
+                if let Err(e) = crate::performance::restore_system_thresholds(&performance_settings).await {
+                    log::warn!("Failed to restore system alert thresholds: {}", e);
+                }
+                if let Err(e) = crate::performance::restore_summary_percentiles(&performance_settings).await {
+                    log::warn!("Failed to restore summary percentiles: {}", e);
+                }
+            });
+            
+            // Periodically flush staged drafts; drafts left over from a previous
+            // session are not flushed so they can be offered for recovery. The final
+            // flush on quit runs as a shutdown step
+            let draft_db = db_service.clone();
+            let draft_settings = settings_service.clone();
+            let draft_handle = app.handle().clone();
+            let draft_shutdown_flag = shutdown_manager.get_shutdown_flag();
+            let session_started_at = shutdown_manager.session_started_at().to_string();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let interval_secs = draft_settings
+                        .get_int_setting(crate::database::DRAFT_FLUSH_INTERVAL_SETTING_KEY)
+                        .await
+                        .ok()
+                        .flatten()
+                        .filter(|secs| *secs > 0)
+                        .map(|secs| secs as u64)
+                        .unwrap_or(crate::database::DEFAULT_DRAFT_FLUSH_INTERVAL_SECS);
+                    tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
+                    
+                    match draft_db.flush_drafts(Some(&session_started_at)).await {
+                        Ok(flushed) => crate::commands::notes::emit_notes_changed(
+                            &draft_handle,
+                            crate::commands::notes::NoteChangeKind::Updated,
+                            flushed,
+                        ),
+                        Err(e) => log::warn!("Failed to flush staged drafts: {}", e),
+                    }
+                    if draft_shutdown_flag.load(std::sync::atomic::Ordering::Relaxed) {
+                        break;
+                    }
+                }
+            });
+            
+            // Periodically purge notes that have been in the trash past the retention period
+            let purge_db = db_service.clone();
+            let purge_shutdown_flag = shutdown_manager.get_shutdown_flag();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match purge_db.trash_retention_days().await {
+                        Ok(retention_days) => {
+                            if let Err(e) = purge_db.purge_deleted_notes(retention_days).await {
+                                log::warn!("Failed to purge trashed notes: {}", e);
+                            }
+                        }
+                        Err(e) => log::warn!("Skipping trash purge: {}", e),
+                    }
+                    tokio::time::sleep(std::time::Duration::from_secs(crate::database::TRASH_PURGE_INTERVAL_SECS)).await;
+                    if purge_shutdown_flag.load(std::sync::atomic::Ordering::Relaxed) {
+                        break;
+                    }
+                }
+            });
+            
+            // Periodically pin frequently opened notes when auto-pin is enabled
+            let auto_pin_db = db_service.clone();
+            let auto_pin_handle = app.handle().clone();
+            let auto_pin_shutdown_flag = shutdown_manager.get_shutdown_flag();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    tokio::time::sleep(std::time::Duration::from_secs(crate::database::AUTO_PIN_INTERVAL_SECS)).await;
+                    if auto_pin_shutdown_flag.load(std::sync::atomic::Ordering::Relaxed) {
+                        break;
+                    }
+                    match auto_pin_db.auto_pin_enabled().await {
+                        Ok(true) => match auto_pin_db.apply_auto_pin().await {
+                            Ok(pinned) => crate::commands::notes::emit_notes_changed(
+                                &auto_pin_handle,
+                                crate::commands::notes::NoteChangeKind::Pinned,
+                                pinned,
+                            ),
+                            Err(e) => log::warn!("Failed to auto-pin notes: {}", e),
+                        },
+                        Ok(false) => {}
+                        Err(e) => log::warn!("Skipping auto-pin: {}", e),
+                    }
+                }
+            });
+            
+            // Periodically write the performance analytics report when scheduled exports are enabled;
+            // settings are re-read each cycle so changes apply without a restart
+            let report_settings = settings_service.clone();
+            let report_shutdown_flag = shutdown_manager.get_shutdown_flag();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let interval = match crate::performance::load_report_export_schedule(&report_settings).await {
+                        Ok(Some(schedule)) => {
+                            match crate::performance::analytics::get_analytics_engine().generate_report(None).await {
+                                Ok(report) => {
+                                    if let Err(e) = crate::performance::write_report_snapshot(&report, &schedule.directory, schedule.keep, chrono::Utc::now()) {
+                                        log::warn!("Failed to write performance report: {}", e);
+                                    }
+                                }
+                                Err(e) => log::warn!("Failed to generate performance report: {}", e),
+                            }
+                            schedule.interval
+                        }
+                        Ok(None) => std::time::Duration::from_secs(crate::performance::DEFAULT_REPORT_EXPORT_INTERVAL_SECS),
+                        Err(e) => {
+                            log::warn!("Skipping scheduled performance report: {}", e);
+                            std::time::Duration::from_secs(crate::performance::DEFAULT_REPORT_EXPORT_INTERVAL_SECS)
+                        }
+                    };
+                    tokio::time::sleep(interval).await;
+                    if report_shutdown_flag.load(std::sync::atomic::Ordering::Relaxed) {
+                        break;
+                    }
+                }
+            });
+            
+            // Register the configured global shortcuts
+            let startup_shortcuts = global_shortcut.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = startup_shortcuts.initialize().await {
+                    log::warn!("Failed to register global shortcuts: {}", e);
+                }
+            });
+            
             // Compose app state
             let app_state = AppState {
                 db: db_service,
@@ -101,6 +291,27 @@ pub fn run() {
             // Install the app state
             app.manage(app_state);
             
+            // Serve later launches: show the window and capture the content they were given
+            let handoff_handle = app.handle().clone();
+            let handoff_started = crate::cli::start_handoff_listener(move |message| {
+                let app_state = handoff_handle.state::<AppState>().inner().clone();
+                tauri::async_runtime::block_on(async {
+                    if let Some(content) = &message.content {
+                        let note = crate::cli::capture_content(&app_state.db, content, message.append_daily).await?;
+                        let kind = if message.append_daily {
+                            crate::commands::notes::NoteChangeKind::Updated
+                        } else {
+                            crate::commands::notes::NoteChangeKind::Created
+                        };
+                        crate::commands::notes::emit_notes_changed(&handoff_handle, kind, vec![note.id]);
+                    }
+                    app_state.window_manager.show_window().await
+                })
+            });
+            if let Err(e) = handoff_started {
+                log::warn!("Failed to listen for handoffs from later launches: {}", e);
+            }
+            
             Ok(())
         })
         .invoke_handler(crate::generate_command_handler!())
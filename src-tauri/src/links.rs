@@ -0,0 +1,69 @@
+// links.rs - [[wiki-link]] references between notes
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// Most distinct links read from one note; later ones are ignored
+pub const MAX_LINKS_PER_NOTE: usize = 200;
+
+/// Most candidate ids reported for an ambiguous link
+pub const MAX_AMBIGUOUS_CANDIDATES: usize = 10;
+
+/// How a link target resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkStatus {
+    /// Exactly one note has the nickname
+    Resolved,
+    /// No note outside the trash has the nickname
+    Missing,
+    /// Several notes share the nickname; see `candidates`
+    Ambiguous,
+    /// The target is not a valid nickname
+    Invalid,
+}
+
+/// A `[[target]]` found in a note and what it points at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoteLink {
+    /// The target as written, trimmed
+    pub target: String,
+    pub status: LinkStatus,
+    /// The linked note, when `status` is `resolved`
+    pub note_id: Option<i64>,
+    /// Ids of the notes sharing the nickname, when `status` is `ambiguous`
+    pub candidates: Vec<i64>,
+}
+
+fn link_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\[\[([^\[\]\n]+)\]\]").unwrap())
+}
+
+/// Distinct `[[target]]` targets of `content` in order of first appearance
+///
+/// Targets are trimmed and compared case-insensitively, like nicknames; empty
+/// targets are skipped and at most `MAX_LINKS_PER_NOTE` are returned.
+pub fn extract_wiki_links(content: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    link_pattern()
+        .captures_iter(content)
+        .map(|captures| captures[1].trim().to_string())
+        .filter(|target| !target.is_empty() && seen.insert(target.to_lowercase()))
+        .take(MAX_LINKS_PER_NOTE)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_wiki_links_dedupes_and_skips_empty_targets() {
+        let content = "See [[Reading List]] and [[ reading list ]], then [[Ideas]].\n[[ ]] [[broken\n]] [single]";
+        assert_eq!(extract_wiki_links(content), vec!["Reading List".to_string(), "Ideas".to_string()]);
+        assert!(extract_wiki_links("no links here").is_empty());
+    }
+}
@@ -0,0 +1,222 @@
+// logging.rs - Leveled logging to a size-rotated file in the app data directory
+
+use crate::error::AppError;
+use log::{LevelFilter, Log, Metadata, Record};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Setting holding the log level: off, error, warn, info, debug or trace
+pub const LOG_LEVEL_SETTING_KEY: &str = "logging.level";
+
+/// Environment variable overriding `logging.level`, for debugging a single run
+pub const LOG_LEVEL_ENV_VAR: &str = "SCRATCH_PAD_LOG";
+
+/// Level used when neither the environment nor the setting names a valid one
+pub const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Info;
+
+/// Name of the active log file; rotated files get a `.1`, `.2`, ... suffix
+pub const LOG_FILE_NAME: &str = "scratch-pad.log";
+
+/// Size at which the active log file is rotated
+pub const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated log files kept besides the active one
+pub const MAX_ROTATED_LOG_FILES: usize = 3;
+
+/// Most lines `read_recent_lines` returns
+pub const MAX_RECENT_LOG_LINES: usize = 1000;
+
+static LOGGER: OnceLock<FileLogger> = OnceLock::new();
+
+/// Parse a log level name, case-insensitively
+pub fn parse_level(value: &str) -> Option<LevelFilter> {
+    match value.trim().to_lowercase().as_str() {
+        "off" => Some(LevelFilter::Off),
+        "error" => Some(LevelFilter::Error),
+        "warn" | "warning" => Some(LevelFilter::Warn),
+        "info" => Some(LevelFilter::Info),
+        "debug" => Some(LevelFilter::Debug),
+        "trace" => Some(LevelFilter::Trace),
+        _ => None,
+    }
+}
+
+/// The level to log at: a valid environment override wins over the setting
+pub fn resolve_level(env: Option<&str>, setting: Option<&str>) -> LevelFilter {
+    env.and_then(parse_level)
+        .or_else(|| setting.and_then(parse_level))
+        .unwrap_or(DEFAULT_LOG_LEVEL)
+}
+
+/// Start logging to `LOG_FILE_NAME` in `dir`, returning the log file path
+///
+/// Records are also echoed to stderr. The level comes from `LOG_LEVEL_ENV_VAR`
+/// until `apply_level_setting` is called with the stored setting.
+pub fn init(dir: &Path) -> Result<PathBuf, AppError> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(LOG_FILE_NAME);
+    let file = LogFile::open(path.clone())?;
+
+    let logger = LOGGER.get_or_init(|| FileLogger { file: Mutex::new(file) });
+    log::set_logger(logger).map_err(|e| AppError::General(format!("Logging already initialized: {}", e)))?;
+    log::set_max_level(resolve_level(std::env::var(LOG_LEVEL_ENV_VAR).ok().as_deref(), None));
+
+    Ok(path)
+}
+
+/// Apply the stored `logging.level` value; the environment override still wins
+pub fn apply_level_setting(setting: Option<&str>) {
+    log::set_max_level(resolve_level(std::env::var(LOG_LEVEL_ENV_VAR).ok().as_deref(), setting));
+}
+
+/// Path of the active log file, once `init` has run
+pub fn log_file_path() -> Option<PathBuf> {
+    let logger = LOGGER.get()?;
+    let file = logger.file.lock().ok()?;
+    Some(file.path.clone())
+}
+
+/// The last `lines` lines logged, oldest first
+///
+/// Reads back into the most recent rotated file when the active one is shorter.
+pub fn read_recent_lines(path: &Path, lines: usize) -> Result<Vec<String>, AppError> {
+    let lines = lines.min(MAX_RECENT_LOG_LINES);
+    let mut recent: Vec<String> = Vec::new();
+    for candidate in std::iter::once(path.to_path_buf()).chain(std::iter::once(rotated_path(path, 1))) {
+        if recent.len() >= lines {
+            break;
+        }
+        let text = match std::fs::read_to_string(&candidate) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let older: Vec<String> = text.lines().rev().take(lines - recent.len()).map(str::to_string).collect();
+        recent.extend(older);
+    }
+    recent.reverse();
+    Ok(recent)
+}
+
+/// Path of the `index`th rotated log file
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+/// The active log file and how much has been written to it
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    len: u64,
+    max_bytes: u64,
+}
+
+impl LogFile {
+    fn open(path: PathBuf) -> Result<Self, AppError> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let len = file.metadata()?.len();
+        Ok(Self { path, file, len, max_bytes: MAX_LOG_FILE_BYTES })
+    }
+
+    /// Append a line, rotating first if it would take the file past its limit
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.len > 0 && self.len + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.len += line.len() as u64;
+        Ok(())
+    }
+
+    /// Shift `.1`..`.N-1` up by one, dropping the oldest, and start a fresh file
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        for index in (1..MAX_ROTATED_LOG_FILES).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                std::fs::rename(&from, rotated_path(&self.path, index + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+struct FileLogger {
+    file: Mutex<LogFile>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!(
+            "{} {:<5} {}: {}\n",
+            crate::database::current_timestamp(),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        eprint!("{}", line);
+        // A failing log file must never take the app down with it
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.write_line(&line);
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_level_resolution_prefers_valid_env_override() {
+        assert_eq!(resolve_level(Some("debug"), Some("error")), LevelFilter::Debug);
+        assert_eq!(resolve_level(Some("loud"), Some("WARN")), LevelFilter::Warn);
+        assert_eq!(resolve_level(None, Some("off")), LevelFilter::Off);
+        assert_eq!(resolve_level(None, None), DEFAULT_LOG_LEVEL);
+        assert_eq!(parse_level("verbose"), None);
+    }
+
+    #[test]
+    fn test_rotation_keeps_recent_lines_readable() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(LOG_FILE_NAME);
+        let mut file = LogFile::open(path.clone()).unwrap();
+        file.max_bytes = 64;
+
+        for i in 0..20 {
+            file.write_line(&format!("line {:02} of the test log\n", i)).unwrap();
+        }
+        assert!(rotated_path(&path, 1).exists());
+        assert!(!rotated_path(&path, MAX_ROTATED_LOG_FILES + 1).exists());
+
+        let recent = read_recent_lines(&path, 3).unwrap();
+        assert_eq!(recent, vec![
+            "line 17 of the test log".to_string(),
+            "line 18 of the test log".to_string(),
+            "line 19 of the test log".to_string(),
+        ]);
+        let spanning = read_recent_lines(&path, 4).unwrap();
+        assert_eq!(spanning.first().map(String::as_str), Some("line 16 of the test log"));
+        assert!(read_recent_lines(&temp_dir.path().join("missing.log"), 5).unwrap().is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use scratch_pad_lib::cli::{parse_cli_args, handle_cli_args, create_lock_file, cleanup_lock_file};
+use scratch_pad_lib::cli::{parse_cli_args, handle_cli_args, create_lock_file, cleanup_lock_file, send_handoff, HandoffReply};
 use scratch_pad_lib::database::DbService;
 use scratch_pad_lib::error::AppError;
 use std::process;
@@ -10,6 +10,17 @@ fn main() {
     // Parse CLI arguments first
     let cli_args = parse_cli_args();
     
+    // Hand off to a running instance when there is one; it shows its window and
+    // captures any content. Otherwise carry on as the only instance.
+    match send_handoff(&cli_args) {
+        Ok(HandoffReply::Accepted) => return,
+        Ok(HandoffReply::Rejected(message)) => {
+            eprintln!("Error: {}", message);
+            process::exit(1);
+        }
+        Err(_) => {}
+    }
+    
     // If we have content to create a note, handle it in CLI mode
     if cli_args.content.is_some() {
         if let Err(e) = handle_cli_mode(cli_args) {
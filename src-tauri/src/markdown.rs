@@ -0,0 +1,333 @@
+// markdown.rs - Markdown helpers for rendering note content outside the editor
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+struct InlinePatterns {
+    image: Regex,
+    link: Regex,
+    code: Regex,
+    bold: Regex,
+    italic: Regex,
+    strikethrough: Regex,
+}
+
+fn inline_patterns() -> &'static InlinePatterns {
+    static PATTERNS: OnceLock<InlinePatterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| InlinePatterns {
+        image: Regex::new(r"!\[([^\]]*)\]\(([^)]*)\)").unwrap(),
+        link: Regex::new(r"\[([^\]]+)\]\(([^)]*)\)").unwrap(),
+        code: Regex::new(r"`([^`]+)`").unwrap(),
+        bold: Regex::new(r"(\*\*|__)(.+?)(\*\*|__)").unwrap(),
+        italic: Regex::new(r"(^|[^\w*])[*_]([^*_\s][^*_]*?)[*_]").unwrap(),
+        strikethrough: Regex::new(r"~~(.+?)~~").unwrap(),
+    })
+}
+
+/// Render Markdown as plain text by stripping formatting syntax
+///
+/// Headings, emphasis, inline code, links, images, blockquotes and horizontal
+/// rules are reduced to their text; fenced code blocks keep their contents.
+/// List markers are kept so list structure stays readable.
+pub fn to_plain_text(markdown: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(line.to_string());
+            continue;
+        }
+        if is_horizontal_rule(trimmed) {
+            continue;
+        }
+
+        let mut text = trimmed;
+        while let Some(rest) = text.strip_prefix('>') {
+            text = rest.trim_start();
+        }
+        if text.starts_with('#') {
+            text = text.trim_start_matches('#').trim_start();
+        }
+
+        lines.push(strip_inline(text));
+    }
+
+    lines.join("\n").trim().to_string()
+}
+
+/// Render Markdown as an HTML fragment
+///
+/// Covers headings, paragraphs, flat lists, blockquotes, fenced code blocks,
+/// horizontal rules, emphasis, inline code, links and images. All note text is
+/// HTML-escaped, so raw HTML in the note is shown rather than interpreted, and
+/// link targets other than http(s), mailto and relative ones are dropped.
+pub fn to_html(markdown: &str) -> String {
+    let mut html = String::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut list: Option<(&str, Vec<String>)> = None;
+    let mut quote: Vec<String> = Vec::new();
+    let mut code_block: Option<Vec<String>> = None;
+
+    fn flush(html: &mut String, paragraph: &mut Vec<String>, list: &mut Option<(&str, Vec<String>)>, quote: &mut Vec<String>) {
+        if !paragraph.is_empty() {
+            html.push_str(&format!("<p>{}</p>\n", paragraph.join("\n")));
+            paragraph.clear();
+        }
+        if let Some((tag, items)) = list.take() {
+            let items: String = items.iter().map(|item| format!("<li>{}</li>", item)).collect();
+            html.push_str(&format!("<{tag}>{}</{tag}>\n", items));
+        }
+        if !quote.is_empty() {
+            html.push_str(&format!("<blockquote><p>{}</p></blockquote>\n", quote.join("\n")));
+            quote.clear();
+        }
+    }
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            match code_block.take() {
+                Some(code) => html.push_str(&format!("<pre><code>{}</code></pre>\n", escape_html(&code.join("\n")))),
+                None => {
+                    flush(&mut html, &mut paragraph, &mut list, &mut quote);
+                    code_block = Some(Vec::new());
+                }
+            }
+            continue;
+        }
+        if let Some(code) = code_block.as_mut() {
+            code.push(line.to_string());
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            flush(&mut html, &mut paragraph, &mut list, &mut quote);
+        } else if is_horizontal_rule(trimmed) {
+            flush(&mut html, &mut paragraph, &mut list, &mut quote);
+            html.push_str("<hr>\n");
+        } else if let Some(rest) = trimmed.strip_prefix('>') {
+            if !paragraph.is_empty() || list.is_some() {
+                flush(&mut html, &mut paragraph, &mut list, &mut quote);
+            }
+            quote.push(inline_html(rest.trim_start()));
+        } else if trimmed.starts_with('#') {
+            flush(&mut html, &mut paragraph, &mut list, &mut quote);
+            let level = trimmed.chars().take_while(|c| *c == '#').count().min(6);
+            let text = trimmed.trim_start_matches('#').trim();
+            html.push_str(&format!("<h{level}>{}</h{level}>\n", inline_html(text)));
+        } else if let Some((tag, item)) = list_item(trimmed) {
+            if list.as_ref().is_some_and(|(current, _)| *current != tag) || !paragraph.is_empty() || !quote.is_empty() {
+                flush(&mut html, &mut paragraph, &mut list, &mut quote);
+            }
+            list.get_or_insert((tag, Vec::new())).1.push(inline_html(item));
+        } else {
+            if list.is_some() || !quote.is_empty() {
+                flush(&mut html, &mut paragraph, &mut list, &mut quote);
+            }
+            paragraph.push(inline_html(trimmed));
+        }
+    }
+
+    if let Some(code) = code_block {
+        html.push_str(&format!("<pre><code>{}</code></pre>\n", escape_html(&code.join("\n"))));
+    }
+    flush(&mut html, &mut paragraph, &mut list, &mut quote);
+    html
+}
+
+/// Escape text for use in HTML element content or attribute values
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Split a list line into its list tag and item text
+fn list_item(line: &str) -> Option<(&'static str, &str)> {
+    if let Some(item) = line.strip_prefix(['-', '*', '+']).and_then(|rest| rest.strip_prefix(' ')) {
+        return Some(("ul", item.trim_start()));
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 {
+        let item = line[digits..].strip_prefix(['.', ')']).and_then(|rest| rest.strip_prefix(' '))?;
+        return Some(("ol", item.trim_start()));
+    }
+    None
+}
+
+/// Link targets rendered as-is: web, mail and relative URLs; anything else becomes `#`
+fn safe_link_target(url: &str) -> &str {
+    let lower = url.trim().to_lowercase();
+    let has_scheme = lower.find(':').is_some_and(|colon| !lower[..colon].contains(['/', '?', '#']));
+    if !has_scheme || lower.starts_with("http://") || lower.starts_with("https://") || lower.starts_with("mailto:") {
+        url.trim()
+    } else {
+        "#"
+    }
+}
+
+/// Render inline Markdown in a single line as HTML; code spans are left unformatted
+fn inline_html(text: &str) -> String {
+    let patterns = inline_patterns();
+    let mut html = String::new();
+    let mut last = 0;
+    for code in patterns.code.captures_iter(text) {
+        let span = code.get(0).unwrap();
+        html.push_str(&format_inline_html(&text[last..span.start()]));
+        html.push_str(&format!("<code>{}</code>", escape_html(&code[1])));
+        last = span.end();
+    }
+    html.push_str(&format_inline_html(&text[last..]));
+    html
+}
+
+/// Inline formatting other than code spans, for `inline_html`
+fn format_inline_html(text: &str) -> String {
+    let patterns = inline_patterns();
+    let text = escape_html(text);
+    let text = patterns.image.replace_all(&text, |caps: &regex::Captures| {
+        format!("<img src=\"{}\" alt=\"{}\">", safe_link_target(&caps[2]), &caps[1])
+    });
+    let text = patterns.link.replace_all(&text, |caps: &regex::Captures| {
+        format!("<a href=\"{}\">{}</a>", safe_link_target(&caps[2]), &caps[1])
+    });
+    let text = patterns.bold.replace_all(&text, "<strong>$2</strong>");
+    let text = patterns.strikethrough.replace_all(&text, "<del>$1</del>");
+    let text = patterns.italic.replace_all(&text, "$1<em>$2</em>");
+    text.into_owned()
+}
+
+/// Escape text so Markdown renders it literally
+///
+/// Inline syntax characters are backslash-escaped everywhere; heading, quote
+/// and list markers only at the start of a line, where they carry meaning.
+pub fn escape_markdown(text: &str) -> String {
+    text.split('\n')
+        .map(escape_line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Escape a single line for `escape_markdown`
+fn escape_line(line: &str) -> String {
+    let indent_len = line.len() - line.trim_start().len();
+    let (indent, rest) = line.split_at(indent_len);
+    let mut escaped = String::with_capacity(line.len() + 8);
+    escaped.push_str(indent);
+
+    // Block markers: headings, quotes, bullets and ordered list numbers
+    let digits = rest.chars().take_while(|c| c.is_ascii_digit()).count();
+    let rest = if rest.starts_with(['#', '>', '-', '+']) {
+        escaped.push('\\');
+        rest
+    } else if digits > 0 && rest[digits..].starts_with(['.', ')']) {
+        escaped.push_str(&rest[..digits]);
+        escaped.push('\\');
+        &rest[digits..]
+    } else {
+        rest
+    };
+
+    for c in rest.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '~') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Strip inline Markdown syntax from a single line
+fn strip_inline(text: &str) -> String {
+    let patterns = inline_patterns();
+    let text = patterns.image.replace_all(text, "$1");
+    let text = patterns.link.replace_all(&text, "$1");
+    let text = patterns.code.replace_all(&text, "$1");
+    let text = patterns.bold.replace_all(&text, "$2");
+    let text = patterns.strikethrough.replace_all(&text, "$1");
+    let text = patterns.italic.replace_all(&text, "$1$2");
+    text.into_owned()
+}
+
+/// A line made only of three or more `-`, `*` or `_` characters (spaces allowed)
+fn is_horizontal_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|marker| compact.chars().all(|c| c == *marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_block_formatting() {
+        let markdown = "# Title\n\n> Quoted **bold** text\n\n---\n\n- item one\n- item two";
+        assert_eq!(
+            to_plain_text(markdown),
+            "Title\n\nQuoted bold text\n\n\n- item one\n- item two"
+        );
+    }
+
+    #[test]
+    fn test_strips_inline_formatting() {
+        assert_eq!(
+            to_plain_text("Use `cargo test`, see [the docs](https://example.com) and ![logo](logo.png)"),
+            "Use cargo test, see the docs and logo"
+        );
+        assert_eq!(to_plain_text("*italic*, _also_, __bold__ and ~~gone~~"), "italic, also, bold and gone");
+        // Identifiers with underscores are left alone
+        assert_eq!(to_plain_text("call snake_case_name"), "call snake_case_name");
+    }
+
+    #[test]
+    fn test_escapes_markdown_syntax() {
+        assert_eq!(
+            escape_markdown("# not a heading with *stars* and [brackets]"),
+            "\\# not a heading with \\*stars\\* and \\[brackets\\]"
+        );
+        assert_eq!(escape_markdown("  - item\n1. first\nsnake_case"), "  \\- item\n1\\. first\nsnake\\_case");
+        // Markers are only special at the start of a line
+        assert_eq!(escape_markdown("a - b + c # d 2. e"), "a - b + c # d 2. e");
+    }
+
+    #[test]
+    fn test_renders_html() {
+        let markdown = "# Title\n\nSome **bold** and `<b>code</b>`\nsecond line\n\n- one\n- two\n\n> quoted\n\n```\nlet x = 1 < 2;\n```";
+        assert_eq!(
+            to_html(markdown),
+            "<h1>Title</h1>\n<p>Some <strong>bold</strong> and <code>&lt;b&gt;code&lt;/b&gt;</code>\nsecond line</p>\n\
+             <ul><li>one</li><li>two</li></ul>\n<blockquote><p>quoted</p></blockquote>\n\
+             <pre><code>let x = 1 &lt; 2;</code></pre>\n"
+        );
+        assert_eq!(
+            to_html("[site](https://example.com) [bad](javascript:alert(1)) <i onclick=\"x\">raw</i>"),
+            "<p><a href=\"https://example.com\">site</a> <a href=\"#\">bad</a>) &lt;i onclick=&quot;x&quot;&gt;raw&lt;/i&gt;</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_keeps_code_block_contents() {
+        let markdown = "Example:\n```rust\nlet x = **not bold**;\n```";
+        assert_eq!(to_plain_text(markdown), "Example:\nlet x = **not bold**;");
+    }
+}
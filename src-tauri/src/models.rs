@@ -11,6 +11,12 @@ pub struct Note {
     pub path: String,
     #[serde(alias = "is_pinned")] // Allow both names for backward compatibility
     pub is_favorite: bool,  // Changed back to is_favorite to match integration tests
+    /// Content is stored encrypted; it reads as `ENCRYPTED_NOTE_PLACEHOLDER` unless unlocked
+    #[serde(default)]
+    pub is_encrypted: bool,
+    /// Stable UUID for external tools; kept by archive export and import, unlike `id`
+    #[serde(default)]
+    pub external_id: String,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -33,12 +39,248 @@ impl fmt::Display for NoteFormat {
     }
 }
 
+impl NoteFormat {
+    /// Value stored in the `format` column
+    pub const fn as_db_value(&self) -> &'static str {
+        match self {
+            NoteFormat::PlainText => "plaintext",
+            NoteFormat::Markdown => "markdown",
+        }
+    }
+
+    /// Parse a format name as stored and serialized, e.g. for the `notes.default_format` setting
+    pub fn from_name(value: &str) -> Option<Self> {
+        match value {
+            "plaintext" => Some(NoteFormat::PlainText),
+            "markdown" => Some(NoteFormat::Markdown),
+            _ => None,
+        }
+    }
+
+    /// Parse a stored `format` column value; legacy or unknown values are plain text
+    pub fn from_db_value(value: Option<&str>) -> Self {
+        match value {
+            Some("markdown") => NoteFormat::Markdown,
+            _ => NoteFormat::PlainText,
+        }
+    }
+}
+
 impl Default for NoteFormat {
     fn default() -> Self {
         NoteFormat::PlainText
     }
 }
 
+/// How a note's `path` is generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NotePathScheme {
+    /// `/note/<id>`
+    #[default]
+    Id,
+    /// `/note/<id>/<nickname-slug>`, falling back to `/note/<id>` without a nickname
+    Slug,
+}
+
+impl NotePathScheme {
+    /// Parse the `notes.path_scheme` setting value
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value {
+            "id" => Some(NotePathScheme::Id),
+            "slug" => Some(NotePathScheme::Slug),
+            _ => None,
+        }
+    }
+
+    /// Path of the note with the given id and nickname under this scheme
+    pub fn note_path(&self, id: i64, nickname: Option<&str>) -> String {
+        let slug = match self {
+            NotePathScheme::Id => None,
+            NotePathScheme::Slug => nickname.and_then(crate::validation::SecurityValidator::url_slug),
+        };
+        match slug {
+            Some(slug) => format!("/note/{}/{}", id, slug),
+            None => format!("/note/{}", id),
+        }
+    }
+
+    /// Note id named by a path in any scheme; the slug, if present, is not checked
+    pub fn parse_note_id(path: &str) -> Option<i64> {
+        let rest = path.strip_prefix("/note/")?;
+        let (id, slug) = match rest.split_once('/') {
+            Some((id, slug)) => (id, Some(slug)),
+            None => (rest, None),
+        };
+        if slug.is_some_and(|slug| slug.is_empty() || slug.contains('/')) {
+            return None;
+        }
+        id.parse().ok().filter(|id| *id > 0)
+    }
+}
+
+/// A note together with advisory metadata computed when it is read
+/// Serializes as the note's own fields plus the metadata
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NoteDetails {
+    #[serde(flatten)]
+    pub note: Note,
+    /// Content is longer than `notes.soft_char_limit`; never blocks saving
+    pub over_soft_limit: bool,
+}
+
+/// A note's metadata and the start of its content, for listing notes without their bodies
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NotePreview {
+    pub id: i64,
+    pub format: NoteFormat,
+    pub nickname: Option<String>,
+    pub path: String,
+    #[serde(alias = "is_pinned")]
+    pub is_favorite: bool,
+    pub is_archived: bool,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Leading content on one line; Markdown notes have their syntax stripped
+    pub preview: String,
+    /// The content continues past the preview
+    pub truncated: bool,
+}
+
+/// Token that takes a just-trashed note back out of the trash until `expires_at`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UndoToken {
+    pub token: String,
+    pub note_id: i64,
+    pub expires_at: String,
+}
+
+/// A staged, not yet flushed edit to a note
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NoteDraft {
+    pub note_id: i64,
+    pub content: String,
+    pub staged_at: String,
+}
+
+/// A stored revision of a note's content
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NoteVersion {
+    pub note_id: i64,
+    pub version: i64,
+    pub content: String,
+    pub created_at: String,
+}
+
+/// Note totals by state, for dashboard summaries
+/// Pins and favorites share the same flag, so `pinned` and `favorite` always agree
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct NoteCounts {
+    pub total: i64,
+    pub pinned: i64,
+    pub favorite: i64,
+    pub archived: i64,
+    /// Notes in the trash; these are excluded from the other counts
+    pub trashed: i64,
+}
+
+/// Ordering for `NoteFilter` results; ties are broken by id in the same direction
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteSort {
+    #[default]
+    CreatedDesc,
+    CreatedAsc,
+    UpdatedDesc,
+    UpdatedAsc,
+    /// The drag-and-drop order set with `reorder_notes`
+    Manual,
+}
+
+impl NoteSort {
+    /// SQL `ORDER BY` clause for this ordering
+    pub const fn order_by(&self) -> &'static str {
+        match self {
+            NoteSort::CreatedDesc => "n.created_at DESC, n.id DESC",
+            NoteSort::CreatedAsc => "n.created_at ASC, n.id ASC",
+            NoteSort::UpdatedDesc => "n.updated_at DESC, n.id DESC",
+            NoteSort::UpdatedAsc => "n.updated_at ASC, n.id ASC",
+            NoteSort::Manual => "(SELECT sort_order FROM note_sort_order WHERE note_id = n.id) ASC, n.id ASC",
+        }
+    }
+}
+
+/// Page size used when a `NoteFilter` does not give one
+pub const DEFAULT_NOTE_QUERY_LIMIT: i64 = 50;
+
+fn default_note_query_limit() -> i64 {
+    DEFAULT_NOTE_QUERY_LIMIT
+}
+
+/// Combined state, tag, sort and pagination filter for listing notes
+/// 
+/// Unset state filters match either way, except `trashed`: trashed notes are
+/// only listed when `trashed` is `Some(true)`. Pins and favorites share the same
+/// flag, so `pinned` and `favorite` may not disagree. Notes must carry every tag.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NoteFilter {
+    #[serde(default)]
+    pub pinned: Option<bool>,
+    #[serde(default)]
+    pub favorite: Option<bool>,
+    #[serde(default)]
+    pub archived: Option<bool>,
+    #[serde(default)]
+    pub trashed: Option<bool>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub sort: NoteSort,
+    #[serde(default = "default_note_query_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+impl Default for NoteFilter {
+    fn default() -> Self {
+        Self {
+            pinned: None,
+            favorite: None,
+            archived: None,
+            trashed: None,
+            tags: Vec::new(),
+            sort: NoteSort::default(),
+            limit: DEFAULT_NOTE_QUERY_LIMIT,
+            offset: 0,
+        }
+    }
+}
+
+/// A reference from a note to an external file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NoteAttachment {
+    pub id: i64,
+    pub note_id: i64,
+    pub path: String,
+    pub added_at: String,
+}
+
+/// A recorded search query
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SearchHistoryEntry {
+    pub query: String,
+    pub searched_at: String,
+}
+
+/// A recorded change to a setting; `None` means the setting was unset
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SettingChange {
+    pub key: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_at: String,
+}
+
 /// Represents a user setting
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Setting {
@@ -60,6 +302,8 @@ mod tests {
             nickname: Some("Test Note".to_string()),
             path: "/test".to_string(),
             is_favorite: true,  // Updated to match integration tests
+            is_encrypted: false,
+            external_id: String::new(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
         };
@@ -155,6 +399,8 @@ mod tests {
             nickname: Some("Test Note".to_string()),
             path: "/test".to_string(),
             is_favorite: true,  // Updated field name
+            is_encrypted: false,
+            external_id: String::new(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
         };
@@ -172,6 +418,8 @@ mod tests {
             nickname: Some("Test Note".to_string()),
             path: "/test".to_string(),
             is_favorite: true,  // Updated field name
+            is_encrypted: false,
+            external_id: String::new(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
         };
@@ -8,12 +8,43 @@
 use super::backend::{BackendMetrics, get_backend_monitor};
 use super::frontend::{FrontendAnalysis, get_frontend_monitor};
 use super::system::{SystemAnalysis, get_system_monitor};
+use super::{get_performance_monitor, PerformanceSummary};
 use crate::error::AppError;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Percent change between window halves beyond which a trend is no longer "stable"
+const TREND_CHANGE_THRESHOLD_PERCENT: f64 = 10.0;
+
+/// Number of samples at which trend confidence reaches 1.0
+const TREND_CONFIDENT_SAMPLE_COUNT: usize = 50;
+
+/// Number of samples in the trend visualization moving average
+const TREND_MOVING_AVERAGE_WINDOW: usize = 5;
+
+/// Number of preceding samples each value is compared against for anomaly detection
+const ANOMALY_WINDOW: usize = 20;
+
+/// Fewest preceding samples needed before a value can be flagged as anomalous
+const ANOMALY_MIN_BASELINE: usize = 5;
+
+/// Smallest standard deviation used for scoring, as a fraction of the baseline mean,
+/// so jitter on a perfectly flat baseline is not flagged
+const ANOMALY_MIN_RELATIVE_STD_DEV: f64 = 0.01;
+
+/// Fewest memory samples needed before growth can be called a leak
+const LEAK_MIN_SAMPLES: usize = 5;
+
+/// Memory growth, in MB per hour, above which a steady rise is flagged as a leak
+/// Severity steps up at 5x ("moderate") and 20x ("severe") this rate
+const LEAK_GROWTH_THRESHOLD_MB_PER_HOUR: f64 = 10.0;
+
+/// Largest drop below the running peak, as a fraction of the overall growth, that
+/// still counts as growth "without recovering"
+const LEAK_MAX_RECOVERY_FRACTION: f64 = 0.1;
+
 /// Comprehensive performance analytics report
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceAnalyticsReport {
@@ -37,6 +68,8 @@ pub struct PerformanceAnalyticsReport {
     pub benchmarks: PerformanceBenchmarks,
     /// Risk assessment
     pub risk_assessment: RiskAssessment,
+    /// Per-source operation summaries keyed by `OperationSource` label, when requested
+    pub source_breakdown: Option<HashMap<String, PerformanceSummary>>,
 }
 
 /// Report metadata
@@ -352,7 +385,7 @@ pub struct SecurityOverheadAnalysis {
     pub avg_validation_time_ms: f64,
     /// Security overhead percentage of total time
     pub overhead_percentage: f64,
-    /// Validation efficiency by operation type
+    /// Average measured validation time (ms) by validation label, e.g. `context:read_notes`
     pub efficiency_by_operation: HashMap<String, f64>,
     /// Security performance score (0-100)
     pub performance_score: u8,
@@ -1141,12 +1174,26 @@ impl PerformanceAnalyticsEngine {
 
     /// Generate comprehensive performance analytics report
     pub async fn generate_report(&self, period_hours: Option<u32>) -> Result<PerformanceAnalyticsReport, AppError> {
+        self.generate_report_with_breakdown(period_hours, false).await
+    }
+
+    /// Generate the analytics report, optionally breaking operations down by `OperationSource`
+    pub async fn generate_report_with_breakdown(
+        &self,
+        period_hours: Option<u32>,
+        by_source: bool,
+    ) -> Result<PerformanceAnalyticsReport, AppError> {
         let analysis_period = {
             let config = self.config.lock()
                 .map_err(|e| AppError::Runtime { message: format!("Config lock error: {}", e) })?;
             period_hours.unwrap_or(config.analysis_period_hours)
         };
-        let report_id = format!("report_{}_{}", analysis_period, chrono::Utc::now().timestamp());
+        let report_id = format!(
+            "report_{}{}_{}",
+            analysis_period,
+            if by_source { "_by_source" } else { "" },
+            chrono::Utc::now().timestamp()
+        );
         
         // Check cache first
         if let Ok(cache) = self.report_cache.lock() {
@@ -1181,12 +1228,14 @@ impl PerformanceAnalyticsEngine {
         
         // Generate comprehensive analysis
         let executive_summary = self.generate_executive_summary(&backend_metrics, &frontend_analysis, &system_analysis)?;
-        let backend_analysis = self.generate_backend_analysis(&backend_metrics)?;
+        let backend_analysis = self.generate_backend_analysis(&backend_metrics, analysis_period)?;
         let cross_component_analysis = self.generate_cross_component_analysis(&backend_metrics, &frontend_analysis, &system_analysis)?;
         let trends = self.generate_trends_analysis(analysis_period).await?;
         let recommendations = self.generate_optimization_recommendations(&backend_metrics, &frontend_analysis, &system_analysis)?;
         let benchmarks = self.generate_performance_benchmarks(&backend_metrics)?;
         let risk_assessment = self.generate_risk_assessment(&backend_metrics, &system_analysis)?;
+        let source_breakdown = by_source
+            .then(|| get_performance_monitor().get_source_breakdown(analysis_period as u64));
         
         let report = PerformanceAnalyticsReport {
             metadata,
@@ -1199,6 +1248,7 @@ impl PerformanceAnalyticsEngine {
             recommendations,
             benchmarks,
             risk_assessment,
+            source_breakdown,
         };
         
         // Cache the report
@@ -1223,7 +1273,7 @@ impl PerformanceAnalyticsEngine {
         // Calculate overall performance score
         let backend_score = self.calculate_backend_score(backend_metrics);
         let frontend_score = frontend_analysis.as_ref().map(|f| f.performance_score as f64).unwrap_or(80.0);
-        let system_score = system_analysis.performance_score as f64;
+        let system_score = if system_analysis.data_available { system_analysis.performance_score } else { 80.0 };
         
         let overall_score = ((backend_score + frontend_score + system_score) / 3.0) as u8;
         
@@ -1248,15 +1298,19 @@ impl PerformanceAnalyticsEngine {
         
         key_findings.push(format!("Average database query time: {:.1}ms", backend_metrics.database.avg_query_time_ms));
         key_findings.push(format!("IPC command processing: {:.1}ms average", backend_metrics.ipc.avg_processing_time_ms));
-        key_findings.push(format!("System health score: {}/100", system_analysis.performance_score));
+        if system_analysis.data_available {
+            key_findings.push(format!("System health score: {}/100", system_analysis.performance_score));
+        } else {
+            key_findings.push("System metrics unavailable on this platform".to_string());
+        }
         
         // Business impact assessment
         let business_impact = BusinessImpact {
             user_experience: if overall_score >= 80 { "positive" } else if overall_score >= 60 { "neutral" } else { "negative" }.to_string(),
             productivity: if backend_metrics.ipc.avg_processing_time_ms < 50.0 { "positive" } else { "neutral" }.to_string(),
-            resource_efficiency: if system_analysis.performance_score >= 70.0 { "positive" } else { "neutral" }.to_string(),
+            resource_efficiency: if system_analysis.data_available && system_analysis.performance_score >= 70.0 { "positive" } else { "neutral" }.to_string(),
             cost_implications: "minimal".to_string(),
-            scalability: if system_analysis.performance_score >= 80.0 { "good" } else { "needs_attention" }.to_string(),
+            scalability: if !system_analysis.data_available { "unknown" } else if system_analysis.performance_score >= 80.0 { "good" } else { "needs_attention" }.to_string(),
         };
         
         Ok(ExecutiveSummary {
@@ -1321,7 +1375,7 @@ impl PerformanceAnalyticsEngine {
     }
 
     /// Generate backend-specific analysis
-    fn generate_backend_analysis(&self, metrics: &BackendMetrics) -> Result<BackendAnalysis, AppError> {
+    fn generate_backend_analysis(&self, metrics: &BackendMetrics, period_hours: u32) -> Result<BackendAnalysis, AppError> {
         let performance_score = self.calculate_backend_score(metrics) as u8;
         
         // Database performance analysis
@@ -1404,10 +1458,14 @@ impl PerformanceAnalyticsEngine {
         };
         
         // Memory efficiency analysis
+        let monitor = get_performance_monitor();
+        let memory_series: Vec<(u64, f64)> = monitor.get_system_history(period_hours as u64).iter()
+            .map(|metrics| (metrics.timestamp, metrics.memory_usage as f64))
+            .collect();
         let memory_efficiency = MemoryEfficiencyAnalysis {
             utilization_score: if metrics.memory.growth_rate_bytes_per_min < 1024.0 * 1024.0 { 90 } else { 70 },
             growth_rate_mb_per_hour: metrics.memory.growth_rate_bytes_per_min * 60.0 / (1024.0 * 1024.0),
-            leak_indicators: Vec::new(), // Would be populated from memory analysis
+            leak_indicators: Self::detect_memory_leaks(&memory_series, monitor.get_budget().max_memory_usage_bytes),
             optimization_opportunities: vec![
                 "Monitor search result caching efficiency".to_string(),
                 "Consider implementing memory pooling for frequent allocations".to_string(),
@@ -1415,17 +1473,14 @@ impl PerformanceAnalyticsEngine {
             gc_efficiency: None, // Rust doesn't have traditional GC
         };
         
-        // Security overhead analysis
-        let security_overhead = SecurityOverheadAnalysis {
-            avg_validation_time_ms: metrics.ipc.security_overhead_ms,
-            overhead_percentage: (metrics.ipc.security_overhead_ms / metrics.ipc.avg_processing_time_ms) * 100.0,
-            efficiency_by_operation: HashMap::new(), // Would be populated from detailed metrics
-            performance_score: if metrics.ipc.security_overhead_ms < 5.0 { 95 } else { 80 },
-            optimizations: vec![
-                "Security validation is efficiently implemented".to_string(),
-                "Consider caching validation results for repeated operations".to_string(),
-            ],
-        };
+        // Security overhead analysis from measured validation and operation times
+        let operation_time_ms: f64 = monitor.get_operation_history(period_hours as u64).iter()
+            .map(|op| op.duration_ms as f64)
+            .sum();
+        let security_overhead = Self::analyze_security_overhead(
+            &monitor.get_validation_timings(period_hours as u64),
+            operation_time_ms,
+        );
         
         Ok(BackendAnalysis {
             performance_score,
@@ -1437,6 +1492,42 @@ impl PerformanceAnalyticsEngine {
         })
     }
 
+    /// Security overhead from measured validation timings
+    ///
+    /// `operation_time_ms` is the time spent in the operations themselves over the
+    /// same period; validation runs before them, so the overhead is the share of
+    /// validation in both together.
+    fn analyze_security_overhead(
+        timings: &HashMap<String, super::ValidationTiming>,
+        operation_time_ms: f64,
+    ) -> SecurityOverheadAnalysis {
+        let validation_count: u64 = timings.values().map(|timing| timing.count).sum();
+        let validation_time_ms: f64 = timings.values().map(|timing| timing.total_ms).sum();
+        let avg_validation_time_ms = if validation_count > 0 { validation_time_ms / validation_count as f64 } else { 0.0 };
+        let overhead_percentage = if validation_time_ms + operation_time_ms > 0.0 {
+            validation_time_ms / (validation_time_ms + operation_time_ms) * 100.0
+        } else {
+            0.0
+        };
+
+        let mut optimizations = Vec::new();
+        let slowest = timings.iter().max_by(|a, b| a.1.avg_ms.total_cmp(&b.1.avg_ms));
+        if let Some((operation, timing)) = slowest {
+            optimizations.push(format!("Slowest validation is {} at {:.3}ms on average", operation, timing.avg_ms));
+        }
+        if overhead_percentage > 10.0 {
+            optimizations.push("Consider caching validation results for repeated operations".to_string());
+        }
+
+        SecurityOverheadAnalysis {
+            avg_validation_time_ms,
+            overhead_percentage,
+            efficiency_by_operation: timings.iter().map(|(operation, timing)| (operation.clone(), timing.avg_ms)).collect(),
+            performance_score: if avg_validation_time_ms < 5.0 { 95 } else { 80 },
+            optimizations,
+        }
+    }
+
     /// Generate cross-component analysis
     fn generate_cross_component_analysis(
         &self,
@@ -1588,29 +1679,55 @@ impl PerformanceAnalyticsEngine {
         })
     }
 
-    /// Generate trends analysis
-    async fn generate_trends_analysis(&self, _period_hours: u32) -> Result<PerformanceTrends, AppError> {
-        // This would analyze historical data for trends
-        // For now, provide a basic implementation
+    /// Generate trends analysis from the recorded performance history
+    async fn generate_trends_analysis(&self, period_hours: u32) -> Result<PerformanceTrends, AppError> {
+        let monitor = get_performance_monitor();
+        let operations = monitor.get_operation_history(period_hours as u64);
+        let system_history = monitor.get_system_history(period_hours as u64);
+        
+        let operation_series = |prefixes: &[&str]| -> Vec<(u64, f64)> {
+            operations.iter()
+                .filter(|op| prefixes.iter().any(|prefix| op.operation_type.starts_with(prefix)))
+                .map(|op| (op.start_timestamp, op.duration_ms as f64))
+                .collect()
+        };
+        let query_series = operation_series(&["database_", "search_"]);
+        let ipc_series = operation_series(&["ipc_"]);
+        let memory_series: Vec<(u64, f64)> = system_history.iter()
+            .map(|metrics| (metrics.timestamp, metrics.memory_usage as f64))
+            .collect();
+        let memory_growth_series: Vec<(u64, f64)> = memory_series.windows(2)
+            .map(|pair| (pair[1].0, pair[1].1 - pair[0].1))
+            .collect();
+        
+        let (enable_anomaly_detection, anomaly_sensitivity) = {
+            let config = self.config.lock()
+                .map_err(|e| AppError::Runtime { message: format!("Config lock error: {}", e) })?;
+            (config.enable_anomaly_detection, config.anomaly_sensitivity)
+        };
+        let anomaly_detection = if enable_anomaly_detection {
+            Self::build_anomaly_detection(
+                &[("average_query_time", &query_series), ("memory_growth", &memory_growth_series)],
+                anomaly_sensitivity,
+            )
+        } else {
+            AnomalyDetection {
+                anomalies: Vec::new(),
+                patterns: Vec::new(),
+                model_performance: AnomalyModelPerformance {
+                    accuracy: 0.0,
+                    false_positive_rate: 0.0,
+                    false_negative_rate: 0.0,
+                    confidence: 0.0,
+                },
+            }
+        };
         
         Ok(PerformanceTrends {
             historical_trends: vec![
-                TrendAnalysis {
-                    metric_name: "average_query_time".to_string(),
-                    trend_direction: "stable".to_string(),
-                    trend_strength: 0.3,
-                    rate_of_change: 0.5, // 0.5% change
-                    confidence_level: 0.8,
-                    visualization_data: Vec::new(),
-                },
-                TrendAnalysis {
-                    metric_name: "memory_usage".to_string(),
-                    trend_direction: "improving".to_string(),
-                    trend_strength: 0.6,
-                    rate_of_change: -2.0, // 2% improvement
-                    confidence_level: 0.85,
-                    visualization_data: Vec::new(),
-                },
+                Self::analyze_metric_trend("average_query_time", &query_series),
+                Self::analyze_metric_trend("ipc_command_time", &ipc_series),
+                Self::analyze_metric_trend("memory_usage", &memory_series),
             ],
             predictive_analysis: PredictiveAnalysis {
                 predictions: Vec::new(),
@@ -1642,19 +1759,257 @@ impl PerformanceAnalyticsEngine {
                 rollback_recommendations: Vec::new(),
             },
             seasonal_patterns: None,
-            anomaly_detection: AnomalyDetection {
-                anomalies: Vec::new(),
-                patterns: Vec::new(),
-                model_performance: AnomalyModelPerformance {
-                    accuracy: 0.92,
-                    false_positive_rate: 0.05,
-                    false_negative_rate: 0.03,
-                    confidence: 0.88,
-                },
-            },
+            anomaly_detection,
         })
     }
 
+    /// Run the z-score detector over each named series and summarize the results
+    /// 
+    /// The model stats are what a z-score test implies rather than measured values:
+    /// the false positive rate is the share of normally distributed samples flagged
+    /// at this sensitivity, and confidence grows with the number of samples seen.
+    /// Without labelled data the false negative rate cannot be estimated and is 0.
+    fn build_anomaly_detection(series: &[(&str, &[(u64, f64)])], sensitivity: f64) -> AnomalyDetection {
+        let mut anomalies = Vec::new();
+        let mut patterns = Vec::new();
+        for (metric, values) in series {
+            let detected = Self::detect_anomalies(metric, values, sensitivity);
+            if !detected.is_empty() {
+                patterns.push(AnomalyPattern {
+                    pattern_type: "spike".to_string(),
+                    frequency: detected.len() as f64 / values.len() as f64,
+                    description: format!("{} of {} {} samples were spikes", detected.len(), values.len(), metric),
+                    external_correlations: Vec::new(),
+                });
+            }
+            anomalies.extend(detected);
+        }
+        
+        let false_positive_rate = Self::one_sided_tail(Self::anomaly_z_threshold(sensitivity));
+        let sample_count = series.iter().map(|(_, values)| values.len()).sum::<usize>();
+        AnomalyDetection {
+            anomalies,
+            patterns,
+            model_performance: AnomalyModelPerformance {
+                accuracy: 1.0 - false_positive_rate,
+                false_positive_rate,
+                false_negative_rate: 0.0,
+                confidence: (sample_count as f64 / TREND_CONFIDENT_SAMPLE_COUNT as f64).min(1.0),
+            },
+        }
+    }
+
+    /// Flag values that spike above their rolling baseline
+    /// 
+    /// Each value is scored against the mean and standard deviation of the
+    /// `ANOMALY_WINDOW` samples before it. Values whose z-score exceeds the
+    /// threshold for `sensitivity` (e.g. 0.95 -> z > 1.96) are reported. Only
+    /// spikes are reported since higher query times and memory growth are worse.
+    fn detect_anomalies(metric: &str, series: &[(u64, f64)], sensitivity: f64) -> Vec<PerformanceAnomaly> {
+        let threshold = Self::anomaly_z_threshold(sensitivity);
+        let mut anomalies = Vec::new();
+        
+        for index in ANOMALY_MIN_BASELINE..series.len() {
+            let baseline = &series[index.saturating_sub(ANOMALY_WINDOW)..index];
+            let mean = baseline.iter().map(|(_, value)| value).sum::<f64>() / baseline.len() as f64;
+            let variance = baseline.iter().map(|(_, value)| (value - mean).powi(2)).sum::<f64>() / baseline.len() as f64;
+            let std_dev = variance.sqrt()
+                .max(mean.abs() * ANOMALY_MIN_RELATIVE_STD_DEV)
+                .max(f64::EPSILON);
+            
+            let (timestamp, value) = series[index];
+            let z_score = (value - mean) / std_dev;
+            if z_score <= threshold {
+                continue;
+            }
+            
+            let severity = if z_score >= threshold * 2.0 {
+                "critical"
+            } else if z_score >= threshold * 1.5 {
+                "high"
+            } else {
+                "medium"
+            };
+            let root_cause_hypothesis = if metric.starts_with("memory") {
+                vec![format!("Memory grew by {:.0} bytes against a typical {:.0}", value, mean), "Large allocation or leak".to_string()]
+            } else {
+                vec![format!("Took {:.1}ms against a typical {:.1}ms", value, mean), "Slow query or lock contention".to_string()]
+            };
+            anomalies.push(PerformanceAnomaly {
+                anomaly_id: format!("{}_{}_{}", metric, timestamp, index),
+                metric: metric.to_string(),
+                anomaly_type: "spike".to_string(),
+                severity: severity.to_string(),
+                detected_at: timestamp,
+                duration_ms: (!metric.starts_with("memory")).then_some(value.max(0.0) as u64),
+                root_cause_hypothesis,
+            });
+        }
+        
+        anomalies
+    }
+
+    /// Flag memory that keeps growing across the window without coming back down
+    /// 
+    /// The growth rate is the least-squares slope of the (millisecond timestamp,
+    /// bytes) series. A leak is reported when that rate exceeds
+    /// `LEAK_GROWTH_THRESHOLD_MB_PER_HOUR` and usage never falls more than
+    /// `LEAK_MAX_RECOVERY_FRACTION` of the overall growth below its running peak.
+    /// Time to critical extrapolates the slope to `budget_bytes`.
+    fn detect_memory_leaks(series: &[(u64, f64)], budget_bytes: u64) -> Vec<MemoryLeakIndicator> {
+        if series.len() < LEAK_MIN_SAMPLES {
+            return Vec::new();
+        }
+        let (first, last) = (series[0], series[series.len() - 1]);
+        let growth = last.1 - first.1;
+        if growth <= 0.0 || last.0 <= first.0 {
+            return Vec::new();
+        }
+        
+        let mut peak = f64::MIN;
+        let mut largest_drop: f64 = 0.0;
+        for (_, value) in series {
+            peak = peak.max(*value);
+            largest_drop = largest_drop.max(peak - value);
+        }
+        if largest_drop > growth * LEAK_MAX_RECOVERY_FRACTION {
+            return Vec::new();
+        }
+        
+        let count = series.len() as f64;
+        let mean_t = series.iter().map(|(t, _)| (t - first.0) as f64).sum::<f64>() / count;
+        let mean_v = series.iter().map(|(_, v)| v).sum::<f64>() / count;
+        let (covariance, variance) = series.iter().fold((0.0, 0.0), |(cov, var), (t, v)| {
+            let dt = (t - first.0) as f64 - mean_t;
+            (cov + dt * (v - mean_v), var + dt * dt)
+        });
+        if variance <= 0.0 {
+            return Vec::new();
+        }
+        let bytes_per_ms = covariance / variance;
+        let mb_per_hour = bytes_per_ms * 3_600_000.0 / (1024.0 * 1024.0);
+        if mb_per_hour <= LEAK_GROWTH_THRESHOLD_MB_PER_HOUR {
+            return Vec::new();
+        }
+        
+        let severity = if mb_per_hour >= LEAK_GROWTH_THRESHOLD_MB_PER_HOUR * 20.0 {
+            "severe"
+        } else if mb_per_hour >= LEAK_GROWTH_THRESHOLD_MB_PER_HOUR * 5.0 {
+            "moderate"
+        } else {
+            "minor"
+        };
+        let headroom = budget_bytes as f64 - last.1;
+        let time_to_critical = if headroom <= 0.0 {
+            Some("memory budget already exceeded".to_string())
+        } else {
+            let hours = headroom / bytes_per_ms / 3_600_000.0;
+            Some(if hours < 1.0 { format!("{:.0} minutes", hours * 60.0) } else { format!("{:.1} hours", hours) })
+        };
+        let action = match severity {
+            "severe" => "Restart the application and profile allocations in the fastest-growing operations",
+            "moderate" => "Profile memory over a longer session to find what is retained",
+            _ => "Keep monitoring; check caches and histories for unbounded growth",
+        };
+        
+        vec![MemoryLeakIndicator {
+            component: "process".to_string(),
+            severity: severity.to_string(),
+            pattern: format!(
+                "Memory rose steadily by {:.1} MB over {} samples ({:.1} MB/hour) without recovering",
+                growth / (1024.0 * 1024.0), series.len(), mb_per_hour
+            ),
+            time_to_critical,
+            action: action.to_string(),
+        }]
+    }
+
+    /// Z-score above which a value falls outside the central `sensitivity` share
+    /// of a normal distribution (two-sided), e.g. 0.95 -> 1.96
+    /// 
+    /// Uses the Abramowitz and Stegun 26.2.23 approximation of the inverse normal CDF.
+    fn anomaly_z_threshold(sensitivity: f64) -> f64 {
+        let tail = ((1.0 - sensitivity.clamp(0.5, 0.9999)) / 2.0).max(f64::MIN_POSITIVE);
+        let t = (-2.0 * tail.ln()).sqrt();
+        t - (2.515517 + 0.802853 * t + 0.010328 * t * t) / (1.0 + 1.432788 * t + 0.189269 * t * t + 0.001308 * t * t * t)
+    }
+
+    /// Share of a normal distribution above `z` (the inverse of `anomaly_z_threshold`)
+    fn one_sided_tail(z: f64) -> f64 {
+        // Abramowitz and Stegun 26.2.17
+        let t = 1.0 / (1.0 + 0.2316419 * z.abs());
+        let density = (-z * z / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt();
+        let upper = density * t * (0.319381530 + t * (-0.356563782 + t * (1.781477937 + t * (-1.821255978 + t * 1.330274429))));
+        if z >= 0.0 { upper } else { 1.0 - upper }
+    }
+
+    /// Analyze the trend of a (timestamp, value) series where higher values are worse
+    /// 
+    /// Compares the averages of the first and second halves of the window, in the same
+    /// way as `SystemMonitor::analyze_trends`; rate of change is in percent.
+    fn analyze_metric_trend(metric_name: &str, series: &[(u64, f64)]) -> TrendAnalysis {
+        let visualization_data = Self::build_visualization_data(series);
+        
+        if series.len() < 2 {
+            return TrendAnalysis {
+                metric_name: metric_name.to_string(),
+                trend_direction: "stable".to_string(),
+                trend_strength: 0.0,
+                rate_of_change: 0.0,
+                confidence_level: 0.0,
+                visualization_data,
+            };
+        }
+        
+        let (first_half, second_half) = series.split_at(series.len() / 2);
+        let average = |values: &[(u64, f64)]| values.iter().map(|(_, value)| value).sum::<f64>() / values.len() as f64;
+        let first_avg = average(first_half);
+        let second_avg = average(second_half);
+        
+        let rate_of_change = if first_avg > 0.0 {
+            ((second_avg - first_avg) / first_avg) * 100.0
+        } else if second_avg > 0.0 {
+            100.0
+        } else {
+            0.0
+        };
+        
+        let trend_direction = if rate_of_change > TREND_CHANGE_THRESHOLD_PERCENT {
+            "degrading" // Higher times/usage is worse
+        } else if rate_of_change < -TREND_CHANGE_THRESHOLD_PERCENT {
+            "improving"
+        } else {
+            "stable"
+        };
+        
+        TrendAnalysis {
+            metric_name: metric_name.to_string(),
+            trend_direction: trend_direction.to_string(),
+            trend_strength: (rate_of_change.abs() / 100.0).min(1.0),
+            rate_of_change,
+            // More samples give more confidence, saturating at TREND_CONFIDENT_SAMPLE_COUNT
+            confidence_level: (series.len() as f64 / TREND_CONFIDENT_SAMPLE_COUNT as f64).min(1.0),
+            visualization_data,
+        }
+    }
+
+    /// Build trend visualization points with a trailing moving average
+    fn build_visualization_data(series: &[(u64, f64)]) -> Vec<TrendDataPoint> {
+        series.iter()
+            .enumerate()
+            .map(|(index, (timestamp, value))| {
+                let window_start = index.saturating_sub(TREND_MOVING_AVERAGE_WINDOW - 1);
+                let window = &series[window_start..=index];
+                let moving_average = window.iter().map(|(_, v)| v).sum::<f64>() / window.len() as f64;
+                TrendDataPoint {
+                    timestamp: *timestamp,
+                    value: *value,
+                    moving_average,
+                }
+            })
+            .collect()
+    }
+
     /// Generate optimization recommendations
     fn generate_optimization_recommendations(
         &self,
@@ -1789,7 +2144,7 @@ impl PerformanceAnalyticsEngine {
         }
         
         // System resource optimization
-        if system_analysis.performance_score < 80.0 {
+        if system_analysis.data_available && system_analysis.performance_score < 80.0 {
             medium_priority.push(OptimizationRecommendation {
                 recommendation_id: "system_optimization".to_string(),
                 category: "system".to_string(),
@@ -2063,7 +2418,7 @@ impl PerformanceAnalyticsEngine {
         }
         
         // Stability risks
-        if system_analysis.performance_score < 70.0 {
+        if system_analysis.data_available && system_analysis.performance_score < 70.0 {
             stability_risks.push(StabilityRisk {
                 risk_id: "system_instability".to_string(),
                 description: "System health score indicates potential stability issues".to_string(),
@@ -2149,4 +2504,119 @@ mod tests {
         assert!(config.enable_predictions);
         assert!(config.enable_anomaly_detection);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_metric_trend_detects_degradation() {
+        // Query times steadily rising from 10ms to 48ms
+        let series: Vec<(u64, f64)> = (0..20u64)
+            .map(|i| (1_000 + i * 1_000, 10.0 + i as f64 * 2.0))
+            .collect();
+
+        let trend = PerformanceAnalyticsEngine::analyze_metric_trend("average_query_time", &series);
+        assert_eq!(trend.metric_name, "average_query_time");
+        assert_eq!(trend.trend_direction, "degrading");
+        assert!(trend.rate_of_change > 0.0);
+        assert!(trend.trend_strength > 0.0);
+
+        // Visualization mirrors the stored series
+        assert_eq!(trend.visualization_data.len(), series.len());
+        assert_eq!(trend.visualization_data[0].timestamp, 1_000);
+        assert_eq!(trend.visualization_data[0].moving_average, 10.0);
+        let last = trend.visualization_data.last().unwrap();
+        assert_eq!(last.value, 48.0);
+        assert_eq!(last.moving_average, (40.0 + 42.0 + 44.0 + 46.0 + 48.0) / 5.0);
+    }
+
+    #[test]
+    fn test_metric_trend_stable_and_improving() {
+        let flat: Vec<(u64, f64)> = (0..10u64).map(|i| (i, 25.0)).collect();
+        let trend = PerformanceAnalyticsEngine::analyze_metric_trend("ipc_command_time", &flat);
+        assert_eq!(trend.trend_direction, "stable");
+        assert_eq!(trend.rate_of_change, 0.0);
+
+        let falling: Vec<(u64, f64)> = (0..10u64).map(|i| (i, 100.0 - i as f64 * 8.0)).collect();
+        let trend = PerformanceAnalyticsEngine::analyze_metric_trend("memory_usage", &falling);
+        assert_eq!(trend.trend_direction, "improving");
+        assert!(trend.rate_of_change < 0.0);
+
+        // Too little data to call a trend
+        let trend = PerformanceAnalyticsEngine::analyze_metric_trend("memory_usage", &[(1, 5.0)]);
+        assert_eq!(trend.trend_direction, "stable");
+        assert_eq!(trend.confidence_level, 0.0);
+        assert_eq!(trend.visualization_data.len(), 1);
+    }
+
+    #[test]
+    fn test_validation_times_appear_in_efficiency_breakdown() {
+        let monitor = super::super::PerformanceMonitor::new();
+        monitor.record_validation("context:read_notes", std::time::Duration::from_millis(2));
+        monitor.record_validation("context:read_notes", std::time::Duration::from_millis(4));
+        monitor.record_validation("note_content", std::time::Duration::from_millis(6));
+
+        let analysis = PerformanceAnalyticsEngine::analyze_security_overhead(&monitor.get_validation_timings(1), 88.0);
+        assert_eq!(analysis.efficiency_by_operation.len(), 2);
+        assert!((analysis.efficiency_by_operation["context:read_notes"] - 3.0).abs() < 1e-9);
+        assert!((analysis.efficiency_by_operation["note_content"] - 6.0).abs() < 1e-9);
+        assert!((analysis.avg_validation_time_ms - 4.0).abs() < 1e-9);
+        // 12ms of validation against 88ms of operations
+        assert!((analysis.overhead_percentage - 12.0).abs() < 1e-9);
+
+        // The validator records its own calls
+        let validator = crate::validation::SecurityValidator::new();
+        let context = crate::validation::OperationContext::new_direct(vec![crate::validation::OperationCapability::FileExport]);
+        validator.validate_operation_context(&context).unwrap();
+        assert!(get_performance_monitor().get_validation_timings(1).contains_key("context:file_export"));
+
+        let idle = PerformanceAnalyticsEngine::analyze_security_overhead(&HashMap::new(), 0.0);
+        assert_eq!(idle.overhead_percentage, 0.0);
+        assert!(idle.efficiency_by_operation.is_empty());
+    }
+
+    #[test]
+    fn test_memory_leak_detected_from_steady_growth() {
+        // 100 MB growing by 2 MB a minute, sampled every minute for half an hour
+        let mb = 1024.0 * 1024.0;
+        let growing: Vec<(u64, f64)> = (0..30u64)
+            .map(|i| (1_000 + i * 60_000, 100.0 * mb + i as f64 * 2.0 * mb))
+            .collect();
+        let leaks = PerformanceAnalyticsEngine::detect_memory_leaks(&growing, 512 * 1024 * 1024);
+        assert_eq!(leaks.len(), 1);
+        assert!(!leaks[0].pattern.is_empty());
+        // 120 MB/hour is between 5x and 20x the threshold
+        assert_eq!(leaks[0].severity, "moderate");
+        assert!(leaks[0].time_to_critical.as_deref().unwrap().ends_with("hours"));
+
+        // The same growth with a full recovery halfway is not a leak
+        let mut recovering = growing.clone();
+        recovering[15].1 = 100.0 * mb;
+        assert!(PerformanceAnalyticsEngine::detect_memory_leaks(&recovering, 512 * 1024 * 1024).is_empty());
+        let flat: Vec<(u64, f64)> = (0..30u64).map(|i| (i * 60_000, 100.0 * mb)).collect();
+        assert!(PerformanceAnalyticsEngine::detect_memory_leaks(&flat, 512 * 1024 * 1024).is_empty());
+    }
+
+    #[test]
+    fn test_anomaly_detection_flags_outlier() {
+        // Query times alternating around 10ms with one 80ms outlier
+        let mut series: Vec<(u64, f64)> = (0..30u64)
+            .map(|i| (1_000 + i * 1_000, if i % 2 == 0 { 9.0 } else { 11.0 }))
+            .collect();
+        series[20].1 = 80.0;
+
+        let anomalies = PerformanceAnalyticsEngine::detect_anomalies("average_query_time", &series, 0.95);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].detected_at, series[20].0);
+        assert_eq!(anomalies[0].anomaly_type, "spike");
+        assert_eq!(anomalies[0].severity, "critical");
+        assert_eq!(anomalies[0].duration_ms, Some(80));
+
+        let detection = PerformanceAnalyticsEngine::build_anomaly_detection(&[("average_query_time", &series)], 0.95);
+        assert_eq!(detection.anomalies.len(), 1);
+        assert_eq!(detection.patterns.len(), 1);
+        assert!((detection.model_performance.false_positive_rate - 0.025).abs() < 0.001);
+
+        // The steady series alone has no anomalies
+        series[20].1 = 9.0;
+        assert!(PerformanceAnalyticsEngine::detect_anomalies("average_query_time", &series, 0.95).is_empty());
+        assert!((PerformanceAnalyticsEngine::anomaly_z_threshold(0.95) - 1.96).abs() < 0.01);
+    }
+}
@@ -609,13 +609,13 @@ impl SearchMetricsCollector {
 
         SearchMetrics {
             query_performance,
-            cache_performance: CacheMetrics {
-                total_entries: 100, // Would query actual cache
+            cache_performance: super::current_cache_metrics().unwrap_or(CacheMetrics {
+                total_entries: 0,
                 hit_rate: cache_hit_rate,
-                avg_lookup_time_us: 50,
-                memory_usage: 10 * 1024 * 1024,
+                avg_lookup_time_us: 0,
+                memory_usage: 0,
                 recent_evictions: 0,
-            },
+            }),
             index_utilization: IndexUtilization {
                 fts_hit_rate: 0.95,
                 index_size_bytes: 5 * 1024 * 1024,
@@ -31,6 +31,13 @@ pub struct FrontendMetrics {
     pub timestamp: u64,
 }
 
+impl FrontendMetrics {
+    /// Render, store and error samples carried by this snapshot
+    pub fn sample_count(&self) -> usize {
+        self.render_metrics.len() + self.store_metrics.len() + self.error_metrics.error_samples.len()
+    }
+}
+
 /// React component render performance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RenderMetric {
@@ -248,8 +255,13 @@ pub struct FrontendPerformanceMonitor {
     analysis_cache: Arc<Mutex<Option<FrontendAnalysis>>>,
     /// Maximum history size
     max_history_size: usize,
+    /// Maximum render, store and error samples kept across all snapshots
+    max_buffered_samples: usize,
 }
 
+/// Default cap on samples buffered by `FrontendPerformanceMonitor`
+pub const MAX_BUFFERED_FRONTEND_SAMPLES: usize = 10_000;
+
 impl FrontendPerformanceMonitor {
     /// Create new frontend performance monitor
     pub fn new() -> Self {
@@ -257,6 +269,15 @@ impl FrontendPerformanceMonitor {
             metrics_history: Arc::new(Mutex::new(Vec::new())),
             analysis_cache: Arc::new(Mutex::new(None)),
             max_history_size: 500, // Keep last 500 metric snapshots
+            max_buffered_samples: MAX_BUFFERED_FRONTEND_SAMPLES,
+        }
+    }
+    
+    /// Create a monitor with a custom cap on buffered samples
+    pub fn with_max_buffered_samples(max_buffered_samples: usize) -> Self {
+        Self {
+            max_buffered_samples,
+            ..Self::new()
         }
     }
 
@@ -266,9 +287,13 @@ impl FrontendPerformanceMonitor {
         if let Ok(mut history) = self.metrics_history.lock() {
             history.push(metrics.clone());
             
-            // Maintain circular buffer
-            while history.len() > self.max_history_size {
-                history.remove(0);
+            // Maintain circular buffer, bounded both by snapshots and by the
+            // individual samples they carry
+            let mut buffered_samples: usize = history.iter().map(FrontendMetrics::sample_count).sum();
+            while history.len() > self.max_history_size
+                || (buffered_samples > self.max_buffered_samples && history.len() > 1)
+            {
+                buffered_samples -= history.remove(0).sample_count();
             }
         }
 
@@ -724,6 +749,62 @@ mod tests {
         assert_eq!(monitor.get_recent_metrics(1).len(), 1);
     }
 
+    fn snapshot(timestamp: u64, renders: usize) -> FrontendMetrics {
+        FrontendMetrics {
+            render_metrics: (0..renders)
+                .map(|_| RenderMetric {
+                    component_name: "Buffered".to_string(),
+                    render_time_ms: 4.0,
+                    render_count: 1,
+                    props_changes: 0,
+                    state_changes: 0,
+                    is_memoized: true,
+                    timestamp,
+                })
+                .collect(),
+            browser_memory: None,
+            navigation_timing: None,
+            store_metrics: Vec::new(),
+            search_ui_metrics: SearchUiMetrics {
+                input_lag_ms: 0.0,
+                results_render_ms: 0.0,
+                virtual_scroll_metrics: VirtualScrollMetrics {
+                    items_per_frame: 0,
+                    scroll_fps: 60.0,
+                    visible_items_memory_kb: 0,
+                    item_render_time_ms: 0.0,
+                },
+                highlighting_ms: 0.0,
+                autocomplete_ms: 0.0,
+                filter_application_ms: 0.0,
+            },
+            error_metrics: ErrorMetrics {
+                js_errors: 0,
+                react_errors: 0,
+                network_errors: 0,
+                budget_violations: 0,
+                error_samples: Vec::new(),
+            },
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_buffered_samples_are_capped_dropping_oldest() {
+        let monitor = FrontendPerformanceMonitor::with_max_buffered_samples(5);
+        for timestamp in 1..=3 {
+            monitor.record_frontend_metrics(snapshot(timestamp, 2)).unwrap();
+        }
+        
+        let kept: Vec<u64> = monitor.get_recent_metrics(10).iter().map(|m| m.timestamp).collect();
+        assert_eq!(kept, vec![3, 2]);
+        
+        // A single snapshot over the cap is still kept on its own
+        monitor.record_frontend_metrics(snapshot(4, 8)).unwrap();
+        let kept: Vec<u64> = monitor.get_recent_metrics(10).iter().map(|m| m.timestamp).collect();
+        assert_eq!(kept, vec![4]);
+    }
+
     #[test]
     fn test_component_analysis() {
         let monitor = FrontendPerformanceMonitor::new();
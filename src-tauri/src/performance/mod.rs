@@ -7,9 +7,12 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+use crate::validation::OperationSource;
+
 pub mod backend;
 pub mod frontend;
 pub mod system;
@@ -38,6 +41,66 @@ pub struct OperationMetrics {
     pub context: HashMap<String, String>,
 }
 
+/// Context key holding the `OperationSource` label of the operation
+pub const SOURCE_CONTEXT_KEY: &str = "source";
+
+/// Context key set to "true" on operations that stopped because they were cancelled
+pub const CANCELLED_CONTEXT_KEY: &str = "cancelled";
+
+/// Cancellation flag shared between a running operation and `PerformanceMonitor::cancel_operation`
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+    operation_id: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create an unregistered token; `PerformanceMonitor::start_operation` registers one per operation
+    pub fn new(operation_id: impl Into<String>) -> Self {
+        Self {
+            operation_id: operation_id.into(),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Id of the operation this token belongs to
+    pub fn operation_id(&self) -> &str {
+        &self.operation_id
+    }
+
+    /// Request cancellation
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Fail with `AppError::Cancelled` once cancellation was requested
+    /// Long-running operations call this between items
+    pub fn check(&self) -> Result<(), crate::error::AppError> {
+        if self.is_cancelled() {
+            return Err(crate::error::AppError::Cancelled {
+                operation_id: self.operation_id.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl OperationMetrics {
+    /// Source that triggered the operation, if it was tagged
+    pub fn source(&self) -> Option<OperationSource> {
+        self.context.get(SOURCE_CONTEXT_KEY).and_then(|label| OperationSource::from_label(label))
+    }
+
+    /// Whether the operation stopped because it was cancelled
+    pub fn was_cancelled(&self) -> bool {
+        self.context.get(CANCELLED_CONTEXT_KEY).is_some_and(|value| value == "true")
+    }
+}
+
 /// System performance metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemMetrics {
@@ -70,6 +133,29 @@ pub struct CacheMetrics {
     pub recent_evictions: u32,
 }
 
+/// Source of live cache statistics, such as the search result cache
+pub trait CacheMetricsProvider: Send + Sync {
+    fn cache_metrics(&self) -> CacheMetrics;
+}
+
+fn cache_metrics_provider() -> &'static Mutex<Option<Arc<dyn CacheMetricsProvider>>> {
+    static PROVIDER: std::sync::OnceLock<Mutex<Option<Arc<dyn CacheMetricsProvider>>>> = std::sync::OnceLock::new();
+    PROVIDER.get_or_init(|| Mutex::new(None))
+}
+
+/// Register the cache whose statistics are reported in collected metrics
+pub fn register_cache_metrics_provider(provider: Arc<dyn CacheMetricsProvider>) {
+    if let Ok(mut current) = cache_metrics_provider().lock() {
+        *current = Some(provider);
+    }
+}
+
+/// Statistics from the registered cache, if one has been registered
+pub fn current_cache_metrics() -> Option<CacheMetrics> {
+    let provider = cache_metrics_provider().lock().ok()?.clone()?;
+    Some(provider.cache_metrics())
+}
+
 /// Performance alert levels
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum AlertLevel {
@@ -109,8 +195,11 @@ pub struct PerformanceSummary {
     pub total_operations: u64,
     /// Average operation duration
     pub avg_operation_duration_ms: f64,
-    /// 95th percentile operation duration
+    /// 95th percentile operation duration, also listed in `duration_percentiles`
     pub p95_operation_duration_ms: f64,
+    /// Operation durations at the configured summary percentiles, lowest first
+    #[serde(default)]
+    pub duration_percentiles: Vec<DurationPercentile>,
     /// Success rate (0.0 - 1.0)
     pub success_rate: f64,
     /// Number of active alerts
@@ -121,6 +210,43 @@ pub struct PerformanceSummary {
     pub memory_efficiency_score: u8,
 }
 
+/// Operation duration at one percentile of a summary
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DurationPercentile {
+    /// Percentile in (0, 100], e.g. 99.0 for p99
+    pub percentile: f64,
+    pub duration_ms: f64,
+}
+
+/// Value at `percentile` (in (0, 100]) of ascending `sorted` samples, by nearest rank
+/// Empty samples give 0; a single sample is every percentile
+pub fn percentile_of(sorted: &[u64], percentile: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((percentile / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1] as f64
+}
+
+/// Time spent in one security validation call
+#[derive(Debug, Clone)]
+struct ValidationSample {
+    /// When the validation finished (milliseconds since epoch)
+    timestamp: u64,
+    /// Validation label, e.g. `context:read_notes` or `note_content`
+    operation: String,
+    duration_ms: f64,
+}
+
+/// Security validation time aggregated for one validation label
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidationTiming {
+    pub count: u64,
+    pub total_ms: f64,
+    pub avg_ms: f64,
+    pub max_ms: f64,
+}
+
 /// Performance budget thresholds
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceBudget {
@@ -132,6 +258,9 @@ pub struct PerformanceBudget {
     pub target_cache_hit_rate: f64,
     /// Maximum CPU usage percentage (0-100)
     pub max_cpu_usage_percent: f64,
+    /// Duration budgets (ms) for specific operation types, overriding `max_operation_duration_ms`
+    #[serde(default)]
+    pub operation_duration_overrides_ms: HashMap<String, u64>,
 }
 
 impl Default for PerformanceBudget {
@@ -141,10 +270,142 @@ impl Default for PerformanceBudget {
             max_memory_usage_bytes: 512 * 1024 * 1024, // 512MB
             target_cache_hit_rate: 0.85,
             max_cpu_usage_percent: 80.0,
+            operation_duration_overrides_ms: HashMap::new(),
         }
     }
 }
 
+/// Settings key holding the name of the applied budget preset
+pub const BUDGET_PRESET_SETTING_KEY: &str = "performance.budget_preset";
+
+/// Settings key holding the configured system alert thresholds, as JSON
+pub const SYSTEM_THRESHOLDS_SETTING_KEY: &str = "performance.system_thresholds";
+
+/// Names of the built-in performance budget presets
+pub const BUDGET_PRESET_NAMES: &[&str] = &["strict", "balanced", "relaxed"];
+
+impl PerformanceBudget {
+    /// Duration budget (ms) for an operation type, falling back to the global default
+    pub fn duration_budget_ms(&self, operation_type: &str) -> u64 {
+        self.operation_duration_overrides_ms
+            .get(operation_type)
+            .copied()
+            .unwrap_or(self.max_operation_duration_ms)
+    }
+
+    /// Get a built-in budget preset by name
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "strict" => Some(Self {
+                max_operation_duration_ms: 50,
+                max_memory_usage_bytes: 256 * 1024 * 1024, // 256MB
+                target_cache_hit_rate: 0.95,
+                max_cpu_usage_percent: 50.0,
+                operation_duration_overrides_ms: HashMap::new(),
+            }),
+            "balanced" => Some(Self::default()),
+            "relaxed" => Some(Self {
+                max_operation_duration_ms: 500,
+                max_memory_usage_bytes: 2 * 1024 * 1024 * 1024, // 2GB
+                target_cache_hit_rate: 0.70,
+                max_cpu_usage_percent: 95.0,
+                operation_duration_overrides_ms: HashMap::new(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Default number of entries retained in each performance history
+pub const DEFAULT_HISTORY_CAPACITY: usize = 1000;
+
+/// Upper bound for configurable history capacities
+pub const MAX_HISTORY_CAPACITY: usize = 100_000;
+
+/// Settings key for the operation history capacity
+pub const OPERATION_HISTORY_SETTING_KEY: &str = "performance.operation_history_size";
+
+/// Settings key for the system metrics history capacity
+pub const SYSTEM_HISTORY_SETTING_KEY: &str = "performance.system_history_size";
+
+/// Settings key for the comma-separated percentiles reported in performance summaries
+pub const SUMMARY_PERCENTILES_SETTING_KEY: &str = "performance.summary_percentiles";
+
+/// Percentiles reported in performance summaries unless configured otherwise
+pub const DEFAULT_SUMMARY_PERCENTILES: &[f64] = &[50.0, 95.0, 99.0];
+
+/// Most percentiles a performance summary may report
+pub const MAX_SUMMARY_PERCENTILES: usize = 10;
+
+/// Parse a comma-separated list of summary percentiles such as "50,95,99.9"
+/// Returns them sorted without duplicates; each must lie in (0, 100]
+pub fn parse_summary_percentiles(value: &str) -> Result<Vec<f64>, crate::error::AppError> {
+    let invalid = |message: String| crate::error::AppError::Validation {
+        field: SUMMARY_PERCENTILES_SETTING_KEY.to_string(),
+        message,
+    };
+    let mut percentiles = value
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.parse::<f64>().ok().filter(|p| *p > 0.0 && *p <= 100.0)
+                .ok_or_else(|| invalid(format!("Invalid percentile '{}': expected a number above 0 and at most 100", part)))
+        })
+        .collect::<Result<Vec<f64>, _>>()?;
+    percentiles.sort_by(f64::total_cmp);
+    percentiles.dedup();
+    if percentiles.is_empty() || percentiles.len() > MAX_SUMMARY_PERCENTILES {
+        return Err(invalid(format!("Between 1 and {} percentiles are required", MAX_SUMMARY_PERCENTILES)));
+    }
+    Ok(percentiles)
+}
+
+/// Settings key for the system metrics collection interval, in seconds
+pub const METRICS_INTERVAL_SETTING_KEY: &str = "performance.metrics_interval";
+
+/// Default system metrics collection interval, in seconds
+pub const DEFAULT_METRICS_INTERVAL_SECS: u64 = 30;
+
+/// Accepted range for the metrics collection interval, in seconds
+pub const MIN_METRICS_INTERVAL_SECS: u64 = 1;
+pub const MAX_METRICS_INTERVAL_SECS: u64 = 300;
+
+/// Settings key enabling scheduled performance report exports
+pub const REPORT_EXPORT_ENABLED_SETTING_KEY: &str = "performance.report_export_enabled";
+
+/// Settings key for the directory scheduled performance reports are written to
+pub const REPORT_EXPORT_DIRECTORY_SETTING_KEY: &str = "performance.report_export_directory";
+
+/// Settings key for the scheduled performance report interval, in seconds
+pub const REPORT_EXPORT_INTERVAL_SETTING_KEY: &str = "performance.report_export_interval";
+
+/// Settings key for the number of scheduled performance reports kept on disk
+pub const REPORT_EXPORT_KEEP_SETTING_KEY: &str = "performance.report_export_keep";
+
+/// Default scheduled performance report interval, in seconds
+pub const DEFAULT_REPORT_EXPORT_INTERVAL_SECS: u64 = 3600;
+
+/// Accepted range for the scheduled performance report interval, in seconds
+pub const MIN_REPORT_EXPORT_INTERVAL_SECS: u64 = 60;
+pub const MAX_REPORT_EXPORT_INTERVAL_SECS: u64 = 7 * 24 * 3600;
+
+/// Default number of scheduled performance reports kept on disk
+pub const DEFAULT_REPORT_EXPORT_KEEP: usize = 24;
+
+/// File name prefix of scheduled performance reports; rotation only touches files with it
+const REPORT_FILE_PREFIX: &str = "performance-report-";
+
+/// Lock a monitor mutex, recovering it if a thread panicked while holding it
+/// Monitor state stays usable after a panic: at worst one entry is half-written,
+/// which beats dropping every later metric
+fn lock_recovering<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| {
+        mutex.clear_poison();
+        poisoned.into_inner()
+    })
+}
+
 /// Main performance monitoring system
 pub struct PerformanceMonitor {
     /// Operation history for analysis
@@ -155,19 +416,78 @@ pub struct PerformanceMonitor {
     alerts: Arc<Mutex<Vec<PerformanceAlert>>>,
     /// Performance budget configuration
     budget: Arc<Mutex<PerformanceBudget>>,
-    /// Current operations in progress
-    active_operations: Arc<Mutex<HashMap<String, Instant>>>,
+    /// Current operations in progress, with their start time and cancellation token
+    active_operations: Arc<Mutex<HashMap<String, (Instant, CancellationToken)>>>,
+    /// Maximum number of retained operation metrics
+    operation_capacity: AtomicUsize,
+    /// Maximum number of retained system metrics
+    system_capacity: AtomicUsize,
+    /// Percentiles reported in summaries, ascending
+    summary_percentiles: Mutex<Vec<f64>>,
+    /// Recent security validation timings, capped like the operation history
+    validation_history: Arc<Mutex<VecDeque<ValidationSample>>>,
 }
 
 impl PerformanceMonitor {
     /// Create new performance monitor
     pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_HISTORY_CAPACITY, DEFAULT_HISTORY_CAPACITY)
+    }
+
+    /// Create new performance monitor with custom history capacities
+    pub fn with_capacity(operation_capacity: usize, system_capacity: usize) -> Self {
+        let operation_capacity = operation_capacity.clamp(1, MAX_HISTORY_CAPACITY);
+        let system_capacity = system_capacity.clamp(1, MAX_HISTORY_CAPACITY);
+        
         Self {
-            operation_history: Arc::new(Mutex::new(VecDeque::with_capacity(1000))),
-            system_history: Arc::new(Mutex::new(VecDeque::with_capacity(1000))),
+            operation_history: Arc::new(Mutex::new(VecDeque::with_capacity(operation_capacity))),
+            system_history: Arc::new(Mutex::new(VecDeque::with_capacity(system_capacity))),
             alerts: Arc::new(Mutex::new(Vec::new())),
             budget: Arc::new(Mutex::new(PerformanceBudget::default())),
             active_operations: Arc::new(Mutex::new(HashMap::new())),
+            operation_capacity: AtomicUsize::new(operation_capacity),
+            system_capacity: AtomicUsize::new(system_capacity),
+            summary_percentiles: Mutex::new(DEFAULT_SUMMARY_PERCENTILES.to_vec()),
+            validation_history: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// Percentiles reported in summaries, ascending
+    pub fn summary_percentiles(&self) -> Vec<f64> {
+        lock_recovering(&self.summary_percentiles).clone()
+    }
+
+    /// Change the percentiles reported in summaries; see `parse_summary_percentiles`
+    pub fn set_summary_percentiles(&self, percentiles: Vec<f64>) {
+        *lock_recovering(&self.summary_percentiles) = percentiles;
+    }
+
+    /// Get the configured (operation, system) history capacities
+    pub fn history_capacity(&self) -> (usize, usize) {
+        (
+            self.operation_capacity.load(Ordering::Relaxed),
+            self.system_capacity.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Change the history capacities, evicting the oldest entries if needed
+    pub fn set_history_capacity(&self, operation_capacity: usize, system_capacity: usize) {
+        let operation_capacity = operation_capacity.clamp(1, MAX_HISTORY_CAPACITY);
+        let system_capacity = system_capacity.clamp(1, MAX_HISTORY_CAPACITY);
+        self.operation_capacity.store(operation_capacity, Ordering::Relaxed);
+        self.system_capacity.store(system_capacity, Ordering::Relaxed);
+
+        {
+            let mut history = lock_recovering(&self.operation_history);
+            while history.len() > operation_capacity {
+                history.pop_front();
+            }
+        }
+        {
+            let mut history = lock_recovering(&self.system_history);
+            while history.len() > system_capacity {
+                history.pop_front();
+            }
         }
     }
 
@@ -179,36 +499,49 @@ impl PerformanceMonitor {
             .unwrap()
             .as_millis() as u64;
         let memory_usage_start = self.get_memory_usage();
+        let cancellation = CancellationToken::new(operation_id.clone());
 
         // Record operation start
-        if let Ok(mut active) = self.active_operations.lock() {
-            active.insert(operation_id.clone(), start_time);
-        }
+        lock_recovering(&self.active_operations).insert(operation_id.clone(), (start_time, cancellation.clone()));
 
         OperationTracker {
             operation_id,
+            cancellation,
             operation_type,
             start_time,
             start_timestamp,
             memory_usage_start,
+            context: HashMap::new(),
             monitor: self,
             completed: false,
         }
     }
 
+    /// Signal the in-progress operation with this id to stop
+    /// Returns false when no such operation is running
+    pub fn cancel_operation(&self, operation_id: &str) -> bool {
+        match lock_recovering(&self.active_operations).get(operation_id) {
+            Some((_, cancellation)) => {
+                cancellation.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Record a completed operation
     pub fn record_operation(&self, metrics: OperationMetrics) {
         // Remove from active operations
-        if let Ok(mut active) = self.active_operations.lock() {
-            active.remove(&metrics.operation_id);
-        }
+        lock_recovering(&self.active_operations).remove(&metrics.operation_id);
 
         // Add to history
-        if let Ok(mut history) = self.operation_history.lock() {
+        {
+            let mut history = lock_recovering(&self.operation_history);
             history.push_back(metrics.clone());
             
-            // Keep only last 1000 operations
-            if history.len() > 1000 {
+            // Keep only the most recent operations up to the configured capacity
+            let capacity = self.operation_capacity.load(Ordering::Relaxed);
+            while history.len() > capacity {
                 history.pop_front();
             }
         }
@@ -217,13 +550,49 @@ impl PerformanceMonitor {
         self.check_budget_violations(&metrics);
     }
 
+    /// Record how long a security validation took, under a label such as `context:read_notes`
+    pub fn record_validation(&self, operation: &str, duration: Duration) {
+        let sample = ValidationSample {
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            operation: operation.to_string(),
+            duration_ms: duration.as_secs_f64() * 1000.0,
+        };
+        let mut history = lock_recovering(&self.validation_history);
+        history.push_back(sample);
+        let capacity = self.operation_capacity.load(Ordering::Relaxed);
+        while history.len() > capacity {
+            history.pop_front();
+        }
+    }
+
+    /// Security validation time over the last `period_hours`, by validation label
+    pub fn get_validation_timings(&self, period_hours: u64) -> HashMap<String, ValidationTiming> {
+        let cutoff_time = Self::period_cutoff(period_hours);
+        let mut timings: HashMap<String, ValidationTiming> = HashMap::new();
+        for sample in lock_recovering(&self.validation_history).iter().filter(|sample| sample.timestamp >= cutoff_time) {
+            let timing = timings.entry(sample.operation.clone()).or_default();
+            timing.count += 1;
+            timing.total_ms += sample.duration_ms;
+            timing.max_ms = timing.max_ms.max(sample.duration_ms);
+        }
+        for timing in timings.values_mut() {
+            timing.avg_ms = timing.total_ms / timing.count as f64;
+        }
+        timings
+    }
+
     /// Record system metrics
     pub fn record_system_metrics(&self, metrics: SystemMetrics) {
-        if let Ok(mut history) = self.system_history.lock() {
+        {
+            let mut history = lock_recovering(&self.system_history);
             history.push_back(metrics.clone());
             
-            // Keep only last 1000 metrics
-            if history.len() > 1000 {
+            // Keep only the most recent metrics up to the configured capacity
+            let capacity = self.system_capacity.load(Ordering::Relaxed);
+            while history.len() > capacity {
                 history.pop_front();
             }
         }
@@ -241,22 +610,38 @@ impl PerformanceMonitor {
 
     /// Get active performance alerts
     pub fn get_active_alerts(&self) -> Vec<PerformanceAlert> {
-        self.alerts.lock()
-            .map(|alerts| alerts.iter().filter(|a| a.is_active).cloned().collect())
-            .unwrap_or_default()
+        lock_recovering(&self.alerts).iter().filter(|a| a.is_active).cloned().collect()
     }
 
     /// Get performance summary for a time period
     pub fn get_performance_summary(&self, period_hours: u64) -> PerformanceSummary {
+        self.summarize(period_hours, |_| true)
+    }
+
+    /// Get performance summary for a time period, counting only operations tagged with `source`
+    pub fn get_performance_summary_by_source(&self, source: &OperationSource, period_hours: u64) -> PerformanceSummary {
+        self.summarize(period_hours, |op| op.source().as_ref() == Some(source))
+    }
+
+    /// Summaries for every source with at least one tagged operation in the period
+    pub fn get_source_breakdown(&self, period_hours: u64) -> HashMap<String, PerformanceSummary> {
+        OperationSource::ALL
+            .iter()
+            .map(|source| (source.as_str().to_string(), self.get_performance_summary_by_source(source, period_hours)))
+            .filter(|(_, summary)| summary.total_operations > 0)
+            .collect()
+    }
+
+    fn summarize<F: Fn(&OperationMetrics) -> bool>(&self, period_hours: u64, include: F) -> PerformanceSummary {
         let cutoff_time = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_millis() as u64 - (period_hours * 3600 * 1000);
 
-        let history = self.operation_history.lock().unwrap();
+        let history = lock_recovering(&self.operation_history);
         let relevant_ops: Vec<_> = history
             .iter()
-            .filter(|op| op.start_timestamp >= cutoff_time)
+            .filter(|op| op.start_timestamp >= cutoff_time && include(op))
             .collect();
 
         if relevant_ops.is_empty() {
@@ -269,6 +654,7 @@ impl PerformanceMonitor {
                 total_operations: 0,
                 avg_operation_duration_ms: 0.0,
                 p95_operation_duration_ms: 0.0,
+                duration_percentiles: Vec::new(),
                 success_rate: 1.0,
                 active_alerts: self.get_active_alerts().len() as u32,
                 performance_score: 100,
@@ -284,11 +670,12 @@ impl PerformanceMonitor {
             .map(|op| op.duration_ms as f64)
             .sum::<f64>() / total_operations as f64;
 
-        // Calculate 95th percentile
         let mut durations: Vec<_> = relevant_ops.iter().map(|op| op.duration_ms).collect();
         durations.sort();
-        let p95_index = ((durations.len() as f64) * 0.95) as usize;
-        let p95_duration = durations.get(p95_index).copied().unwrap_or(0) as f64;
+        let p95_duration = percentile_of(&durations, 95.0);
+        let duration_percentiles = self.summary_percentiles().into_iter()
+            .map(|percentile| DurationPercentile { percentile, duration_ms: percentile_of(&durations, percentile) })
+            .collect();
 
         let performance_score = self.calculate_performance_score(&relevant_ops);
         let memory_efficiency_score = self.calculate_memory_efficiency_score(&relevant_ops);
@@ -302,6 +689,7 @@ impl PerformanceMonitor {
             total_operations,
             avg_operation_duration_ms: avg_duration,
             p95_operation_duration_ms: p95_duration,
+            duration_percentiles,
             success_rate,
             active_alerts: self.get_active_alerts().len() as u32,
             performance_score,
@@ -309,29 +697,57 @@ impl PerformanceMonitor {
         }
     }
 
+    /// Get recorded operation metrics from the last `period_hours`, oldest first
+    pub fn get_operation_history(&self, period_hours: u64) -> Vec<OperationMetrics> {
+        let cutoff_time = Self::period_cutoff(period_hours);
+        lock_recovering(&self.operation_history).iter()
+            .filter(|op| op.start_timestamp >= cutoff_time)
+            .cloned()
+            .collect()
+    }
+
+    /// Get recorded system metrics from the last `period_hours`, oldest first
+    pub fn get_system_history(&self, period_hours: u64) -> Vec<SystemMetrics> {
+        let cutoff_time = Self::period_cutoff(period_hours);
+        lock_recovering(&self.system_history).iter()
+            .filter(|metrics| metrics.timestamp >= cutoff_time)
+            .cloned()
+            .collect()
+    }
+
+    /// Millisecond timestamp marking the start of a lookback period
+    fn period_cutoff(period_hours: u64) -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+            .saturating_sub(period_hours * 3600 * 1000)
+    }
+
     /// Get current performance budget
     pub fn get_budget(&self) -> PerformanceBudget {
-        self.budget.lock()
-            .map(|budget| budget.clone())
-            .unwrap_or_default()
+        lock_recovering(&self.budget).clone()
     }
 
     /// Update performance budget
     pub fn update_budget(&self, new_budget: PerformanceBudget) {
-        if let Ok(mut budget) = self.budget.lock() {
-            *budget = new_budget;
-        }
+        *lock_recovering(&self.budget) = new_budget;
+    }
+
+    /// Set the duration budget for one operation type, replacing any previous override
+    pub fn set_operation_budget(&self, operation_type: &str, max_ms: u64) {
+        lock_recovering(&self.budget).operation_duration_overrides_ms.insert(operation_type.to_string(), max_ms);
     }
 
     /// Check for budget violations and create alerts
     fn check_budget_violations(&self, metrics: &OperationMetrics) {
-        let budget = self.get_budget();
+        let max_duration_ms = self.get_budget().duration_budget_ms(&metrics.operation_type);
         
-        if metrics.duration_ms > budget.max_operation_duration_ms {
+        if metrics.duration_ms > max_duration_ms {
             self.create_alert(
                 AlertLevel::Warning,
                 format!("Operation '{}' exceeded duration budget ({} ms > {} ms)", 
-                       metrics.operation_type, metrics.duration_ms, budget.max_operation_duration_ms),
+                       metrics.operation_type, metrics.duration_ms, max_duration_ms),
                 Some(metrics.clone()),
                 Some("Consider optimizing the operation or increasing the budget".to_string()),
             );
@@ -386,13 +802,12 @@ impl PerformanceMonitor {
             suggested_action,
         };
 
-        if let Ok(mut alerts) = self.alerts.lock() {
-            alerts.push(alert);
-            
-            // Keep only last 100 alerts
-            if alerts.len() > 100 {
-                alerts.remove(0);
-            }
+        let mut alerts = lock_recovering(&self.alerts);
+        alerts.push(alert);
+        
+        // Keep only last 100 alerts
+        if alerts.len() > 100 {
+            alerts.remove(0);
         }
     }
 
@@ -476,19 +891,25 @@ impl Default for PerformanceMonitor {
 /// Operation tracker for measuring individual operation performance
 pub struct OperationTracker<'a> {
     operation_id: String,
+    cancellation: CancellationToken,
     operation_type: String,
     start_time: Instant,
     start_timestamp: u64,
     memory_usage_start: Option<u64>,
+    context: HashMap<String, String>,
     monitor: &'a PerformanceMonitor,
     completed: bool,
 }
 
 impl<'a> OperationTracker<'a> {
-    /// Add context information to the operation
-    pub fn add_context(&mut self, _key: String, _value: String) {
-        // Context would be stored for inclusion in the final metrics
-        // For now, this is a placeholder
+    /// Add context information to the operation, included in the recorded metrics
+    pub fn add_context(&mut self, key: String, value: String) {
+        self.context.insert(key, value);
+    }
+
+    /// Tag the operation with the source that triggered it
+    pub fn set_source(&mut self, source: &OperationSource) {
+        self.add_context(SOURCE_CONTEXT_KEY.to_string(), source.as_str().to_string());
     }
 
     /// Complete the operation with success
@@ -503,6 +924,18 @@ impl<'a> OperationTracker<'a> {
         self.completed = true;
     }
 
+    /// Token that `PerformanceMonitor::cancel_operation` signals for this operation
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
+    /// Complete the operation as cancelled, recorded as a failure tagged with `CANCELLED_CONTEXT_KEY`
+    pub fn complete_cancelled(mut self) {
+        self.add_context(CANCELLED_CONTEXT_KEY.to_string(), "true".to_string());
+        self.complete_with_result(false, Some("Cancelled".to_string()));
+        self.completed = true;
+    }
+
     /// Complete the operation with custom result
     fn complete_with_result(&self, success: bool, error_message: Option<String>) {
         let duration = self.start_time.elapsed();
@@ -517,7 +950,7 @@ impl<'a> OperationTracker<'a> {
             error_message,
             memory_usage_start: self.memory_usage_start,
             memory_usage_end,
-            context: HashMap::new(), // Would include context from add_context calls
+            context: self.context.clone(),
         };
 
         self.monitor.record_operation(metrics);
@@ -541,7 +974,7 @@ impl<'a> Drop for OperationTracker<'a> {
                 error_message: None,
                 memory_usage_start: self.memory_usage_start,
                 memory_usage_end,
-                context: HashMap::new(),
+                context: self.context.clone(),
             };
 
             self.monitor.record_operation(metrics);
@@ -557,41 +990,259 @@ pub fn get_performance_monitor() -> &'static PerformanceMonitor {
     PERFORMANCE_MONITOR.get_or_init(|| PerformanceMonitor::new())
 }
 
+/// Reapply the budget preset stored in settings, if any
+/// Returns the name of the preset that was applied
+pub async fn restore_budget_preset(
+    settings_service: &crate::settings::SettingsService,
+) -> Result<Option<String>, crate::error::AppError> {
+    let Some(name) = settings_service.get_setting(BUDGET_PRESET_SETTING_KEY).await? else {
+        return Ok(None);
+    };
+
+    match PerformanceBudget::preset(&name) {
+        Some(budget) => {
+            get_performance_monitor().update_budget(budget);
+            Ok(Some(name))
+        }
+        None => {
+            log::warn!("Ignoring unknown performance budget preset '{}'", name);
+            Ok(None)
+        }
+    }
+}
+
+/// Reapply the system alert thresholds stored in settings, if any
+/// Stored thresholds that no longer parse or validate are ignored, keeping the defaults
+pub async fn restore_system_thresholds(
+    settings_service: &crate::settings::SettingsService,
+) -> Result<Option<system::SystemThresholds>, crate::error::AppError> {
+    let Some(value) = settings_service.get_setting(SYSTEM_THRESHOLDS_SETTING_KEY).await? else {
+        return Ok(None);
+    };
+
+    let thresholds = match serde_json::from_str::<system::SystemThresholds>(&value) {
+        Ok(thresholds) => thresholds,
+        Err(e) => {
+            log::warn!("Ignoring unreadable system thresholds: {}", e);
+            return Ok(None);
+        }
+    };
+    if let Err(e) = system::get_system_monitor()?.set_thresholds(thresholds.clone()) {
+        log::warn!("Ignoring invalid system thresholds: {}", e);
+        return Ok(None);
+    }
+    Ok(Some(thresholds))
+}
+
+/// Apply the history capacities stored in settings, falling back to the defaults
+pub async fn restore_history_capacity(
+    settings_service: &crate::settings::SettingsService,
+) -> Result<(usize, usize), crate::error::AppError> {
+    let operation_capacity = settings_service.get_int_setting(OPERATION_HISTORY_SETTING_KEY).await?
+        .map(|value| value.max(0) as usize)
+        .unwrap_or(DEFAULT_HISTORY_CAPACITY);
+    let system_capacity = settings_service.get_int_setting(SYSTEM_HISTORY_SETTING_KEY).await?
+        .map(|value| value.max(0) as usize)
+        .unwrap_or(DEFAULT_HISTORY_CAPACITY);
+
+    let monitor = get_performance_monitor();
+    monitor.set_history_capacity(operation_capacity, system_capacity);
+    Ok(monitor.history_capacity())
+}
+
+/// Apply the summary percentiles stored in settings, falling back to the defaults
+/// A stored list that no longer parses is ignored
+pub async fn restore_summary_percentiles(
+    settings_service: &crate::settings::SettingsService,
+) -> Result<Vec<f64>, crate::error::AppError> {
+    let percentiles = match settings_service.get_setting(SUMMARY_PERCENTILES_SETTING_KEY).await? {
+        Some(value) => parse_summary_percentiles(&value).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid summary percentiles: {}", e);
+            DEFAULT_SUMMARY_PERCENTILES.to_vec()
+        }),
+        None => DEFAULT_SUMMARY_PERCENTILES.to_vec(),
+    };
+    get_performance_monitor().set_summary_percentiles(percentiles.clone());
+    Ok(percentiles)
+}
+
+/// Apply the metrics collection interval stored in settings, falling back to the default
+/// Out-of-range stored values are clamped into the accepted range
+pub async fn restore_metrics_interval(
+    settings_service: &crate::settings::SettingsService,
+) -> Result<Duration, crate::error::AppError> {
+    let seconds = settings_service.get_int_setting(METRICS_INTERVAL_SETTING_KEY).await?
+        .map(|value| (value.max(0) as u64).clamp(MIN_METRICS_INTERVAL_SECS, MAX_METRICS_INTERVAL_SECS))
+        .unwrap_or(DEFAULT_METRICS_INTERVAL_SECS);
+    set_metrics_interval(seconds)
+}
+
+/// Channel publishing the metrics collection interval to the background task
+fn metrics_interval_channel() -> &'static tokio::sync::watch::Sender<Duration> {
+    static CHANNEL: std::sync::OnceLock<tokio::sync::watch::Sender<Duration>> = std::sync::OnceLock::new();
+    CHANNEL.get_or_init(|| {
+        tokio::sync::watch::channel(Duration::from_secs(DEFAULT_METRICS_INTERVAL_SECS)).0
+    })
+}
+
+/// Current system metrics collection interval
+pub fn metrics_interval() -> Duration {
+    *metrics_interval_channel().borrow()
+}
+
+/// Change the system metrics collection interval
+/// The running collection task picks up the new interval without restarting
+pub fn set_metrics_interval(seconds: u64) -> Result<Duration, crate::error::AppError> {
+    if !(MIN_METRICS_INTERVAL_SECS..=MAX_METRICS_INTERVAL_SECS).contains(&seconds) {
+        return Err(crate::error::AppError::Validation {
+            field: "seconds".to_string(),
+            message: format!(
+                "Metrics interval must be between {} and {} seconds",
+                MIN_METRICS_INTERVAL_SECS, MAX_METRICS_INTERVAL_SECS
+            ),
+        });
+    }
+
+    let interval = Duration::from_secs(seconds);
+    metrics_interval_channel().send_replace(interval);
+    Ok(interval)
+}
+
+/// Call `collect` on every tick of the interval published by `interval_rx`
+/// A new interval restarts the schedule, so the next tick is one full new period away
+async fn run_metrics_loop<F: FnMut()>(
+    mut interval_rx: tokio::sync::watch::Receiver<Duration>,
+    mut collect: F,
+) {
+    let period = *interval_rx.borrow_and_update();
+    let mut interval = tokio::time::interval(period);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => collect(),
+            changed = interval_rx.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                let period = *interval_rx.borrow_and_update();
+                interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            }
+        }
+    }
+}
+
+/// Where and how often scheduled performance reports are written
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportExportSchedule {
+    pub directory: std::path::PathBuf,
+    pub interval: Duration,
+    /// Number of report files kept; older ones are deleted after each write
+    pub keep: usize,
+}
+
+/// Read the scheduled report export settings; returns `Ok(None)` while exports are disabled
+/// The directory must exist, be free of traversal sequences and be writable
+pub async fn load_report_export_schedule(
+    settings_service: &crate::settings::SettingsService,
+) -> Result<Option<ReportExportSchedule>, crate::error::AppError> {
+    let enabled = settings_service.get_setting(REPORT_EXPORT_ENABLED_SETTING_KEY).await?
+        .map(|value| value == "true")
+        .unwrap_or(false);
+    if !enabled {
+        return Ok(None);
+    }
+
+    let directory = settings_service.get_setting(REPORT_EXPORT_DIRECTORY_SETTING_KEY).await?.unwrap_or_default();
+    let directory = validate_report_directory(&directory)?;
+    let interval = settings_service.get_int_setting(REPORT_EXPORT_INTERVAL_SETTING_KEY).await?
+        .map(|value| (value.max(0) as u64).clamp(MIN_REPORT_EXPORT_INTERVAL_SECS, MAX_REPORT_EXPORT_INTERVAL_SECS))
+        .unwrap_or(DEFAULT_REPORT_EXPORT_INTERVAL_SECS);
+    let keep = settings_service.get_int_setting(REPORT_EXPORT_KEEP_SETTING_KEY).await?
+        .map(|value| value.max(1) as usize)
+        .unwrap_or(DEFAULT_REPORT_EXPORT_KEEP);
+
+    Ok(Some(ReportExportSchedule { directory, interval: Duration::from_secs(interval), keep }))
+}
+
+/// Validate a report destination the same way as export directories, then check it is writable
+pub fn validate_report_directory(dir: &str) -> Result<std::path::PathBuf, crate::error::AppError> {
+    let directory = crate::export::validate_export_directory(dir)?;
+    let probe = directory.join(format!(".{}write-test", REPORT_FILE_PREFIX));
+    std::fs::write(&probe, b"").map_err(|e| crate::error::AppError::Validation {
+        field: "directory".to_string(),
+        message: format!("Report directory is not writable: {}", e),
+    })?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(directory)
+}
+
+/// Write `report` to a timestamped JSON file in `dir`, then rotate old reports
+/// Returns the path of the written file
+pub fn write_report_snapshot<T: Serialize>(
+    report: &T,
+    dir: &std::path::Path,
+    keep: usize,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<std::path::PathBuf, crate::error::AppError> {
+    let path = dir.join(format!("{}{}.json", REPORT_FILE_PREFIX, now.format("%Y%m%dT%H%M%S%.3fZ")));
+    std::fs::write(&path, serde_json::to_string_pretty(report)?)?;
+    rotate_report_files(dir, keep)?;
+    Ok(path)
+}
+
+/// Delete the oldest report files in `dir` so at most `keep` remain
+/// Report names embed their timestamp, so name order is age order; other files are left alone
+pub fn rotate_report_files(dir: &std::path::Path, keep: usize) -> Result<Vec<std::path::PathBuf>, crate::error::AppError> {
+    let mut reports: Vec<std::path::PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path.extension().is_some_and(|ext| ext == "json")
+                && path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with(REPORT_FILE_PREFIX))
+        })
+        .collect();
+    reports.sort();
+
+    let excess = reports.len().saturating_sub(keep);
+    let removed: Vec<std::path::PathBuf> = reports.into_iter().take(excess).collect();
+    for path in &removed {
+        std::fs::remove_file(path)?;
+    }
+    Ok(removed)
+}
+
+/// Record a snapshot of the current system metrics
+fn collect_system_metrics() {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+
+    let system_metrics = SystemMetrics {
+        timestamp,
+        memory_usage: get_performance_monitor().get_memory_usage().unwrap_or(0),
+        cpu_usage: None, // Would be populated with actual CPU usage
+        active_db_connections: 1, // Placeholder
+        operations_in_progress: 0, // Would be tracked
+        cache_stats: current_cache_metrics().unwrap_or(CacheMetrics {
+            total_entries: 0,
+            hit_rate: 0.0,
+            avg_lookup_time_us: 0,
+            memory_usage: 0,
+            recent_evictions: 0,
+        }),
+    };
+
+    get_performance_monitor().record_system_metrics(system_metrics);
+}
+
 /// Initialize performance monitoring system
 pub fn initialize_performance_monitoring() {
     let _monitor = get_performance_monitor();
     
-    // Start background metrics collection
-    tokio::spawn(async {
-        let mut interval = tokio::time::interval(Duration::from_secs(30));
-        
-        loop {
-            interval.tick().await;
-            
-            // Collect system metrics
-            let timestamp = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as u64;
-
-            let system_metrics = SystemMetrics {
-                timestamp,
-                memory_usage: get_performance_monitor().get_memory_usage().unwrap_or(0),
-                cpu_usage: None, // Would be populated with actual CPU usage
-                active_db_connections: 1, // Placeholder
-                operations_in_progress: 0, // Would be tracked
-                cache_stats: CacheMetrics {
-                    total_entries: 0,
-                    hit_rate: 0.0,
-                    avg_lookup_time_us: 0,
-                    memory_usage: 0,
-                    recent_evictions: 0,
-                },
-            };
-
-            get_performance_monitor().record_system_metrics(system_metrics);
-        }
-    });
+    // Start background metrics collection on the configurable interval
+    let interval_rx = metrics_interval_channel().subscribe();
+    tokio::spawn(run_metrics_loop(interval_rx, collect_system_metrics));
 }
 
 #[cfg(test)]
@@ -606,6 +1257,20 @@ mod tests {
         assert_eq!(budget.target_cache_hit_rate, 0.85);
     }
 
+    #[test]
+    fn test_budget_presets() {
+        for name in BUDGET_PRESET_NAMES {
+            assert!(PerformanceBudget::preset(name).is_some(), "Preset '{}' should exist", name);
+        }
+        assert!(PerformanceBudget::preset("unknown").is_none());
+
+        let strict = PerformanceBudget::preset("strict").unwrap();
+        let relaxed = PerformanceBudget::preset("relaxed").unwrap();
+        assert!(strict.max_operation_duration_ms < relaxed.max_operation_duration_ms);
+        assert!(strict.max_memory_usage_bytes < relaxed.max_memory_usage_bytes);
+        assert_eq!(PerformanceBudget::preset("balanced").unwrap().max_operation_duration_ms, 100);
+    }
+
     #[test]
     fn test_operation_tracking() {
         let monitor = PerformanceMonitor::new();
@@ -618,6 +1283,124 @@ mod tests {
         assert_eq!(summary.success_rate, 1.0);
     }
 
+    #[test]
+    fn test_performance_summary_by_source() {
+        let monitor = PerformanceMonitor::new();
+        for i in 0..3 {
+            let mut tracker = monitor.start_operation(format!("plugin_op_{}", i), "note_crud".to_string());
+            tracker.set_source(&OperationSource::Plugin);
+            tracker.complete_success();
+        }
+        let mut tracker = monitor.start_operation("ipc_op".to_string(), "note_crud".to_string());
+        tracker.set_source(&OperationSource::IPC);
+        tracker.complete_error("failed".to_string());
+        // Untagged operations only count towards the overall summary
+        monitor.start_operation("untagged_op".to_string(), "note_crud".to_string()).complete_success();
+
+        let plugin = monitor.get_performance_summary_by_source(&OperationSource::Plugin, 1);
+        assert_eq!(plugin.total_operations, 3);
+        assert_eq!(plugin.success_rate, 1.0);
+
+        let ipc = monitor.get_performance_summary_by_source(&OperationSource::IPC, 1);
+        assert_eq!(ipc.total_operations, 1);
+        assert_eq!(ipc.success_rate, 0.0);
+
+        assert_eq!(monitor.get_performance_summary_by_source(&OperationSource::CLI, 1).total_operations, 0);
+        assert_eq!(monitor.get_performance_summary(1).total_operations, 5);
+
+        let breakdown = monitor.get_source_breakdown(1);
+        assert_eq!(breakdown.len(), 2);
+        assert_eq!(breakdown["plugin"].total_operations, 3);
+        assert_eq!(breakdown["ipc"].total_operations, 1);
+    }
+
+    #[test]
+    fn test_summary_percentiles_on_known_distribution() {
+        let monitor = PerformanceMonitor::new();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let record = |i: u64, duration_ms: u64| monitor.record_operation(OperationMetrics {
+            operation_id: format!("op_{}", i),
+            operation_type: "test".to_string(),
+            start_timestamp: now,
+            duration_ms,
+            success: true,
+            error_message: None,
+            memory_usage_start: None,
+            memory_usage_end: None,
+            context: HashMap::new(),
+        });
+
+        // A single sample is every percentile
+        record(0, 42);
+        let summary = monitor.get_performance_summary(1);
+        assert_eq!(summary.p95_operation_duration_ms, 42.0);
+        assert!(summary.duration_percentiles.iter().all(|p| p.duration_ms == 42.0));
+
+        // Together with the 42 above: one operation for each of 1..=100 ms
+        for duration in 1..=99u64 {
+            record(duration, if duration >= 42 { duration + 1 } else { duration });
+        }
+        let summary = monitor.get_performance_summary(1);
+        assert_eq!(summary.total_operations, 100);
+        let values: Vec<(f64, f64)> = summary.duration_percentiles.iter().map(|p| (p.percentile, p.duration_ms)).collect();
+        assert_eq!(values, vec![(50.0, 50.0), (95.0, 95.0), (99.0, 99.0)]);
+        assert_eq!(summary.p95_operation_duration_ms, 95.0);
+
+        monitor.set_summary_percentiles(parse_summary_percentiles("99.9, 10,10").unwrap());
+        let values: Vec<(f64, f64)> = monitor.get_performance_summary(1).duration_percentiles.iter()
+            .map(|p| (p.percentile, p.duration_ms)).collect();
+        assert_eq!(values, vec![(10.0, 10.0), (99.9, 100.0)]);
+
+        assert!(parse_summary_percentiles("0").is_err());
+        assert!(parse_summary_percentiles("101").is_err());
+        assert!(parse_summary_percentiles("").is_err());
+        assert_eq!(percentile_of(&[], 50.0), 0.0);
+    }
+
+    #[test]
+    fn test_history_capacity_eviction() {
+        let monitor = PerformanceMonitor::with_capacity(3, 2);
+        assert_eq!(monitor.history_capacity(), (3, 2));
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+
+        // Oldest two operations are slow failures, the retained three are fast successes
+        for i in 0..5u64 {
+            monitor.record_operation(OperationMetrics {
+                operation_id: format!("op_{}", i),
+                operation_type: "test".to_string(),
+                start_timestamp: now,
+                duration_ms: if i < 2 { 5000 } else { 10 },
+                success: i >= 2,
+                error_message: None,
+                memory_usage_start: Some(100),
+                memory_usage_end: Some(100),
+                context: HashMap::new(),
+            });
+        }
+
+        {
+            let history = monitor.operation_history.lock().unwrap();
+            assert_eq!(history.len(), 3);
+            assert_eq!(history.front().unwrap().operation_id, "op_2");
+        }
+
+        let summary = monitor.get_performance_summary(1);
+        assert_eq!(summary.total_operations, 3);
+        assert_eq!(summary.success_rate, 1.0);
+        assert_eq!(summary.avg_operation_duration_ms, 10.0);
+
+        // Shrinking the capacity evicts the oldest remaining entries
+        monitor.set_history_capacity(1, 1);
+        assert_eq!(monitor.get_performance_summary(1).total_operations, 1);
+    }
+
     #[test]
     fn test_performance_budget() {
         let monitor = PerformanceMonitor::new();
@@ -626,6 +1409,7 @@ mod tests {
             max_memory_usage_bytes: 1024 * 1024 * 1024,
             target_cache_hit_rate: 0.9,
             max_cpu_usage_percent: 70.0,
+            operation_duration_overrides_ms: HashMap::new(),
         };
         
         monitor.update_budget(new_budget.clone());
@@ -708,6 +1492,7 @@ mod tests {
             max_memory_usage_bytes: 1024,
             target_cache_hit_rate: 0.9,
             max_cpu_usage_percent: 50.0,
+            operation_duration_overrides_ms: HashMap::new(),
         });
         
         // Create an operation that exceeds the budget
@@ -729,4 +1514,113 @@ mod tests {
         assert!(!alerts.is_empty());
         assert!(alerts[0].message.contains("exceeded duration budget"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_operation_budget_override_suppresses_alerts() {
+        let monitor = PerformanceMonitor::new();
+        monitor.set_operation_budget("index_rebuild", 5000);
+        assert_eq!(monitor.get_budget().duration_budget_ms("index_rebuild"), 5000);
+        assert_eq!(monitor.get_budget().duration_budget_ms("note_read"), 100);
+        
+        let operation = |operation_type: &str| OperationMetrics {
+            operation_id: format!("{}_op", operation_type),
+            operation_type: operation_type.to_string(),
+            start_timestamp: 1000,
+            duration_ms: 800,
+            success: true,
+            error_message: None,
+            memory_usage_start: None,
+            memory_usage_end: None,
+            context: HashMap::new(),
+        };
+        
+        // Within its own budget, though past the global default
+        monitor.record_operation(operation("index_rebuild"));
+        assert!(monitor.get_active_alerts().is_empty());
+        
+        monitor.record_operation(operation("note_read"));
+        let alerts = monitor.get_active_alerts();
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].message.contains("'note_read' exceeded duration budget (800 ms > 100 ms)"));
+    }
+
+    #[test]
+    fn test_set_metrics_interval_range() {
+        assert!(set_metrics_interval(0).is_err());
+        assert!(set_metrics_interval(MAX_METRICS_INTERVAL_SECS + 1).is_err());
+        assert_eq!(set_metrics_interval(MAX_METRICS_INTERVAL_SECS).unwrap(), Duration::from_secs(MAX_METRICS_INTERVAL_SECS));
+        assert_eq!(metrics_interval(), Duration::from_secs(MAX_METRICS_INTERVAL_SECS));
+        set_metrics_interval(DEFAULT_METRICS_INTERVAL_SECS).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_metrics_interval_change_updates_tick_duration() {
+        let (interval_tx, interval_rx) = tokio::sync::watch::channel(Duration::from_secs(30));
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let counter = ticks.clone();
+        let task = tokio::spawn(run_metrics_loop(interval_rx, move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        // First tick fires immediately, the next one a full 30s period later
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert_eq!(ticks.load(Ordering::SeqCst), 1);
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        assert_eq!(ticks.load(Ordering::SeqCst), 2);
+
+        // After switching to 2s, ticks arrive every 2s
+        interval_tx.send(Duration::from_secs(2)).unwrap();
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert_eq!(ticks.load(Ordering::SeqCst), 2);
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        assert_eq!(ticks.load(Ordering::SeqCst), 7);
+
+        // Closing the channel stops the task
+        drop(interval_tx);
+        task.await.unwrap();
+    }
+
+    #[test]
+    fn test_record_operation_recovers_poisoned_history() {
+        let monitor = Arc::new(PerformanceMonitor::new());
+        let poisoner = monitor.clone();
+        let result = std::thread::spawn(move || {
+            let _history = poisoner.operation_history.lock().unwrap();
+            panic!("panic while holding the history lock");
+        }).join();
+        assert!(result.is_err());
+        assert!(monitor.operation_history.is_poisoned());
+
+        let tracker = monitor.start_operation("after_panic".to_string(), "test".to_string());
+        tracker.complete_success();
+
+        assert!(!monitor.operation_history.is_poisoned());
+        let history = monitor.get_operation_history(1);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].operation_id, "after_panic");
+        assert_eq!(monitor.get_performance_summary(1).total_operations, 1);
+    }
+
+    #[test]
+    fn test_report_rotation_deletes_oldest_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = validate_report_directory(&temp_dir.path().to_string_lossy()).unwrap();
+        std::fs::write(dir.join("unrelated.json"), "{}").unwrap();
+        let start = chrono::Utc::now();
+
+        let written: Vec<std::path::PathBuf> = (0..4)
+            .map(|i| {
+                let report = serde_json::json!({ "snapshot": i });
+                write_report_snapshot(&report, &dir, 3, start + chrono::Duration::seconds(i)).unwrap()
+            })
+            .collect();
+
+        // The fourth write pushed the count past the cap, removing only the oldest report
+        assert!(!written[0].exists());
+        assert!(written[1..].iter().all(|path| path.exists()));
+        assert!(dir.join("unrelated.json").exists());
+        assert_eq!(std::fs::read_to_string(&written[3]).unwrap(), serde_json::to_string_pretty(&serde_json::json!({ "snapshot": 3 })).unwrap());
+
+        assert!(validate_report_directory(&format!("{}/../escape", temp_dir.path().display())).is_err());
+    }
+}
@@ -22,9 +22,28 @@ pub struct DetailedSystemMetrics {
     pub process: ProcessMetrics,
     pub platform: PlatformInfo,
     pub timestamp: u64,
+    /// Which components were actually collected; the rest hold zeroed placeholders
+    pub components: ComponentAvailability,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Per-component record of whether metrics could be collected
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComponentAvailability {
+    pub cpu: bool,
+    pub memory: bool,
+    pub disk: bool,
+    pub network: bool,
+    pub process: bool,
+}
+
+impl ComponentAvailability {
+    /// Whether any component that feeds the performance score was collected
+    pub fn has_resource_data(&self) -> bool {
+        self.cpu || self.memory || self.disk
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CpuMetrics {
     pub overall_usage: f32,
     pub per_core_usage: Vec<f32>,
@@ -40,7 +59,7 @@ pub struct LoadAverage {
     pub fifteen_minutes: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MemoryMetrics {
     pub total: u64,       // bytes
     pub used: u64,        // bytes
@@ -53,7 +72,7 @@ pub struct MemoryMetrics {
     pub cached: Option<u64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DiskMetrics {
     pub read_bytes: u64,
     pub write_bytes: u64,
@@ -97,7 +116,7 @@ pub struct NetworkInterface {
     pub speed: Option<u64>, // Mbps
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProcessMetrics {
     pub cpu_usage: f32,
     pub memory_usage: u64,     // bytes
@@ -110,6 +129,9 @@ pub struct ProcessMetrics {
     pub uptime: u64,               // seconds
 }
 
+/// Placeholder reported for platform fields that could not be determined
+pub const UNKNOWN_PLATFORM_VALUE: &str = "unknown";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlatformInfo {
     pub os_name: String,
@@ -122,7 +144,7 @@ pub struct PlatformInfo {
     pub kernel_version: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SystemThresholds {
     pub cpu_warning: f32,      // %
     pub cpu_critical: f32,     // %
@@ -149,8 +171,43 @@ impl Default for SystemThresholds {
     }
 }
 
+impl SystemThresholds {
+    /// Check that each warning level is below its critical level and percentages lie within 0-100
+    pub fn validate(&self) -> Result<(), AppError> {
+        let percentages = [
+            ("cpu", self.cpu_warning as f64, self.cpu_critical as f64),
+            ("memory", self.memory_warning, self.memory_critical),
+            ("disk", self.disk_warning, self.disk_critical),
+        ];
+        for (metric, warning, critical) in percentages {
+            if !(0.0..=100.0).contains(&warning) || !(0.0..=100.0).contains(&critical) {
+                return Err(AppError::Validation {
+                    field: metric.to_string(),
+                    message: format!("{} thresholds must be between 0 and 100%", metric),
+                });
+            }
+            if warning >= critical {
+                return Err(AppError::Validation {
+                    field: metric.to_string(),
+                    message: format!("{} warning threshold must be below the critical threshold", metric),
+                });
+            }
+        }
+        if self.response_warning >= self.response_critical {
+            return Err(AppError::Validation {
+                field: "response".to_string(),
+                message: "response warning threshold must be below the critical threshold".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemAnalysis {
+    /// False when no resource metrics could be collected; scores and statuses are then placeholders
+    pub data_available: bool,
+    pub components: ComponentAvailability,
     pub overall_health: String,
     pub performance_score: f64,
     pub bottlenecks: Vec<String>,
@@ -189,6 +246,39 @@ pub struct SystemAlert {
     pub recommendation: Option<String>,
 }
 
+/// Status reported for components whose metrics could not be collected
+pub const UNAVAILABLE_STATUS: &str = "unknown";
+
+impl SystemAnalysis {
+    /// Placeholder analysis for when no resource metrics could be collected
+    pub fn unavailable(components: ComponentAvailability) -> Self {
+        let unknown = || UNAVAILABLE_STATUS.to_string();
+        Self {
+            data_available: false,
+            components,
+            overall_health: unknown(),
+            performance_score: 0.0,
+            bottlenecks: Vec::new(),
+            recommendations: Vec::new(),
+            resource_utilization: ResourceUtilization {
+                cpu_status: unknown(),
+                memory_status: unknown(),
+                disk_status: unknown(),
+                network_status: unknown(),
+                overall_status: unknown(),
+            },
+            trend_analysis: TrendAnalysis {
+                cpu_trend: unknown(),
+                memory_trend: unknown(),
+                disk_trend: unknown(),
+                performance_trend: unknown(),
+                prediction: None,
+            },
+            alerts: Vec::new(),
+        }
+    }
+}
+
 pub struct SystemMonitor {
     #[allow(dead_code)] system: Arc<Mutex<System>>,
     thresholds: Arc<Mutex<SystemThresholds>>,
@@ -211,6 +301,18 @@ impl SystemMonitor {
         })
     }
 
+    /// Get platform information, collecting it on first use
+    pub fn get_platform_info(&self) -> Result<PlatformInfo, AppError> {
+        let mut platform_guard = self.platform_info.lock()
+            .map_err(|e| AppError::Runtime { message: format!("Lock error: {}", e) })?;
+        
+        if platform_guard.is_none() {
+            *platform_guard = Some(Self::collect_platform_info()?);
+        }
+        
+        Ok(platform_guard.as_ref().unwrap().clone())
+    }
+
     /// Get current system metrics
     pub async fn get_system_metrics(&self) -> Result<SystemMetrics, AppError> {
         let detailed = self.get_detailed_metrics().await?;
@@ -300,8 +402,14 @@ impl SystemMonitor {
         }
     }
 
-    /// Set performance thresholds
+    /// Current performance thresholds
+    pub fn get_thresholds(&self) -> SystemThresholds {
+        self.thresholds.lock().map(|thresholds| thresholds.clone()).unwrap_or_default()
+    }
+
+    /// Set performance thresholds, rejecting invalid ones (see `SystemThresholds::validate`)
     pub fn set_thresholds(&self, thresholds: SystemThresholds) -> Result<(), AppError> {
+        thresholds.validate()?;
         if let Ok(mut current_thresholds) = self.thresholds.lock() {
             *current_thresholds = thresholds;
             Ok(())
@@ -323,39 +431,60 @@ impl SystemMonitor {
     }
 
     /// Analyze system performance
+    ///
+    /// Components that could not be collected are skipped rather than failing the
+    /// analysis; with no usable metrics at all a placeholder analysis is returned
+    /// with `data_available` unset.
     pub fn analyze_performance(&self) -> Result<SystemAnalysis, AppError> {
         let recent_metrics = self.get_recent_metrics(60); // Last hour
         
-        if recent_metrics.is_empty() {
-            return Err(AppError::Validation {
-                field: "system_metrics".to_string(),
-                message: "No system metrics available for analysis".to_string()
-            });
-        }
+        let latest = match recent_metrics.last() {
+            Some(latest) if latest.components.has_resource_data() => latest,
+            latest => {
+                let components = latest.map(|m| m.components).unwrap_or_default();
+                return Ok(SystemAnalysis::unavailable(components));
+            }
+        };
 
         let thresholds = self.thresholds.lock()
             .map_err(|e| AppError::Runtime { message: format!("Lock error: {}", e) })?;
-
-        let latest = recent_metrics.last().unwrap();
+        let components = latest.components;
         
-        // Calculate performance score
-        let cpu_score = Self::calculate_resource_score(latest.cpu.overall_usage as f64, 100.0);
-        let memory_score = Self::calculate_resource_score(latest.memory.usage_percent, 100.0);
-        let disk_score = if latest.disk_io.disks.is_empty() {
-            100.0
-        } else {
-            latest.disk_io.disks.iter()
-                .map(|disk| Self::calculate_resource_score(disk.usage_percent, 100.0))
-                .fold(0.0f64, |acc, x| acc.min(x))
-        };
+        // Calculate performance score from the components that were collected
+        let mut scores = Vec::new();
+        if components.cpu {
+            scores.push(Self::calculate_resource_score(latest.cpu.overall_usage as f64, 100.0));
+        }
+        if components.memory {
+            scores.push(Self::calculate_resource_score(latest.memory.usage_percent, 100.0));
+        }
+        if components.disk {
+            scores.push(if latest.disk_io.disks.is_empty() {
+                100.0
+            } else {
+                latest.disk_io.disks.iter()
+                    .map(|disk| Self::calculate_resource_score(disk.usage_percent, 100.0))
+                    .fold(100.0f64, |acc, x| acc.min(x))
+            });
+        }
         
-        let performance_score = (cpu_score + memory_score + disk_score) / 3.0;
+        let performance_score = scores.iter().sum::<f64>() / scores.len() as f64;
         
         // Determine resource utilization status
         let resource_utilization = ResourceUtilization {
-            cpu_status: Self::get_status_from_usage(latest.cpu.overall_usage as f64, thresholds.cpu_warning as f64, thresholds.cpu_critical as f64),
-            memory_status: Self::get_status_from_usage(latest.memory.usage_percent, thresholds.memory_warning, thresholds.memory_critical),
-            disk_status: if latest.disk_io.disks.is_empty() {
+            cpu_status: if components.cpu {
+                Self::get_status_from_usage(latest.cpu.overall_usage as f64, thresholds.cpu_warning as f64, thresholds.cpu_critical as f64)
+            } else {
+                UNAVAILABLE_STATUS.to_string()
+            },
+            memory_status: if components.memory {
+                Self::get_status_from_usage(latest.memory.usage_percent, thresholds.memory_warning, thresholds.memory_critical)
+            } else {
+                UNAVAILABLE_STATUS.to_string()
+            },
+            disk_status: if !components.disk {
+                UNAVAILABLE_STATUS.to_string()
+            } else if latest.disk_io.disks.is_empty() {
                 "normal".to_string()
             } else {
                 let max_disk_usage = latest.disk_io.disks.iter()
@@ -363,7 +492,7 @@ impl SystemMonitor {
                     .fold(0.0f64, |acc, x| acc.max(x));
                 Self::get_status_from_usage(max_disk_usage, thresholds.disk_warning, thresholds.disk_critical)
             },
-            network_status: "normal".to_string(), // Simplified for now
+            network_status: if components.network { "normal" } else { UNAVAILABLE_STATUS }.to_string(), // Simplified for now
             overall_status: Self::get_overall_status(performance_score),
         };
 
@@ -371,12 +500,14 @@ impl SystemMonitor {
         let trend_analysis = Self::analyze_trends(&recent_metrics);
         
         // Generate bottlenecks and recommendations
-        let (bottlenecks, recommendations) = Self::identify_bottlenecks_and_recommendations(&latest, &thresholds);
+        let (bottlenecks, recommendations) = Self::identify_bottlenecks_and_recommendations(latest, &thresholds);
         
         // Generate alerts
-        let alerts = Self::generate_alerts(&latest, &thresholds);
+        let alerts = Self::generate_alerts(latest, &thresholds);
         
         Ok(SystemAnalysis {
+            data_available: true,
+            components,
             overall_health: resource_utilization.overall_status.clone(),
             performance_score,
             bottlenecks,
@@ -388,6 +519,9 @@ impl SystemMonitor {
     }
 
     /// Collect system metrics from the OS
+    ///
+    /// Individual collectors may fail on restricted platforms; their components are
+    /// reported as unavailable instead of failing the whole collection.
     async fn collect_system_metrics(platform_info: &Arc<Mutex<Option<PlatformInfo>>>) -> Result<DetailedSystemMetrics, AppError> {
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -406,53 +540,98 @@ impl SystemMonitor {
             platform_guard.as_ref().unwrap().clone()
         };
 
-        // Collect CPU metrics
-        let cpu = Self::collect_cpu_metrics().await?;
-        
-        // Collect memory metrics
-        let memory = Self::collect_memory_metrics()?;
-        
-        // Collect disk I/O metrics
-        let disk_io = Self::collect_disk_metrics()?;
+        let cpu = Self::collect_cpu_metrics().await;
         
-        // Collect network metrics (optional)
-        let network = Self::collect_network_metrics().ok();
-        
-        // Collect process metrics
-        let process = Self::collect_process_metrics()?;
+        Ok(Self::assemble_metrics(
+            timestamp,
+            platform,
+            cpu,
+            Self::collect_memory_metrics(),
+            Self::collect_disk_metrics(),
+            Self::collect_network_metrics(),
+            Self::collect_process_metrics(),
+        ))
+    }
+
+    /// Combine per-component collection results, substituting zeroed placeholders for failures
+    fn assemble_metrics(
+        timestamp: u64,
+        platform: PlatformInfo,
+        cpu: Result<CpuMetrics, AppError>,
+        memory: Result<MemoryMetrics, AppError>,
+        disk_io: Result<DiskMetrics, AppError>,
+        network: Result<NetworkMetrics, AppError>,
+        process: Result<ProcessMetrics, AppError>,
+    ) -> DetailedSystemMetrics {
+        fn component<T: Default>(name: &str, result: Result<T, AppError>) -> (T, bool) {
+            match result {
+                Ok(metrics) => (metrics, true),
+                Err(e) => {
+                    log::warn!("{} metrics unavailable: {}", name, e);
+                    (T::default(), false)
+                }
+            }
+        }
 
-        Ok(DetailedSystemMetrics {
+        let (cpu, cpu_available) = component("CPU", cpu);
+        let (memory, memory_available) = component("Memory", memory);
+        let (disk_io, disk_available) = component("Disk", disk_io);
+        let (process, process_available) = component("Process", process);
+        let network_available = network.is_ok();
+
+        DetailedSystemMetrics {
             cpu,
             memory,
             disk_io,
-            network,
+            network: network.ok(),
             process,
             platform,
             timestamp,
-        })
+            components: ComponentAvailability {
+                cpu: cpu_available,
+                memory: memory_available,
+                disk: disk_available,
+                network: network_available,
+                process: process_available,
+            },
+        }
     }
 
     /// Collect platform information
     fn collect_platform_info() -> Result<PlatformInfo, AppError> {
-        // This would use platform-specific APIs to gather system information
-        // For now, provide a basic implementation
-        
         let os_name = std::env::consts::OS.to_string();
         let architecture = std::env::consts::ARCH.to_string();
+        
+        // Hostname lookup failure is not fatal - report a sentinel instead
         let hostname = hostname::get()
-            .map_err(|e| AppError::Runtime { message: format!("Failed to get hostname: {}", e) })?
-            .to_string_lossy()
-            .to_string();
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to get hostname: {}", e);
+                UNKNOWN_PLATFORM_VALUE.to_string()
+            });
+        
+        let os_version = System::long_os_version()
+            .or_else(System::os_version)
+            .unwrap_or_else(|| UNKNOWN_PLATFORM_VALUE.to_string());
+        
+        let mut system = System::new();
+        system.refresh_cpu_all();
+        let cpu_model = system.cpus()
+            .first()
+            .map(|cpu| cpu.brand().trim().to_string())
+            .filter(|brand| !brand.is_empty());
+        
+        let boot_time = Some(System::boot_time()).filter(|&time| time > 0);
         
         Ok(PlatformInfo {
             os_name,
-            os_version: "unknown".to_string(), // Would query OS version
+            os_version,
             architecture,
             hostname,
             cpu_cores: num_cpus::get() as u32,
-            cpu_model: None, // Would query CPU model
-            boot_time: None, // Would query system boot time
-            kernel_version: None, // Would query kernel version
+            cpu_model,
+            boot_time,
+            kernel_version: System::kernel_version(),
         })
     }
 
@@ -520,30 +699,14 @@ impl SystemMonitor {
     fn collect_disk_metrics() -> Result<DiskMetrics, AppError> {
         // Disk metrics collection disabled in sysinfo 0.37
         // The disk API has changed significantly
-        Ok(DiskMetrics {
-            read_bytes: 0,
-            write_bytes: 0,
-            read_ops: 0,
-            write_ops: 0,
-            read_time: 0,
-            write_time: 0,
-            disks: Vec::new(),
-        })
+        Err(AppError::Runtime { message: "Disk metrics are not supported on this build".to_string() })
     }
 
     /// Collect network metrics (optional)
     fn collect_network_metrics() -> Result<NetworkMetrics, AppError> {
         // Network metrics collection disabled in sysinfo 0.37
         // The networks API has changed significantly
-        Ok(NetworkMetrics {
-            bytes_sent: 0,
-            bytes_received: 0,
-            packets_sent: 0,
-            packets_received: 0,
-            errors_sent: 0,
-            errors_received: 0,
-            interfaces: Vec::new(),
-        })
+        Err(AppError::Runtime { message: "Network metrics are not supported on this build".to_string() })
     }
 
     /// Collect process metrics for the current process
@@ -577,18 +740,7 @@ impl SystemMonitor {
                 uptime,
             })
         } else {
-            // Return default metrics if process not found
-            Ok(ProcessMetrics {
-                cpu_usage: 0.0,
-                memory_usage: 0,
-                memory_percent: 0.0,
-                disk_read: 0,
-                disk_written: 0,
-                thread_count: 1,
-                handle_count: None,
-                start_time: 0,
-                uptime: 0,
-            })
+            Err(AppError::Runtime { message: format!("Process {} not found", pid) })
         }
     }
 
@@ -628,6 +780,11 @@ impl SystemMonitor {
 
     /// Analyze trends from historical metrics
     fn analyze_trends(metrics: &[DetailedSystemMetrics]) -> TrendAnalysis {
+        // Samples with placeholder CPU or memory values would skew the averages
+        let metrics: Vec<&DetailedSystemMetrics> = metrics.iter()
+            .filter(|m| m.components.cpu && m.components.memory)
+            .collect();
+
         if metrics.len() < 2 {
             return TrendAnalysis {
                 cpu_trend: "stable".to_string(),
@@ -692,21 +849,25 @@ impl SystemMonitor {
         let mut recommendations = Vec::new();
 
         // CPU analysis
-        if metrics.cpu.overall_usage >= thresholds.cpu_critical {
-            bottlenecks.push("CPU usage is critically high".to_string());
-            recommendations.push("Consider closing unnecessary applications or upgrading CPU".to_string());
-        } else if metrics.cpu.overall_usage >= thresholds.cpu_warning {
-            bottlenecks.push("CPU usage is high".to_string());
-            recommendations.push("Monitor CPU-intensive processes".to_string());
+        if metrics.components.cpu {
+            if metrics.cpu.overall_usage >= thresholds.cpu_critical {
+                bottlenecks.push("CPU usage is critically high".to_string());
+                recommendations.push("Consider closing unnecessary applications or upgrading CPU".to_string());
+            } else if metrics.cpu.overall_usage >= thresholds.cpu_warning {
+                bottlenecks.push("CPU usage is high".to_string());
+                recommendations.push("Monitor CPU-intensive processes".to_string());
+            }
         }
 
         // Memory analysis
-        if metrics.memory.usage_percent >= thresholds.memory_critical {
-            bottlenecks.push("Memory usage is critically high".to_string());
-            recommendations.push("Close memory-intensive applications or add more RAM".to_string());
-        } else if metrics.memory.usage_percent >= thresholds.memory_warning {
-            bottlenecks.push("Memory usage is high".to_string());
-            recommendations.push("Consider restarting applications to free memory".to_string());
+        if metrics.components.memory {
+            if metrics.memory.usage_percent >= thresholds.memory_critical {
+                bottlenecks.push("Memory usage is critically high".to_string());
+                recommendations.push("Close memory-intensive applications or add more RAM".to_string());
+            } else if metrics.memory.usage_percent >= thresholds.memory_warning {
+                bottlenecks.push("Memory usage is high".to_string());
+                recommendations.push("Consider restarting applications to free memory".to_string());
+            }
         }
 
         // Disk analysis
@@ -734,49 +895,53 @@ impl SystemMonitor {
         let timestamp = metrics.timestamp;
 
         // CPU alerts
-        if metrics.cpu.overall_usage >= thresholds.cpu_critical {
-            alerts.push(SystemAlert {
-                level: "critical".to_string(),
-                category: "cpu".to_string(),
-                message: format!("CPU usage is critically high at {:.1}%", metrics.cpu.overall_usage),
-                timestamp,
-                metric_value: Some(metrics.cpu.overall_usage as f64),
-                threshold_value: Some(thresholds.cpu_critical as f64),
-                recommendation: Some("Close unnecessary applications immediately".to_string()),
-            });
-        } else if metrics.cpu.overall_usage >= thresholds.cpu_warning {
-            alerts.push(SystemAlert {
-                level: "warning".to_string(),
-                category: "cpu".to_string(),
-                message: format!("CPU usage is high at {:.1}%", metrics.cpu.overall_usage),
-                timestamp,
-                metric_value: Some(metrics.cpu.overall_usage as f64),
-                threshold_value: Some(thresholds.cpu_warning as f64),
-                recommendation: Some("Monitor CPU-intensive processes".to_string()),
-            });
+        if metrics.components.cpu {
+            if metrics.cpu.overall_usage >= thresholds.cpu_critical {
+                alerts.push(SystemAlert {
+                    level: "critical".to_string(),
+                    category: "cpu".to_string(),
+                    message: format!("CPU usage is critically high at {:.1}%", metrics.cpu.overall_usage),
+                    timestamp,
+                    metric_value: Some(metrics.cpu.overall_usage as f64),
+                    threshold_value: Some(thresholds.cpu_critical as f64),
+                    recommendation: Some("Close unnecessary applications immediately".to_string()),
+                });
+            } else if metrics.cpu.overall_usage >= thresholds.cpu_warning {
+                alerts.push(SystemAlert {
+                    level: "warning".to_string(),
+                    category: "cpu".to_string(),
+                    message: format!("CPU usage is high at {:.1}%", metrics.cpu.overall_usage),
+                    timestamp,
+                    metric_value: Some(metrics.cpu.overall_usage as f64),
+                    threshold_value: Some(thresholds.cpu_warning as f64),
+                    recommendation: Some("Monitor CPU-intensive processes".to_string()),
+                });
+            }
         }
 
         // Memory alerts
-        if metrics.memory.usage_percent >= thresholds.memory_critical {
-            alerts.push(SystemAlert {
-                level: "critical".to_string(),
-                category: "memory".to_string(),
-                message: format!("Memory usage is critically high at {:.1}%", metrics.memory.usage_percent),
-                timestamp,
-                metric_value: Some(metrics.memory.usage_percent),
-                threshold_value: Some(thresholds.memory_critical),
-                recommendation: Some("Close memory-intensive applications immediately".to_string()),
-            });
-        } else if metrics.memory.usage_percent >= thresholds.memory_warning {
-            alerts.push(SystemAlert {
-                level: "warning".to_string(),
-                category: "memory".to_string(),
-                message: format!("Memory usage is high at {:.1}%", metrics.memory.usage_percent),
-                timestamp,
-                metric_value: Some(metrics.memory.usage_percent),
-                threshold_value: Some(thresholds.memory_warning),
-                recommendation: Some("Consider restarting applications".to_string()),
-            });
+        if metrics.components.memory {
+            if metrics.memory.usage_percent >= thresholds.memory_critical {
+                alerts.push(SystemAlert {
+                    level: "critical".to_string(),
+                    category: "memory".to_string(),
+                    message: format!("Memory usage is critically high at {:.1}%", metrics.memory.usage_percent),
+                    timestamp,
+                    metric_value: Some(metrics.memory.usage_percent),
+                    threshold_value: Some(thresholds.memory_critical),
+                    recommendation: Some("Close memory-intensive applications immediately".to_string()),
+                });
+            } else if metrics.memory.usage_percent >= thresholds.memory_warning {
+                alerts.push(SystemAlert {
+                    level: "warning".to_string(),
+                    category: "memory".to_string(),
+                    message: format!("Memory usage is high at {:.1}%", metrics.memory.usage_percent),
+                    timestamp,
+                    metric_value: Some(metrics.memory.usage_percent),
+                    threshold_value: Some(thresholds.memory_warning),
+                    recommendation: Some("Consider restarting applications".to_string()),
+                });
+            }
         }
 
         alerts
@@ -816,6 +981,48 @@ mod tests {
         assert_eq!(thresholds.memory_critical, 95.0);
     }
 
+    #[test]
+    fn test_set_thresholds_validates_levels() {
+        let monitor = SystemMonitor::new().unwrap();
+        let valid = SystemThresholds {
+            cpu_warning: 60.0,
+            cpu_critical: 85.0,
+            memory_warning: 75.0,
+            memory_critical: 90.0,
+            disk_warning: 80.0,
+            disk_critical: 99.0,
+            response_warning: 500,
+            response_critical: 2000,
+        };
+        monitor.set_thresholds(valid.clone()).unwrap();
+        assert_eq!(monitor.get_thresholds(), valid);
+
+        // Warning above critical is rejected and leaves the current thresholds in place
+        let inverted = SystemThresholds { memory_warning: 95.0, memory_critical: 90.0, ..valid.clone() };
+        assert!(matches!(monitor.set_thresholds(inverted), Err(AppError::Validation { ref field, .. }) if field == "memory"));
+        let inverted = SystemThresholds { response_warning: 3000, ..valid.clone() };
+        assert!(monitor.set_thresholds(inverted).is_err());
+        let out_of_range = SystemThresholds { cpu_critical: 120.0, ..valid.clone() };
+        assert!(monitor.set_thresholds(out_of_range).is_err());
+        assert_eq!(monitor.get_thresholds(), valid);
+    }
+
+    #[test]
+    fn test_platform_info() {
+        let monitor = SystemMonitor::new().unwrap();
+        let info = monitor.get_platform_info().unwrap();
+        
+        assert_eq!(info.os_name, std::env::consts::OS);
+        assert_eq!(info.architecture, std::env::consts::ARCH);
+        assert!(info.cpu_cores > 0);
+        assert!(!info.hostname.is_empty());
+        assert!(!info.os_version.is_empty());
+        
+        // Cached after the first call
+        let again = monitor.get_platform_info().unwrap();
+        assert_eq!(info.hostname, again.hostname);
+    }
+
     #[test]
     fn test_calculate_resource_score() {
         assert_eq!(SystemMonitor::calculate_resource_score(0.0, 100.0), 100.0);
@@ -847,6 +1054,67 @@ mod tests {
         assert_eq!(SystemMonitor::determine_trend(50.0, 52.0), "stable");
     }
 
+    fn collection_denied() -> AppError {
+        AppError::Runtime { message: "Access denied".to_string() }
+    }
+
+    #[test]
+    fn test_collection_failures_yield_partial_analysis() {
+        let monitor = SystemMonitor::new().unwrap();
+        let platform = monitor.get_platform_info().unwrap();
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        let memory = MemoryMetrics { total: 1000, used: 400, available: 600, usage_percent: 40.0, ..Default::default() };
+
+        let metrics = SystemMonitor::assemble_metrics(
+            timestamp,
+            platform,
+            Err(collection_denied()),
+            Ok(memory),
+            Err(collection_denied()),
+            Err(collection_denied()),
+            Err(collection_denied()),
+        );
+        assert_eq!(metrics.components, ComponentAvailability { memory: true, ..Default::default() });
+        assert!(metrics.network.is_none());
+        monitor.metrics_history.lock().unwrap().push(metrics);
+
+        let analysis = monitor.analyze_performance().unwrap();
+        assert!(analysis.data_available);
+        assert!(!analysis.components.cpu);
+        assert_eq!(analysis.performance_score, 60.0);
+        assert_eq!(analysis.resource_utilization.cpu_status, UNAVAILABLE_STATUS);
+        assert_eq!(analysis.resource_utilization.disk_status, UNAVAILABLE_STATUS);
+        assert_eq!(analysis.resource_utilization.memory_status, "low");
+        assert!(analysis.alerts.is_empty());
+    }
+
+    #[test]
+    fn test_analysis_without_metrics_is_unavailable() {
+        let monitor = SystemMonitor::new().unwrap();
+        let analysis = monitor.analyze_performance().unwrap();
+        assert!(!analysis.data_available);
+        assert_eq!(analysis.overall_health, UNAVAILABLE_STATUS);
+
+        // Every collector failing still produces a valid, empty analysis
+        let platform = monitor.get_platform_info().unwrap();
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        let metrics = SystemMonitor::assemble_metrics(
+            timestamp,
+            platform,
+            Err(collection_denied()),
+            Err(collection_denied()),
+            Err(collection_denied()),
+            Err(collection_denied()),
+            Err(collection_denied()),
+        );
+        monitor.metrics_history.lock().unwrap().push(metrics);
+
+        let analysis = monitor.analyze_performance().unwrap();
+        assert!(!analysis.data_available);
+        assert_eq!(analysis.components, ComponentAvailability::default());
+        assert!(analysis.bottlenecks.is_empty());
+    }
+
     #[tokio::test]
     async fn test_system_monitor_creation() {
         let monitor = SystemMonitor::new();
@@ -1,9 +1,11 @@
 use crate::error::AppError;
 use crate::models::NoteFormat;
 use crate::validation::OperationCapability;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use std::sync::{Mutex, PoisonError};
 
 /// Plugin manifest defining security capabilities and metadata
 #[derive(Debug, Clone)]
@@ -107,12 +109,38 @@ pub trait Plugin: Send + Sync {
     }
 }
 
+/// Public description of a loaded plugin, as listed over IPC
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PluginSummary {
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub max_operations_per_minute: u32,
+}
+
+impl PluginSummary {
+    fn from_manifest(manifest: &PluginManifest) -> Self {
+        Self {
+            name: manifest.name.clone(),
+            version: manifest.version.clone(),
+            description: manifest.description.clone(),
+            author: manifest.author.clone(),
+            max_operations_per_minute: manifest.max_operations_per_minute,
+        }
+    }
+}
+
 /// Manages all loaded plugins in the application with security validation
+/// 
+/// Shared as `Arc<tokio::sync::RwLock<PluginManager>>`: queries and operation
+/// validation only need `&self`, so readers never contend with each other and
+/// the write lock is reserved for loading, registration and shutdown.
 pub struct PluginManager {
     plugins: Vec<Box<dyn Plugin>>,
     note_formats: HashMap<String, NoteFormat>,
-    /// Plugin operation frequency tracking
-    operation_counts: HashMap<String, Vec<std::time::Instant>>,
+    /// Plugin operation frequency tracking, updated under shared access
+    operation_counts: Mutex<HashMap<String, Vec<std::time::Instant>>>,
 }
 
 impl PluginManager {
@@ -121,7 +149,7 @@ impl PluginManager {
         Self {
             plugins: Vec::new(),
             note_formats: HashMap::new(),
-            operation_counts: HashMap::new(),
+            operation_counts: Mutex::new(HashMap::new()),
         }
     }
     
@@ -204,7 +232,7 @@ Check the `examples/` subdirectory for sample plugin implementations.
     
     /// Validate that a plugin can perform an operation with frequency control
     pub fn validate_plugin_operation(
-        &mut self,
+        &self,
         plugin_name: &str,
         capability: OperationCapability,
     ) -> Result<(), AppError> {
@@ -219,14 +247,14 @@ Check the `examples/` subdirectory for sample plugin implementations.
             plugin.validate_operation(&capability)?;
         }
         
-        // Then check frequency limits (mutable borrow after immutable borrow ends)
+        // Then check frequency limits
         self.check_plugin_frequency_by_name(plugin_name)?;
         
         Ok(())
     }
     
     /// Check plugin operation frequency limits by plugin name
-    fn check_plugin_frequency_by_name(&mut self, plugin_name: &str) -> Result<(), AppError> {
+    fn check_plugin_frequency_by_name(&self, plugin_name: &str) -> Result<(), AppError> {
         let now = std::time::Instant::now();
         let window_start = now - std::time::Duration::from_secs(60); // 1-minute window
         
@@ -239,7 +267,10 @@ Check the `examples/` subdirectory for sample plugin implementations.
             })?;
         
         // Get or create frequency tracking for this plugin
-        let timestamps = self.operation_counts.entry(plugin_name.to_string()).or_insert_with(Vec::new);
+        let mut operation_counts = self.operation_counts.lock().map_err(|_| AppError::Plugin {
+            message: "Failed to acquire plugin frequency lock".to_string(),
+        })?;
+        let timestamps = operation_counts.entry(plugin_name.to_string()).or_default();
         
         // Remove old timestamps
         timestamps.retain(|&timestamp| timestamp > window_start);
@@ -269,17 +300,27 @@ Check the `examples/` subdirectory for sample plugin implementations.
     pub fn get_plugin_manifests(&self) -> Vec<&PluginManifest> {
         self.plugins.iter().map(|p| p.manifest()).collect()
     }
+    
+    /// Summaries of all loaded plugins, in registration order
+    pub fn list_plugins(&self) -> Vec<PluginSummary> {
+        self.plugins.iter().map(|p| PluginSummary::from_manifest(p.manifest())).collect()
+    }
+    
+    /// Summary of one plugin by name
+    pub fn plugin_summary(&self, name: &str) -> Option<PluginSummary> {
+        self.get_plugin(name).map(|p| PluginSummary::from_manifest(p.manifest()))
+    }
 
     /// Gracefully shutdown all plugins
     pub async fn shutdown(&mut self) -> Result<(), AppError> {
-        println!("Shutting down {} plugins...", self.plugins.len());
+        log::info!("Shutting down {} plugins...", self.plugins.len());
         
         // Clear plugin resources
         self.plugins.clear();
         self.note_formats.clear();
-        self.operation_counts.clear();
+        self.operation_counts.get_mut().unwrap_or_else(PoisonError::into_inner).clear();
         
-        println!("Plugin shutdown completed successfully");
+        log::info!("Plugin shutdown completed successfully");
         Ok(())
     }
     
@@ -332,7 +373,7 @@ impl Plugin for HelloWorldPlugin {
             return Ok(());
         }
         
-        println!("Initializing Hello World Plugin v{}", self.version());
+        log::info!("Initializing Hello World Plugin v{}", self.version());
         self.initialized = true;
         Ok(())
     }
@@ -379,6 +420,19 @@ mod tests {
         assert_eq!(manager.plugin_count(), 1);
     }
     
+    #[test]
+    fn test_list_plugins_and_summary() {
+        let mut manager = PluginManager::new();
+        manager.register_plugin(Box::new(HelloWorldPlugin::new())).unwrap();
+        
+        let plugins = manager.list_plugins();
+        assert_eq!(plugins.len(), 1);
+        assert_eq!(plugins[0].name, "Hello World Plugin");
+        assert_eq!(plugins[0].version, "1.0.0");
+        assert_eq!(manager.plugin_summary("Hello World Plugin"), Some(plugins[0].clone()));
+        assert_eq!(manager.plugin_summary("Missing"), None);
+    }
+    
     #[test]
     fn test_load_plugins_creates_directory() {
         let temp_dir = TempDir::new().unwrap();
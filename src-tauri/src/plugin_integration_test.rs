@@ -2,7 +2,7 @@
 mod plugin_integration_tests {
     use crate::plugin::{Plugin, PluginManager, HelloWorldPlugin};
     use std::sync::Arc;
-    use tokio::sync::Mutex;
+    use tokio::sync::RwLock;
     
     #[tokio::test]
     async fn test_plugin_manager_integration() {
@@ -27,19 +27,19 @@ mod plugin_integration_tests {
     }
     
     #[tokio::test]
-    async fn test_plugin_manager_with_mutex() {
-        // Test the plugin manager wrapped in Arc<Mutex<>> as used in AppState
-        let plugin_manager = Arc::new(Mutex::new(PluginManager::new()));
+    async fn test_plugin_manager_with_rwlock() {
+        // Test the plugin manager wrapped in Arc<RwLock<>> as used in AppState
+        let plugin_manager = Arc::new(RwLock::new(PluginManager::new()));
         
         {
-            let mut manager = plugin_manager.lock().await;
+            let mut manager = plugin_manager.write().await;
             let plugin_dir = std::env::temp_dir().join("test_plugins_mutex");
             assert!(manager.load_plugins(&plugin_dir).is_ok());
         }
         
-        // Test accessing plugin info through the mutex
+        // Test accessing plugin info through the lock
         {
-            let manager = plugin_manager.lock().await;
+            let manager = plugin_manager.read().await;
             assert!(manager.plugin_count() > 0);
             
             let formats = manager.get_note_formats();
@@ -49,6 +49,34 @@ mod plugin_integration_tests {
         }
     }
     
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_list_plugins_does_not_deadlock() {
+        let plugin_manager = Arc::new(RwLock::new(PluginManager::new()));
+        plugin_manager.write().await.register_plugin(Box::new(HelloWorldPlugin::new())).unwrap();
+        
+        // Hold one read guard while the other readers run: they must not wait on it
+        let held = plugin_manager.read().await;
+        let readers: Vec<_> = (0..64)
+            .map(|_| {
+                let plugin_manager = plugin_manager.clone();
+                tokio::spawn(async move {
+                    let manager = plugin_manager.read().await;
+                    manager.validate_plugin_operation("Hello World Plugin", crate::validation::OperationCapability::ReadNotes).ok();
+                    manager.list_plugins().len()
+                })
+            })
+            .collect();
+        let counts = tokio::time::timeout(std::time::Duration::from_secs(5), futures::future::join_all(readers))
+            .await
+            .expect("concurrent list_plugins calls deadlocked");
+        assert!(counts.into_iter().all(|count| count.unwrap() == 1));
+        drop(held);
+        
+        // Writers still get through once the readers are done
+        let update = tokio::time::timeout(std::time::Duration::from_secs(5), plugin_manager.write()).await;
+        assert!(update.is_ok());
+    }
+    
     #[test]
     fn test_hello_world_plugin_standalone() {
         let mut plugin = HelloWorldPlugin::new();
@@ -2,6 +2,11 @@ use std::sync::Arc;
 use std::collections::HashMap;
 use crate::database::DbService;
 use crate::error::AppError;
+use crate::models::SettingChange;
+use crate::validation::{SanitizeMode, SecurityValidator, SANITIZE_MODE_SETTING_KEY};
+
+/// Setting holding the comma-separated list of file extensions allowed for export
+pub const EXPORT_EXTENSIONS_SETTING_KEY: &str = "export.allowed_extensions";
 
 pub struct SettingsService {
     db_service: Arc<DbService>,
@@ -20,8 +25,10 @@ impl SettingsService {
     /// Set a specific setting value
     pub async fn set_setting(&self, key: &str, value: &str) -> Result<(), AppError> {
         // SECURITY: Validate setting before storing
-        use crate::validation::SecurityValidator;
         SecurityValidator::validate_setting(key, value)?;
+        if key == EXPORT_EXTENSIONS_SETTING_KEY {
+            SecurityValidator::parse_export_extensions(value)?;
+        }
         
         self.db_service.set_setting(key, value).await
     }
@@ -50,8 +57,27 @@ impl SettingsService {
         Ok(settings)
     }
 
+    /// File extensions currently allowed for export
+    /// Falls back to the built-in list when `export.allowed_extensions` is unset
+    pub async fn allowed_export_extensions(&self) -> Result<Vec<String>, AppError> {
+        match self.get_setting(EXPORT_EXTENSIONS_SETTING_KEY).await? {
+            Some(value) => SecurityValidator::parse_export_extensions(&value),
+            None => Ok(SecurityValidator::DEFAULT_EXPORT_EXTENSIONS.iter().map(|ext| ext.to_string()).collect()),
+        }
+    }
+
+    /// How exported HTML is sanitized
+    /// Falls back to `SanitizeMode::default()` when `security.sanitize_mode` is unset or unrecognised
+    pub async fn sanitize_mode(&self) -> Result<SanitizeMode, AppError> {
+        let value = self.get_setting(SANITIZE_MODE_SETTING_KEY).await?;
+        Ok(value.as_deref().and_then(SanitizeMode::from_setting).unwrap_or_default())
+    }
+
     /// Export settings to a JSON file
     pub async fn export_settings_to_file(&self, file_path: &str) -> Result<(), AppError> {
+        let allowed_extensions = self.allowed_export_extensions().await?;
+        SecurityValidator::validate_file_extension_against(std::path::Path::new(file_path), &allowed_extensions)?;
+        
         let settings = self.get_all_settings().await?;
         let json = serde_json::to_string_pretty(&settings)?;
         std::fs::write(file_path, json).map_err(|e| AppError::Io(e))
@@ -97,13 +123,11 @@ impl SettingsService {
         Ok(imported_count)
     }
 
-    /// Reset all settings to default values
-    pub async fn reset_to_defaults(&self) -> Result<(), AppError> {
-        // Clear all existing settings
-        self.db_service.clear_all_settings().await?;
-        
-        // Initialize with defaults
-        self.initialize_defaults().await
+    /// Restore every setting in `get_default_settings` to its default, in one transaction
+    /// With `remove_custom`, settings without a default are deleted too. Notes are
+    /// untouched. Returns the keys that changed, sorted.
+    pub async fn reset_to_defaults(&self, remove_custom: bool) -> Result<Vec<String>, AppError> {
+        self.db_service.reset_settings(&get_default_settings(), remove_custom).await
     }
 
     /// Initialize default settings if they don't exist
@@ -195,11 +219,58 @@ impl SettingsService {
         self.set_setting(key, &value.to_string()).await
     }
 
+    /// Get all settings in a namespace (keys starting with `prefix.`)
+    pub async fn get_namespace(&self, prefix: &str) -> Result<HashMap<String, String>, AppError> {
+        let namespace = Self::namespace_prefix(prefix)?;
+        
+        let settings = self.db_service.get_settings_with_prefix(&namespace).await?;
+        Ok(settings.into_iter().map(|setting| (setting.key, setting.value)).collect())
+    }
+
+    /// Delete all settings in a namespace, returning the number of removed keys
+    pub async fn delete_namespace(&self, prefix: &str) -> Result<usize, AppError> {
+        let namespace = Self::namespace_prefix(prefix)?;
+        
+        self.db_service.delete_settings_with_prefix(&namespace).await
+    }
+
+    /// Validate a namespace prefix and return it with the trailing separator
+    fn namespace_prefix(prefix: &str) -> Result<String, AppError> {
+        let prefix = prefix.strip_suffix('.').unwrap_or(prefix);
+        SecurityValidator::validate_setting_key(prefix)?;
+        
+        Ok(format!("{}.", prefix))
+    }
+
     /// Delete a specific setting
     pub async fn delete_setting(&self, key: &str) -> Result<(), AppError> {
         self.db_service.delete_setting(key).await
     }
 
+    /// Recorded changes to a setting, most recent first
+    pub async fn get_setting_history(&self, key: &str, limit: usize) -> Result<Vec<SettingChange>, AppError> {
+        self.db_service.get_setting_history(key, limit).await
+    }
+
+    /// Undo the most recent change to a setting, returning the restored value
+    /// 
+    /// A setting that did not exist before its last change is deleted again (`None`).
+    /// The revert is itself recorded, so reverting twice restores the later value.
+    pub async fn revert_setting(&self, key: &str) -> Result<Option<String>, AppError> {
+        let change = self.get_setting_history(key, 1).await?.into_iter().next()
+            .ok_or_else(|| AppError::Validation {
+                field: "key".to_string(),
+                message: format!("No recorded changes for setting '{}'", key),
+            })?;
+        
+        match &change.old_value {
+            Some(value) => self.set_setting(key, value).await?,
+            None => self.delete_setting(key).await?,
+        }
+        
+        Ok(change.old_value)
+    }
+
     /// Get setting with a default value if not found
     pub async fn get_setting_or_default(&self, key: &str, default: &str) -> Result<String, AppError> {
         Ok(self.get_setting(key).await?.unwrap_or_else(|| default.to_string()))
@@ -236,6 +307,8 @@ pub fn get_default_settings() -> HashMap<String, String> {
     defaults.insert("window.resizable".to_string(), "true".to_string());
     defaults.insert("window.decorations".to_string(), "true".to_string());
     defaults.insert("window.transparent".to_string(), "false".to_string());
+    defaults.insert("window.focus_on_show".to_string(), "true".to_string());
+    defaults.insert("window.toggle_mode".to_string(), "hide".to_string()); // hide, minimize
     
     // Theme settings
     defaults.insert("theme.mode".to_string(), "auto".to_string());
@@ -250,17 +323,43 @@ pub fn get_default_settings() -> HashMap<String, String> {
     defaults.insert("editor.line_numbers".to_string(), "false".to_string());
     defaults.insert("editor.vim_mode".to_string(), "false".to_string());
     defaults.insert("editor.default_format".to_string(), "plaintext".to_string());
+    defaults.insert("editor.draft_flush_interval".to_string(), "5".to_string()); // seconds
+    
+    // Database settings
+    defaults.insert("database.lock_retry_attempts".to_string(), "3".to_string()); // 1-10, including the first attempt
+    defaults.insert("database.lock_retry_backoff_ms".to_string(), "50".to_string()); // doubles per retry, at most 5000
+    
+    // Note settings
+    defaults.insert("notes.max_pinned".to_string(), "0".to_string()); // 0 = unlimited
+    defaults.insert("notes.auto_pin_enabled".to_string(), "false".to_string()); // opt-in
+    defaults.insert("notes.auto_pin_threshold".to_string(), "10".to_string()); // opens before a note is auto-pinned
+    defaults.insert("notes.trash_retention_days".to_string(), "30".to_string()); // 0 = never purge
+    defaults.insert("notes.soft_char_limit".to_string(), "0".to_string()); // 0 = no warning
+    defaults.insert("notes.compression_threshold_bytes".to_string(), "65536".to_string()); // 0 = never compress
+    defaults.insert("notes.path_scheme".to_string(), "id".to_string()); // "id" or "slug"
+    defaults.insert("notes.default_format".to_string(), "plaintext".to_string()); // "plaintext" or "markdown"
+    defaults.insert("notes.capture_appends_to_locked".to_string(), "true".to_string()); // quick capture may append to a locked daily note
+    defaults.insert("import.base_directory".to_string(), "".to_string()); // empty = file import disabled
+    
+    // Capture settings, applied to CLI-captured content before it is stored
+    defaults.insert("capture.trim".to_string(), "true".to_string()); // trailing whitespace and surrounding blank lines
+    defaults.insert("capture.collapse_blank_lines".to_string(), "true".to_string());
+    defaults.insert("capture.strip_url_params".to_string(), "true".to_string()); // utm_* and other tracking parameters
     
     // Search settings
-    defaults.insert("search.max_results".to_string(), "100".to_string());
+    defaults.insert("search.max_results".to_string(), "100".to_string()); // page size cap, at most 1000
+    defaults.insert("search.default_limit".to_string(), "50".to_string());
     defaults.insert("search.highlight_matches".to_string(), "true".to_string());
     defaults.insert("search.case_sensitive".to_string(), "false".to_string());
     defaults.insert("search.fuzzy_threshold".to_string(), "0.6".to_string());
+    defaults.insert("search.record_history".to_string(), "false".to_string()); // opt-in
+    defaults.insert(crate::search::STOPWORDS_SETTING_KEY.to_string(), crate::search::DEFAULT_STOPWORDS.to_string()); // empty = keep every term
     
     // Global shortcut settings
     defaults.insert("shortcuts.toggle_window".to_string(), "Ctrl+Alt+Space".to_string());
     defaults.insert("shortcuts.quick_note".to_string(), "Ctrl+Alt+N".to_string());
     defaults.insert("shortcuts.search".to_string(), "Ctrl+Alt+F".to_string());
+    defaults.insert("shortcuts.recent_note".to_string(), "".to_string()); // empty = unbound
     
     // General settings
     defaults.insert("general.startup_behavior".to_string(), "minimize".to_string());
@@ -269,10 +368,31 @@ pub fn get_default_settings() -> HashMap<String, String> {
     defaults.insert("general.backup_interval".to_string(), "24".to_string()); // hours
     defaults.insert("general.max_recent_files".to_string(), "10".to_string());
     
+    // Export settings
+    defaults.insert(
+        EXPORT_EXTENSIONS_SETTING_KEY.to_string(),
+        SecurityValidator::DEFAULT_EXPORT_EXTENSIONS.join(","),
+    );
+    
+    // Logging settings
+    defaults.insert(crate::logging::LOG_LEVEL_SETTING_KEY.to_string(), "info".to_string()); // off, error, warn, info, debug, trace
+    
+    // Security settings
+    defaults.insert(SANITIZE_MODE_SETTING_KEY.to_string(), "neutralize".to_string()); // neutralize, escape, flag
+    
     // Performance settings
     defaults.insert("performance.animation_enabled".to_string(), "true".to_string());
     defaults.insert("performance.virtual_scrolling".to_string(), "true".to_string());
     defaults.insert("performance.debounce_delay".to_string(), "300".to_string());
+    defaults.insert("performance.operation_history_size".to_string(), "1000".to_string());
+    defaults.insert("performance.system_history_size".to_string(), "1000".to_string());
+    defaults.insert("performance.metrics_interval".to_string(), "30".to_string()); // seconds
+    defaults.insert("performance.summary_percentiles".to_string(), "50,95,99".to_string());
+    defaults.insert("ipc.max_payload_bytes".to_string(), "1048576".to_string());
+    defaults.insert("performance.report_export_enabled".to_string(), "false".to_string());
+    defaults.insert("performance.report_export_directory".to_string(), "".to_string());
+    defaults.insert("performance.report_export_interval".to_string(), "3600".to_string()); // seconds
+    defaults.insert("performance.report_export_keep".to_string(), "24".to_string());
     
     // Privacy settings
     defaults.insert("privacy.analytics_enabled".to_string(), "false".to_string());
@@ -285,7 +405,7 @@ pub fn get_default_settings() -> HashMap<String, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::database::DbService;
+    use crate::database::{DbService, SETTINGS_HISTORY_PER_KEY};
     use tempfile::tempdir;
     use anyhow::Context;
 
@@ -342,6 +462,28 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_reset_to_defaults_restores_every_known_key() -> Result<(), anyhow::Error> {
+        let service = setup_test_service().await?;
+        service.initialize_defaults().await?;
+        service.set_setting("theme.mode", "dark").await?;
+        service.set_setting("window.width", "1024").await?;
+        service.set_setting("custom.flag", "on").await?;
+        
+        let changed = service.reset_to_defaults(false).await?;
+        assert_eq!(changed, vec!["theme.mode".to_string(), "window.width".to_string()]);
+        for (key, value) in get_default_settings() {
+            assert_eq!(service.get_setting(&key).await?, Some(value), "{} was not reset", key);
+        }
+        assert_eq!(service.get_setting("custom.flag").await?.as_deref(), Some("on"));
+        assert_eq!(service.get_setting_history("theme.mode", 1).await?[0].new_value.as_deref(), Some("auto"));
+        
+        // Custom settings are only removed when asked
+        assert_eq!(service.reset_to_defaults(true).await?, vec!["custom.flag".to_string()]);
+        assert!(!service.has_setting("custom.flag").await?);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_typed_settings() -> Result<(), anyhow::Error> {
         let service = setup_test_service().await?;
@@ -397,6 +539,39 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_namespace_operations() -> Result<(), anyhow::Error> {
+        let service = setup_test_service().await?;
+        
+        service.set_setting("window.width", "800").await
+            .context("Failed to set window.width")?;
+        service.set_setting("window.height", "600").await
+            .context("Failed to set window.height")?;
+        service.set_setting("windowed", "true").await
+            .context("Failed to set windowed")?;
+        service.set_setting("theme.mode", "dark").await
+            .context("Failed to set theme.mode")?;
+        
+        let window = service.get_namespace("window").await
+            .context("Failed to get window namespace")?;
+        assert_eq!(window.len(), 2);
+        assert_eq!(window.get("window.width"), Some(&"800".to_string()));
+        assert!(!window.contains_key("windowed"));
+        
+        // Invalid prefixes are rejected like setting keys
+        assert!(service.get_namespace("").await.is_err());
+        assert!(service.get_namespace("window%").await.is_err());
+        
+        let removed = service.delete_namespace("window").await
+            .context("Failed to delete window namespace")?;
+        assert_eq!(removed, 2);
+        assert!(service.has_setting("windowed").await?);
+        assert!(service.has_setting("theme.mode").await?);
+        assert!(service.get_namespace("window").await?.is_empty());
+        
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_delete_setting() -> Result<(), anyhow::Error> {
         let service = setup_test_service().await?;
@@ -451,6 +626,32 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_allowed_export_extensions_setting() -> Result<(), anyhow::Error> {
+        let service = setup_test_service().await?;
+        let temp_dir = tempdir().context("Failed to create temp dir")?;
+        let org_file = temp_dir.path().join("settings.org");
+        
+        // The default list doesn't include .org
+        assert!(service.export_settings_to_file(&org_file.to_string_lossy()).await.is_err());
+        
+        service.set_setting(EXPORT_EXTENSIONS_SETTING_KEY, "json, org").await
+            .context("Failed to allow .org exports")?;
+        assert_eq!(service.allowed_export_extensions().await?, vec!["json", "org"]);
+        service.export_settings_to_file(&org_file.to_string_lossy()).await
+            .context("Failed to export to an allowed custom extension")?;
+        assert!(org_file.exists());
+        
+        // Deny-listed extensions can't be enabled and the previous list stays in place
+        assert!(service.set_setting(EXPORT_EXTENSIONS_SETTING_KEY, "json,sh").await.is_err());
+        assert_eq!(service.allowed_export_extensions().await?, vec!["json", "org"]);
+        let sh_file = temp_dir.path().join("settings.sh");
+        assert!(service.export_settings_to_file(&sh_file.to_string_lossy()).await.is_err());
+        assert!(!sh_file.exists());
+        
+        Ok(())
+    }
+
     #[tokio::test] 
     async fn test_export_import() -> Result<(), anyhow::Error> {
         let service = setup_test_service().await?;
@@ -510,4 +711,39 @@ mod tests {
         
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_setting_history_records_changes_and_reverts() -> Result<(), anyhow::Error> {
+        let service = setup_test_service().await?;
+        
+        service.set_setting("theme.mode", "light").await?;
+        service.set_setting("theme.mode", "dark").await?;
+        service.set_setting("theme.mode", "dark").await?; // unchanged, not recorded
+        
+        let history = service.get_setting_history("theme.mode", 10).await?;
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].old_value.as_deref(), Some("light"));
+        assert_eq!(history[0].new_value.as_deref(), Some("dark"));
+        assert_eq!(history[1].old_value, None);
+        
+        assert_eq!(service.revert_setting("theme.mode").await?.as_deref(), Some("light"));
+        assert_eq!(service.get_setting("theme.mode").await?.as_deref(), Some("light"));
+        assert_eq!(service.get_setting_history("theme.mode", 1).await?[0].new_value.as_deref(), Some("light"));
+        
+        // Reverting a newly created setting removes it again
+        service.set_setting("fresh.key", "value").await?;
+        assert_eq!(service.revert_setting("fresh.key").await?, None);
+        assert_eq!(service.get_setting("fresh.key").await?, None);
+        assert!(service.revert_setting("never.set").await.is_err());
+        
+        // History is capped per key, dropping the oldest entries
+        for i in 0..(SETTINGS_HISTORY_PER_KEY + 5) {
+            service.set_setting("capped.key", &i.to_string()).await?;
+        }
+        let capped = service.get_setting_history("capped.key", 100).await?;
+        assert_eq!(capped.len(), SETTINGS_HISTORY_PER_KEY);
+        assert_eq!(capped[0].new_value, Some((SETTINGS_HISTORY_PER_KEY + 4).to_string()));
+        
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -1,8 +1,8 @@
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::time::timeout;
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 
 use crate::database::DbService;
 use crate::settings::SettingsService;
@@ -11,6 +11,14 @@ use crate::window_manager::WindowManager;
 use crate::plugin::PluginManager;
 use crate::validation::SecurityValidator;
 use crate::error::AppError;
+use crate::traits::events::EventEmitter;
+
+/// Event emitted when a graceful shutdown begins
+pub const SHUTDOWN_STARTED_EVENT: &str = "shutdown-started";
+/// Event emitted as each shutdown step starts and finishes
+pub const SHUTDOWN_STEP_EVENT: &str = "shutdown-step";
+/// Event emitted when shutdown has finished, successfully or not
+pub const SHUTDOWN_COMPLETE_EVENT: &str = "shutdown-complete";
 
 /// Graceful shutdown manager for the application
 #[derive(Debug)]
@@ -19,8 +27,32 @@ pub struct ShutdownManager {
     is_shutting_down: Arc<AtomicBool>,
     /// Maximum time to wait for graceful shutdown
     shutdown_timeout: Duration,
-    /// Application handle for emitting events
-    app_handle: Option<AppHandle>,
+    /// Emitter for shutdown progress events
+    emitter: Option<Arc<dyn EventEmitter>>,
+    /// The step currently being executed, if shutdown is running
+    current_step: Arc<Mutex<Option<ShutdownStep>>>,
+    /// When the application started, for uptime reporting
+    started_at: Instant,
+    /// Timestamp the session started at; drafts staged earlier are left for recovery
+    session_started_at: String,
+}
+
+/// A single named step of the shutdown sequence
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ShutdownStep {
+    pub name: String,
+    pub status: ShutdownStepStatus,
+}
+
+/// Progress of an individual shutdown step
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub enum ShutdownStepStatus {
+    #[serde(rename = "running")]
+    Running,
+    #[serde(rename = "completed")]
+    Completed,
+    #[serde(rename = "failed")]
+    Failed,
 }
 
 /// Shutdown status information
@@ -58,7 +90,10 @@ impl ShutdownManager {
         Self {
             is_shutting_down: Arc::new(AtomicBool::new(false)),
             shutdown_timeout: Duration::from_secs(30),
-            app_handle: None,
+            emitter: None,
+            current_step: Arc::new(Mutex::new(None)),
+            started_at: Instant::now(),
+            session_started_at: crate::database::current_timestamp(),
         }
     }
 
@@ -67,13 +102,26 @@ impl ShutdownManager {
         Self {
             is_shutting_down: Arc::new(AtomicBool::new(false)),
             shutdown_timeout: timeout,
-            app_handle: None,
+            emitter: None,
+            current_step: Arc::new(Mutex::new(None)),
+            started_at: Instant::now(),
+            session_started_at: crate::database::current_timestamp(),
         }
     }
 
     /// Set the application handle for event emission
     pub fn set_app_handle(&mut self, app_handle: AppHandle) {
-        self.app_handle = Some(app_handle);
+        self.emitter = Some(Arc::new(app_handle));
+    }
+
+    /// Set a custom event emitter (e.g. a mock in tests)
+    pub fn set_event_emitter(&mut self, emitter: Arc<dyn EventEmitter>) {
+        self.emitter = Some(emitter);
+    }
+
+    /// The shutdown step currently executing, or the last one if shutdown has finished
+    pub fn current_step(&self) -> Option<ShutdownStep> {
+        self.current_step.lock().ok().and_then(|step| step.clone())
     }
 
     /// Check if shutdown is in progress
@@ -81,20 +129,62 @@ impl ShutdownManager {
         self.is_shutting_down.load(Ordering::Relaxed)
     }
 
+    /// Time since the application started
+    pub fn uptime(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    /// Timestamp the session started at, as stored with staged drafts
+    pub fn session_started_at(&self) -> &str {
+        &self.session_started_at
+    }
+
     /// Get a clone of the shutdown flag for use in other services
     pub fn get_shutdown_flag(&self) -> Arc<AtomicBool> {
         self.is_shutting_down.clone()
     }
 
-    /// Emit shutdown status to frontend
-    async fn emit_status(&self, status: ShutdownStatus) {
-        if let Some(app_handle) = &self.app_handle {
-            if let Err(e) = app_handle.emit("shutdown_status", &status) {
-                eprintln!("Warning: Failed to emit shutdown status: {}", e);
+    /// Emit an event to the frontend, logging rather than failing on emission errors
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        if let Some(emitter) = &self.emitter {
+            if let Err(e) = emitter.emit_event(event, payload) {
+                log::warn!("Failed to emit {}: {}", event, e);
+            }
+        }
+    }
+
+    /// Record a step's progress and emit it as a `shutdown-step` event
+    fn report_step(&self, name: &str, status: ShutdownStepStatus) {
+        let step = ShutdownStep {
+            name: name.to_string(),
+            status,
+        };
+        if let Ok(mut current) = self.current_step.lock() {
+            *current = Some(step.clone());
+        }
+        self.emit(SHUTDOWN_STEP_EVENT, serde_json::json!(step));
+    }
+
+    /// Run a shutdown step, reporting it as running and then completed or failed
+    async fn run_step<F>(&self, name: &str, step: F)
+    where
+        F: std::future::Future<Output = Result<(), AppError>>,
+    {
+        self.report_step(name, ShutdownStepStatus::Running);
+        match step.await {
+            Ok(()) => self.report_step(name, ShutdownStepStatus::Completed),
+            Err(e) => {
+                log::warn!("Shutdown step '{}' failed: {}", name, e);
+                self.report_step(name, ShutdownStepStatus::Failed);
             }
         }
+    }
+
+    /// Emit shutdown status to frontend
+    async fn emit_status(&self, status: ShutdownStatus) {
+        self.emit("shutdown_status", serde_json::json!(status));
         
-        println!("Shutdown: {} - {}", 
+        log::info!("Shutdown: {} - {}", 
                  match status.stage {
                      ShutdownStage::Initiated => "Initiated",
                      ShutdownStage::SavingData => "Saving Data",
@@ -112,13 +202,16 @@ impl ShutdownManager {
         settings_service: Arc<SettingsService>,
         global_shortcut_service: Arc<GlobalShortcutService>,
         window_manager: Arc<WindowManager>,
-        plugin_manager: Arc<tokio::sync::Mutex<PluginManager>>,
+        plugin_manager: Arc<tokio::sync::RwLock<PluginManager>>,
         security_validator: Arc<SecurityValidator>,
     ) -> Result<(), AppError> {
         // Set shutdown flag
         self.is_shutting_down.store(true, Ordering::Relaxed);
 
         // Emit initial shutdown status
+        self.emit(SHUTDOWN_STARTED_EVENT, serde_json::json!({
+            "message": "Graceful shutdown initiated",
+        }));
         self.emit_status(ShutdownStatus {
             stage: ShutdownStage::Initiated,
             message: "Graceful shutdown initiated".to_string(),
@@ -145,15 +238,26 @@ impl ShutdownManager {
                     message: "Graceful shutdown completed successfully".to_string(),
                     progress: 100,
                 }).await;
+                self.emit(SHUTDOWN_COMPLETE_EVENT, serde_json::json!({
+                    "success": result.is_ok(),
+                    "message": "Graceful shutdown completed successfully",
+                }));
                 result
             }
             Err(_) => {
-                eprintln!("Warning: Shutdown timeout reached, forcing exit");
+                log::warn!("Shutdown timeout reached, forcing exit");
+                if let Some(step) = self.current_step() {
+                    self.report_step(&step.name, ShutdownStepStatus::Failed);
+                }
                 self.emit_status(ShutdownStatus {
                     stage: ShutdownStage::Complete,
                     message: "Shutdown timeout reached, forcing exit".to_string(),
                     progress: 100,
                 }).await;
+                self.emit(SHUTDOWN_COMPLETE_EVENT, serde_json::json!({
+                    "success": false,
+                    "message": "Shutdown timeout reached, forcing exit",
+                }));
                 
                 // Allow a brief moment for the event to be sent
                 tokio::time::sleep(Duration::from_millis(100)).await;
@@ -171,7 +275,7 @@ impl ShutdownManager {
         settings_service: Arc<SettingsService>,
         global_shortcut_service: Arc<GlobalShortcutService>,
         window_manager: Arc<WindowManager>,
-        plugin_manager: Arc<tokio::sync::Mutex<PluginManager>>,
+        plugin_manager: Arc<tokio::sync::RwLock<PluginManager>>,
         security_validator: Arc<SecurityValidator>,
     ) -> Result<(), AppError> {
         
@@ -182,19 +286,32 @@ impl ShutdownManager {
             progress: 25,
         }).await;
 
+        // Apply this session's drafts, so the last edits are not left for recovery
+        self.run_step("flush_drafts", async {
+            let flushed = db_service.flush_drafts(Some(&self.session_started_at)).await?;
+            if let Some(emitter) = &self.emitter {
+                crate::commands::notes::emit_notes_changed(
+                    emitter.as_ref(),
+                    crate::commands::notes::NoteChangeKind::Updated,
+                    flushed,
+                );
+            }
+            Ok(())
+        }).await;
+
         // Wait for any pending database operations and flush data
-        if let Ok(conn) = db_service.get_connection() {
+        self.run_step("flush_database", async {
+            let conn = db_service.get_connection()?;
             // Force WAL checkpoint to ensure all data is written
             let _ = conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);");
             
             // Optimize database before shutdown
             let _ = conn.execute_batch("PRAGMA optimize;");
-        }
+            Ok(())
+        }).await;
 
         // Ensure settings are persisted
-        if let Err(e) = settings_service.flush_pending_changes().await {
-            eprintln!("Warning: Failed to flush pending settings: {}", e);
-        }
+        self.run_step("flush_settings", settings_service.flush_pending_changes()).await;
 
         // Stage 2: Clean up resources
         self.emit_status(ShutdownStatus {
@@ -204,22 +321,18 @@ impl ShutdownManager {
         }).await;
 
         // Unregister global shortcuts
-        if let Err(e) = global_shortcut_service.cleanup().await {
-            eprintln!("Warning: Failed to clean up global shortcuts: {}", e);
-        }
+        self.run_step("cleanup_shortcuts", global_shortcut_service.cleanup()).await;
 
         // Clean up plugins
-        {
-            let mut pm = plugin_manager.lock().await;
-            if let Err(e) = pm.shutdown().await {
-                eprintln!("Warning: Failed to shutdown plugins: {}", e);
-            }
-        }
+        self.run_step("shutdown_plugins", async {
+            let mut pm = plugin_manager.write().await;
+            pm.shutdown().await
+        }).await;
 
         // Clean up temporary files and IPC resources
-        if let Err(e) = security_validator.cleanup_all_temp_files() {
-            eprintln!("Warning: Failed to cleanup temporary files: {}", e);
-        }
+        self.run_step("cleanup_temp_files", async {
+            security_validator.cleanup_all_temp_files().map(|_| ())
+        }).await;
 
         // Stage 3: Finalize shutdown
         self.emit_status(ShutdownStatus {
@@ -229,12 +342,13 @@ impl ShutdownManager {
         }).await;
 
         // Hide window gracefully
-        if let Err(e) = window_manager.hide_window().await {
-            eprintln!("Warning: Failed to hide window: {}", e);
-        }
+        self.run_step("hide_window", window_manager.hide_window()).await;
 
         // Clean up lock file last
-        crate::cli::cleanup_lock_file();
+        self.run_step("cleanup_lock_file", async {
+            crate::cli::cleanup_lock_file();
+            Ok(())
+        }).await;
 
         Ok(())
     }
@@ -268,13 +382,16 @@ impl ShutdownManager {
         settings_service: Arc<SettingsService>,
         global_shortcut_service: Arc<GlobalShortcutService>,
         window_manager: Arc<WindowManager>,
-        plugin_manager: Arc<tokio::sync::Mutex<PluginManager>>,
+        plugin_manager: Arc<tokio::sync::RwLock<PluginManager>>,
         security_validator: Arc<SecurityValidator>,
     ) {
         let shutdown_manager = Self {
             is_shutting_down: self.is_shutting_down.clone(),
             shutdown_timeout: self.shutdown_timeout,
-            app_handle: self.app_handle.clone(),
+            emitter: self.emitter.clone(),
+            current_step: self.current_step.clone(),
+            started_at: self.started_at,
+            session_started_at: self.session_started_at.clone(),
         };
 
         // Register Ctrl+C handler
@@ -286,7 +403,7 @@ impl ShutdownManager {
         let validator_clone = security_validator.clone();
 
         let _ = ctrlc::set_handler(move || {
-            println!("Received interrupt signal, initiating graceful shutdown...");
+            log::info!("Received interrupt signal, initiating graceful shutdown...");
             
             // Create a new Tokio runtime for the shutdown process since we're in a signal handler
             let rt = tokio::runtime::Runtime::new().unwrap();
@@ -300,7 +417,7 @@ impl ShutdownManager {
                     plugin_clone.clone(),
                     validator_clone.clone(),
                 ).await {
-                    eprintln!("Shutdown error: {}", e);
+                    log::error!("Shutdown error: {}", e);
                     std::process::exit(1);
                 } else {
                     std::process::exit(0);
@@ -385,4 +502,84 @@ mod tests {
         let result2 = manager.initiate_shutdown().await;
         assert!(result2.is_err());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_shutdown_emits_progress_events_in_order() {
+        use crate::global_shortcut::GlobalShortcutService;
+        use crate::testing::mocks::MockEventEmitter;
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let db_service = Arc::new(DbService::new(temp_dir.path().join("test.db")).unwrap());
+        let settings_service = Arc::new(SettingsService::new(db_service.clone()));
+        let global_shortcut_service = Arc::new(GlobalShortcutService::new_test(settings_service.clone()).unwrap());
+        let window_manager = Arc::new(WindowManager::new_test(settings_service.clone()).unwrap());
+        let plugin_manager = Arc::new(tokio::sync::RwLock::new(PluginManager::new()));
+        let security_validator = Arc::new(SecurityValidator::new());
+
+        let emitter = MockEventEmitter::new();
+        let mut manager = ShutdownManager::new();
+        manager.set_event_emitter(Arc::new(emitter.clone()));
+        assert!(manager.current_step().is_none());
+        let note = db_service.create_note("Saved".to_string()).await.unwrap();
+        db_service.stage_draft(note.id, "Edited just before quitting".to_string()).await.unwrap();
+
+        manager.shutdown_gracefully(
+            db_service.clone(),
+            settings_service,
+            global_shortcut_service,
+            window_manager,
+            plugin_manager,
+            security_validator,
+        ).await.unwrap();
+
+        // The session's draft was applied and announced to other windows
+        assert_eq!(db_service.get_note(note.id).await.unwrap().unwrap().content, "Edited just before quitting");
+        assert!(emitter.events().iter().any(|(name, payload)| {
+            name == crate::commands::notes::NOTES_CHANGED_EVENT && payload["ids"] == serde_json::json!([note.id])
+        }));
+
+        // Ignore the legacy aggregate status events and note change notifications
+        let events: Vec<(String, serde_json::Value)> = emitter.events()
+            .into_iter()
+            .filter(|(name, _)| name != "shutdown_status" && name != crate::commands::notes::NOTES_CHANGED_EVENT)
+            .collect();
+
+        assert_eq!(events.first().unwrap().0, SHUTDOWN_STARTED_EVENT);
+        assert_eq!(events.last().unwrap().0, SHUTDOWN_COMPLETE_EVENT);
+        assert_eq!(events.last().unwrap().1["success"], true);
+
+        // Every step reports running then completed, in sequence
+        let steps: Vec<(String, String)> = events[1..events.len() - 1]
+            .iter()
+            .map(|(name, payload)| {
+                assert_eq!(name, SHUTDOWN_STEP_EVENT);
+                (
+                    payload["name"].as_str().unwrap().to_string(),
+                    payload["status"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        let expected_order = [
+            "flush_drafts",
+            "flush_database",
+            "flush_settings",
+            "cleanup_shortcuts",
+            "shutdown_plugins",
+            "cleanup_temp_files",
+            "hide_window",
+            "cleanup_lock_file",
+        ];
+        assert_eq!(steps.len(), expected_order.len() * 2);
+        for (index, name) in expected_order.iter().enumerate() {
+            assert_eq!(steps[index * 2], (name.to_string(), "running".to_string()));
+            assert_eq!(steps[index * 2 + 1], (name.to_string(), "completed".to_string()));
+        }
+
+        // The last step is retained for status queries
+        assert_eq!(manager.current_step(), Some(ShutdownStep {
+            name: "cleanup_lock_file".to_string(),
+            status: ShutdownStepStatus::Completed,
+        }));
+    }
+}
@@ -0,0 +1,52 @@
+//! Mock Event Emitter Implementation
+//! 
+//! Records emitted events in order so tests can assert which events fired
+//! and with what payloads, without a Tauri runtime.
+
+use crate::error::AppError;
+use crate::traits::events::EventEmitter;
+use std::sync::{Arc, Mutex};
+
+/// Mock implementation of EventEmitter for testing
+#[derive(Debug, Clone, Default)]
+pub struct MockEventEmitter {
+    events: Arc<Mutex<Vec<(String, serde_json::Value)>>>,
+}
+
+impl MockEventEmitter {
+    /// Create new mock event emitter
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// All emitted events in emission order
+    pub fn events(&self) -> Vec<(String, serde_json::Value)> {
+        self.events.lock().unwrap().clone()
+    }
+    
+    /// Names of all emitted events in emission order
+    pub fn event_names(&self) -> Vec<String> {
+        self.events().into_iter().map(|(name, _)| name).collect()
+    }
+    
+    /// Payloads of all events emitted under the given name
+    pub fn payloads_for(&self, event: &str) -> Vec<serde_json::Value> {
+        self.events()
+            .into_iter()
+            .filter(|(name, _)| name == event)
+            .map(|(_, payload)| payload)
+            .collect()
+    }
+    
+    /// Forget all recorded events
+    pub fn clear(&self) {
+        self.events.lock().unwrap().clear();
+    }
+}
+
+impl EventEmitter for MockEventEmitter {
+    fn emit_event(&self, event: &str, payload: serde_json::Value) -> Result<(), AppError> {
+        self.events.lock().unwrap().push((event.to_string(), payload));
+        Ok(())
+    }
+}
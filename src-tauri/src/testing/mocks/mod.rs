@@ -13,11 +13,15 @@
 pub mod note_repository_mock;
 pub mod settings_repository_mock;
 pub mod search_repository_mock;
+pub mod event_emitter_mock;
+pub mod shortcut_backend_mock;
 
 // Re-export all mock implementations
 pub use note_repository_mock::MockNoteRepository;
 pub use settings_repository_mock::MockSettingsRepository;
 pub use search_repository_mock::MockSearchRepository;
+pub use event_emitter_mock::MockEventEmitter;
+pub use shortcut_backend_mock::MockShortcutBackend;
 
 use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
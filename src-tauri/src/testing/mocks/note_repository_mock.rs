@@ -97,13 +97,15 @@ impl NoteRepository for MockNoteRepository {
         self.check_error_response("create_note")?;
         
         let id = self.get_next_id();
-        let now = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let now = crate::database::current_timestamp();
         let note = Note {
             id,
             content,
             created_at: now.clone(),
             updated_at: now,
             is_favorite: false,  // Fixed: Use is_favorite instead of is_pinned
+            is_encrypted: false,
+            external_id: uuid::Uuid::new_v4().to_string(),
             format: NoteFormat::PlainText,
             nickname: None,
             path: format!("/note/{}", id),
@@ -127,7 +129,7 @@ impl NoteRepository for MockNoteRepository {
         let key = id.to_string();
         if let Some(mut note) = self.state.get(&key) {
             note.content = content;
-            note.updated_at = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+            note.updated_at = crate::database::current_timestamp();
             self.state.insert(key, note.clone());
             Ok(note)
         } else {
@@ -229,9 +229,11 @@ mod tests {
         Note {
             id,
             content: content.to_string(),
-            created_at: "2024-01-01T00:00:00Z".to_string(),
-            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            created_at: "2024-01-01T00:00:00.000Z".to_string(),
+            updated_at: "2024-01-01T00:00:00.000Z".to_string(),
             is_favorite,  // Fixed: Use is_favorite instead of is_pinned
+            is_encrypted: false,
+            external_id: String::new(),
             format: NoteFormat::PlainText,
             nickname: None,
             path: format!("/note/{}", id),
@@ -0,0 +1,58 @@
+//! Mock Shortcut Backend Implementation
+//! 
+//! Tracks registered shortcuts and the order of register/unregister calls,
+//! and can be told to reject specific shortcuts to exercise rollback paths.
+
+use crate::error::AppError;
+use crate::traits::shortcuts::ShortcutBackend;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// Mock implementation of ShortcutBackend for testing
+#[derive(Debug, Clone, Default)]
+pub struct MockShortcutBackend {
+    registered: Arc<Mutex<HashSet<String>>>,
+    calls: Arc<Mutex<Vec<String>>>,
+    rejected: Arc<Mutex<HashSet<String>>>,
+}
+
+impl MockShortcutBackend {
+    /// Create new mock shortcut backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Shortcuts currently registered
+    pub fn registered(&self) -> HashSet<String> {
+        self.registered.lock().unwrap().clone()
+    }
+    
+    /// Calls in order, as `register:<shortcut>` or `unregister:<shortcut>`
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+    
+    /// Make every future registration of `shortcut` fail
+    pub fn reject(&self, shortcut: &str) {
+        self.rejected.lock().unwrap().insert(shortcut.to_string());
+    }
+}
+
+impl ShortcutBackend for MockShortcutBackend {
+    fn register(&self, shortcut: &str) -> Result<(), AppError> {
+        self.calls.lock().unwrap().push(format!("register:{}", shortcut));
+        if self.rejected.lock().unwrap().contains(shortcut) {
+            return Err(AppError::Runtime {
+                message: format!("Shortcut '{}' is already in use", shortcut),
+            });
+        }
+        self.registered.lock().unwrap().insert(shortcut.to_string());
+        Ok(())
+    }
+    
+    fn unregister(&self, shortcut: &str) -> Result<(), AppError> {
+        self.calls.lock().unwrap().push(format!("unregister:{}", shortcut));
+        self.registered.lock().unwrap().remove(shortcut);
+        Ok(())
+    }
+}
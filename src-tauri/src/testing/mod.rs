@@ -47,9 +47,11 @@ impl TestEnvironment {
         env.note_repo.add_note(Note {
             id: 1,
             content: "Test note 1".to_string(),
-            created_at: "2024-01-01T00:00:00Z".to_string(),
-            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            created_at: "2024-01-01T00:00:00.000Z".to_string(),
+            updated_at: "2024-01-01T00:00:00.000Z".to_string(),
             is_favorite: false,
+            is_encrypted: false,
+            external_id: String::new(),
             format: NoteFormat::PlainText,
             nickname: None,
             path: "/note/1".to_string(),
@@ -57,9 +59,11 @@ impl TestEnvironment {
         env.note_repo.add_note(Note {
             id: 2,
             content: "Rust programming guide".to_string(),
-            created_at: "2024-01-01T00:00:00Z".to_string(),
-            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            created_at: "2024-01-01T00:00:00.000Z".to_string(),
+            updated_at: "2024-01-01T00:00:00.000Z".to_string(),
             is_favorite: true,
+            is_encrypted: false,
+            external_id: String::new(),
             format: NoteFormat::PlainText,
             nickname: None,
             path: "/note/2".to_string(),
@@ -67,9 +71,11 @@ impl TestEnvironment {
         env.note_repo.add_note(Note {
             id: 3,
             content: "JavaScript tutorial".to_string(),
-            created_at: "2024-01-01T00:00:00Z".to_string(),
-            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            created_at: "2024-01-01T00:00:00.000Z".to_string(),
+            updated_at: "2024-01-01T00:00:00.000Z".to_string(),
             is_favorite: false,
+            is_encrypted: false,
+            external_id: String::new(),
             format: NoteFormat::PlainText,
             nickname: None,
             path: "/note/3".to_string(),
@@ -32,7 +32,7 @@ impl TestEnvironment {
             settings: Arc::new(crate::settings::SettingsService::new(db_service.clone())),
             global_shortcut: Arc::new(crate::global_shortcut::GlobalShortcutService::new_test(Arc::new(crate::settings::SettingsService::new(db_service.clone())))?),
             window_manager: Arc::new(crate::window_manager::WindowManager::new_test(Arc::new(crate::settings::SettingsService::new(db_service.clone())))?),
-            plugin_manager: Arc::new(tokio::sync::Mutex::new(crate::plugin::PluginManager::new())),
+            plugin_manager: Arc::new(tokio::sync::RwLock::new(crate::plugin::PluginManager::new())),
             security_validator: Arc::new(SecurityValidator::new()),
             shutdown_manager: Arc::new(crate::shutdown::ShutdownManager::new()),
         };
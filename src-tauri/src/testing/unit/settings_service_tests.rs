@@ -153,8 +153,8 @@ mod settings_service_integration_tests {
         assert!(settings_service.has_setting("custom1").await?);
         assert!(settings_service.has_setting("custom2").await?);
         
-        // Reset to defaults - Fixed: doesn't return count, just ()
-        settings_service.reset_to_defaults().await?;
+        // Reset to defaults, removing custom settings
+        settings_service.reset_to_defaults(true).await?;
         
         // Custom settings should be gone (they get cleared during reset)
         assert!(!settings_service.has_setting("custom1").await?);
@@ -0,0 +1,21 @@
+//! Clipboard Access Trait
+//! 
+//! Abstracts writing text to the system clipboard so commands can be tested
+//! without a Tauri runtime or a real clipboard.
+
+use crate::error::AppError;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Writes text to the system clipboard
+pub trait ClipboardWriter: Send + Sync {
+    fn write_text(&self, text: &str) -> Result<(), AppError>;
+}
+
+impl ClipboardWriter for AppHandle {
+    fn write_text(&self, text: &str) -> Result<(), AppError> {
+        self.clipboard().write_text(text.to_string()).map_err(|e| AppError::Runtime {
+            message: format!("Failed to write to clipboard: {}", e),
+        })
+    }
+}
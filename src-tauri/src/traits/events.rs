@@ -0,0 +1,20 @@
+//! Event Emission Trait
+//! 
+//! Abstracts frontend event emission so services can broadcast state changes
+//! without depending on a live Tauri runtime, enabling isolated testing.
+
+use crate::error::AppError;
+use tauri::{AppHandle, Emitter};
+
+/// Emits named events with a JSON payload to the frontend
+pub trait EventEmitter: Send + Sync + std::fmt::Debug {
+    fn emit_event(&self, event: &str, payload: serde_json::Value) -> Result<(), AppError>;
+}
+
+impl EventEmitter for AppHandle {
+    fn emit_event(&self, event: &str, payload: serde_json::Value) -> Result<(), AppError> {
+        self.emit(event, payload).map_err(|e| AppError::Runtime {
+            message: format!("Failed to emit '{}' event: {}", event, e),
+        })
+    }
+}
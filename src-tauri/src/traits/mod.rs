@@ -4,8 +4,16 @@
 /// and better maintainability. All traits maintain exact compatibility with existing
 /// functionality while providing clear boundaries for dependency injection.
 
+pub mod clipboard;
+pub mod events;
 pub mod repository;
 pub mod services;
+pub mod shortcuts;
+
+// Re-export runtime integration traits
+pub use clipboard::ClipboardWriter;
+pub use events::EventEmitter;
+pub use shortcuts::ShortcutBackend;
 
 // Re-export repository traits
 pub use repository::{NoteRepository, SettingsRepository, SearchRepository};
@@ -195,6 +195,8 @@ impl SearchRepository for DbService {
                 created_at: row.get(2)?,
                 updated_at: row.get(3)?,
                 is_favorite: row.get(4)?,
+                is_encrypted: false,
+                external_id: String::new(),
                 format: crate::models::NoteFormat::PlainText,
                 nickname: None,
                 path: format!("/note/{}", id),
@@ -226,6 +228,8 @@ impl SearchRepository for DbService {
                 created_at: row.get(2)?,
                 updated_at: row.get(3)?,
                 is_favorite: row.get(4)?,
+                is_encrypted: false,
+                external_id: String::new(),
                 format: crate::models::NoteFormat::PlainText,
                 nickname: None,
                 path: format!("/note/{}", id),
@@ -245,7 +249,7 @@ impl SearchRepository for DbService {
         let conn = self.get_connection()?;
         
         let cutoff_date = chrono::Utc::now() - chrono::Duration::days(days as i64);
-        let cutoff_str = cutoff_date.format("%Y-%m-%d %H:%M:%S").to_string();
+        let cutoff_str = crate::database::format_timestamp(cutoff_date);
         
         let mut stmt = conn.prepare_cached(
             "SELECT id, content, created_at, updated_at, is_pinned 
@@ -260,6 +264,8 @@ impl SearchRepository for DbService {
                 created_at: row.get(2)?,
                 updated_at: row.get(3)?,
                 is_favorite: row.get(4)?,
+                is_encrypted: false,
+                external_id: String::new(),
                 format: crate::models::NoteFormat::PlainText,
                 nickname: None,
                 path: format!("/note/{}", id),
@@ -375,6 +381,8 @@ impl SearchRepository for DbService {
                 created_at: row.get(2)?,
                 updated_at: row.get(3)?,
                 is_favorite: row.get(4)?,
+                is_encrypted: false,
+                external_id: String::new(),
                 format: crate::models::NoteFormat::PlainText,
                 nickname: None,
                 path: format!("/note/{}", id),
@@ -0,0 +1,12 @@
+//! Global Shortcut Backend Trait
+//! 
+//! Abstracts OS-level shortcut registration so the shortcut service can be
+//! exercised in tests without a Tauri runtime or real key bindings.
+
+use crate::error::AppError;
+
+/// Registers and releases global shortcuts given in `Ctrl+Shift+N` form
+pub trait ShortcutBackend: Send + Sync {
+    fn register(&self, shortcut: &str) -> Result<(), AppError>;
+    fn unregister(&self, shortcut: &str) -> Result<(), AppError>;
+}
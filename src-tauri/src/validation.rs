@@ -1,14 +1,16 @@
 use crate::error::AppError;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::ffi::OsStr;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use std::fs;
 
 /// Represents the source of an operation to enable capability-based access control
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum OperationSource {
     /// Command line interface operations
     CLI,
@@ -20,8 +22,45 @@ pub enum OperationSource {
     Plugin,
 }
 
+impl OperationSource {
+    /// Every operation source, in reporting order
+    pub const ALL: [OperationSource; 4] = [
+        OperationSource::CLI,
+        OperationSource::IPC,
+        OperationSource::Direct,
+        OperationSource::Plugin,
+    ];
+    
+    /// Stable label used to tag recorded metrics with this source
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperationSource::CLI => "cli",
+            OperationSource::IPC => "ipc",
+            OperationSource::Direct => "direct",
+            OperationSource::Plugin => "plugin",
+        }
+    }
+    
+    /// Parse a label produced by `as_str`
+    pub fn from_label(label: &str) -> Option<OperationSource> {
+        Self::ALL.into_iter().find(|source| source.as_str() == label)
+    }
+    
+    /// Frequency limit applied to contexts created for this source
+    /// Plugins have no default; each plugin context carries its own limit
+    pub fn default_frequency_limit(&self) -> Option<u32> {
+        match self {
+            OperationSource::CLI => Some(10), // CLI operations limited to 10/minute
+            OperationSource::IPC => Some(15), // IPC slightly higher limit
+            OperationSource::Direct => Some(100), // Direct operations have higher limit
+            OperationSource::Plugin => None,
+        }
+    }
+}
+
 /// Defines the capabilities/privileges for different operation types
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum OperationCapability {
     /// Read access to notes
     ReadNotes,
@@ -39,6 +78,98 @@ pub enum OperationCapability {
     PluginManagement,
 }
 
+/// Environment variable restricting the capabilities granted to IPC commands
+/// 
+/// Read once at startup. Accepts `read_only` (read and search), `all`, or a
+/// comma-separated list of capability names such as `read_notes,search`.
+/// Unset or empty grants every capability.
+pub const IPC_CAPABILITIES_ENV: &str = "SCRATCH_PAD_IPC_CAPABILITIES";
+
+/// Setting holding the largest IPC payload accepted, in bytes
+pub const IPC_PAYLOAD_LIMIT_SETTING_KEY: &str = "ipc.max_payload_bytes";
+
+/// IPC payload limit used while the setting is unset
+pub const DEFAULT_MAX_IPC_PAYLOAD_BYTES: usize = 1024 * 1024;
+
+/// Highest value `IPC_PAYLOAD_LIMIT_SETTING_KEY` may be raised to
+pub const MAX_IPC_PAYLOAD_LIMIT_BYTES: usize = 8 * 1024 * 1024;
+
+/// Parse an `IPC_PAYLOAD_LIMIT_SETTING_KEY` value: a byte count from 1 to `MAX_IPC_PAYLOAD_LIMIT_BYTES`
+pub fn parse_ipc_payload_limit(value: &str) -> Result<usize, AppError> {
+    match value.trim().parse::<usize>() {
+        Ok(limit) if (1..=MAX_IPC_PAYLOAD_LIMIT_BYTES).contains(&limit) => Ok(limit),
+        _ => Err(AppError::Validation {
+            field: IPC_PAYLOAD_LIMIT_SETTING_KEY.to_string(),
+            message: format!(
+                "IPC payload limit must be a byte count between 1 and {}, got '{}'",
+                MAX_IPC_PAYLOAD_LIMIT_BYTES, value
+            ),
+        }),
+    }
+}
+
+impl OperationCapability {
+    /// Capabilities granted in read-only (kiosk) mode
+    pub const READ_ONLY: [OperationCapability; 2] = [
+        OperationCapability::ReadNotes,
+        OperationCapability::Search,
+    ];
+    
+    /// Snake_case name of the capability, as accepted by `from_name`
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            OperationCapability::ReadNotes => "read_notes",
+            OperationCapability::WriteNotes => "write_notes",
+            OperationCapability::DeleteNotes => "delete_notes",
+            OperationCapability::SystemAccess => "system_access",
+            OperationCapability::FileExport => "file_export",
+            OperationCapability::Search => "search",
+            OperationCapability::PluginManagement => "plugin_management",
+        }
+    }
+    
+    /// Parse a snake_case capability name, e.g. `write_notes`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "read_notes" => Some(OperationCapability::ReadNotes),
+            "write_notes" => Some(OperationCapability::WriteNotes),
+            "delete_notes" => Some(OperationCapability::DeleteNotes),
+            "system_access" => Some(OperationCapability::SystemAccess),
+            "file_export" => Some(OperationCapability::FileExport),
+            "search" => Some(OperationCapability::Search),
+            "plugin_management" => Some(OperationCapability::PluginManagement),
+            _ => None,
+        }
+    }
+    
+    /// Parse a granted capability set in the `IPC_CAPABILITIES_ENV` format
+    /// Returns `None` when every capability is granted
+    pub fn parse_grant(spec: &str) -> Result<Option<Vec<Self>>, AppError> {
+        match spec.trim().to_lowercase().as_str() {
+            "" | "all" => Ok(None),
+            "read_only" => Ok(Some(Self::READ_ONLY.to_vec())),
+            list => list
+                .split(',')
+                .map(|name| {
+                    Self::from_name(name.trim()).ok_or_else(|| AppError::Validation {
+                        field: IPC_CAPABILITIES_ENV.to_string(),
+                        message: format!("Unknown capability '{}'", name.trim()),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Some),
+        }
+    }
+    
+    /// Granted capability set from `IPC_CAPABILITIES_ENV`
+    pub fn grant_from_env() -> Result<Option<Vec<Self>>, AppError> {
+        match std::env::var(IPC_CAPABILITIES_ENV) {
+            Ok(spec) => Self::parse_grant(&spec),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
 /// Context for operation validation with source attribution and capability control
 #[derive(Debug, Clone)]
 pub struct OperationContext {
@@ -50,6 +181,9 @@ pub struct OperationContext {
     pub frequency_limit: Option<u32>,
     /// Timestamp for frequency tracking
     pub timestamp: std::time::Instant,
+    /// Identifier for correlating this operation with its recorded metrics
+    /// A random UUID unless the caller supplies a correlation id
+    pub operation_id: String,
 }
 
 impl OperationContext {
@@ -58,8 +192,9 @@ impl OperationContext {
         Self {
             source: OperationSource::CLI,
             capabilities,
-            frequency_limit: Some(10), // CLI operations limited to 10/minute
+            frequency_limit: OperationSource::CLI.default_frequency_limit(),
             timestamp: std::time::Instant::now(),
+            operation_id: uuid::Uuid::new_v4().to_string(),
         }
     }
     
@@ -68,8 +203,9 @@ impl OperationContext {
         Self {
             source: OperationSource::IPC,
             capabilities,
-            frequency_limit: Some(15), // IPC slightly higher limit
+            frequency_limit: OperationSource::IPC.default_frequency_limit(),
             timestamp: std::time::Instant::now(),
+            operation_id: uuid::Uuid::new_v4().to_string(),
         }
     }
     
@@ -78,8 +214,9 @@ impl OperationContext {
         Self {
             source: OperationSource::Direct,
             capabilities,
-            frequency_limit: Some(100), // Direct operations have higher limit
+            frequency_limit: OperationSource::Direct.default_frequency_limit(),
             timestamp: std::time::Instant::now(),
+            operation_id: uuid::Uuid::new_v4().to_string(),
         }
     }
     
@@ -90,8 +227,70 @@ impl OperationContext {
             capabilities,
             frequency_limit,
             timestamp: std::time::Instant::now(),
+            operation_id: uuid::Uuid::new_v4().to_string(),
         }
     }
+    
+    /// Use a client-supplied correlation id as the operation id, if one is given
+    pub fn with_correlation_id(mut self, correlation_id: Option<&str>) -> Result<Self, AppError> {
+        if let Some(correlation_id) = correlation_id {
+            SecurityValidator::validate_correlation_id(correlation_id)?;
+            self.operation_id = correlation_id.to_string();
+        }
+        Ok(self)
+    }
+}
+
+/// Setting choosing how `sanitize_content_with_mode` treats active content
+pub const SANITIZE_MODE_SETTING_KEY: &str = "security.sanitize_mode";
+
+/// How active content (script tags, script URLs, event handlers) is handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SanitizeMode {
+    /// Rewrite active content so it is inert (`sanitize_content`)
+    #[default]
+    Neutralize,
+    /// HTML-escape the whole content so it displays verbatim as text
+    Escape,
+    /// Leave the content untouched and only report active content; for output
+    /// that is always rendered escaped
+    Flag,
+}
+
+impl SanitizeMode {
+    /// Parse a `security.sanitize_mode` value; `None` for anything unrecognised
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "neutralize" => Some(SanitizeMode::Neutralize),
+            "escape" => Some(SanitizeMode::Escape),
+            "flag" => Some(SanitizeMode::Flag),
+            _ => None,
+        }
+    }
+}
+
+/// Content after sanitization
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SanitizedContent {
+    pub content: String,
+    /// The input contained active content, whether or not it was rewritten
+    pub flagged: bool,
+}
+
+/// Length of the sliding window used for frequency limits
+pub const FREQUENCY_WINDOW: Duration = Duration::from_secs(60);
+
+/// Current frequency usage for one operation source
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FrequencyStatus {
+    pub source: OperationSource,
+    /// Operations counted in the current window
+    pub count: usize,
+    /// Limit per window, if the source is limited
+    pub limit: Option<u32>,
+    /// Operations left before the limit is hit
+    pub remaining: Option<u32>,
+    pub window_secs: u64,
 }
 
 /// Frequency tracking for operation abuse prevention
@@ -99,20 +298,51 @@ impl OperationContext {
 struct FrequencyTracker {
     /// Operation count per source in current time window
     operation_counts: HashMap<OperationSource, Vec<Instant>>,
+    /// Most recent limit enforced per source, reported by `status_at`
+    last_limits: HashMap<OperationSource, u32>,
+    /// Operation counts for dedicated limits that apply on top of the source limit
+    dedicated_counts: HashMap<&'static str, Vec<Instant>>,
 }
 
 impl FrequencyTracker {
     fn new() -> Self {
         Self {
             operation_counts: HashMap::new(),
+            last_limits: HashMap::new(),
+            dedicated_counts: HashMap::new(),
         }
     }
     
+    /// Report usage per source as of `now` without recording an operation
+    fn status_at(&self, now: Instant) -> Vec<FrequencyStatus> {
+        let window_start = now.checked_sub(FREQUENCY_WINDOW);
+        OperationSource::ALL
+            .iter()
+            .map(|source| {
+                let count = self.operation_counts.get(source).map_or(0, |timestamps| {
+                    timestamps
+                        .iter()
+                        .filter(|&&timestamp| window_start.is_none_or(|start| timestamp > start))
+                        .count()
+                });
+                let limit = self.last_limits.get(source).copied().or_else(|| source.default_frequency_limit());
+                FrequencyStatus {
+                    source: source.clone(),
+                    count,
+                    limit,
+                    remaining: limit.map(|limit| limit.saturating_sub(count as u32)),
+                    window_secs: FREQUENCY_WINDOW.as_secs(),
+                }
+            })
+            .collect()
+    }
+    
     /// Check if operation is within frequency limits
     fn check_frequency(&mut self, context: &OperationContext) -> Result<(), AppError> {
         if let Some(limit) = context.frequency_limit {
             let now = Instant::now();
-            let window_start = now - Duration::from_secs(60); // 1-minute window
+            let window_start = now - FREQUENCY_WINDOW;
+            self.last_limits.insert(context.source.clone(), limit);
             
             // Get or create entry for this source
             let timestamps = self.operation_counts.entry(context.source.clone()).or_insert_with(Vec::new);
@@ -133,24 +363,126 @@ impl FrequencyTracker {
         }
         Ok(())
     }
+    
+    /// Check and record one operation against a dedicated per-window limit
+    fn check_dedicated(&mut self, bucket: &'static str, limit: u32, now: Instant) -> Result<(), AppError> {
+        let window_start = now.checked_sub(FREQUENCY_WINDOW);
+        let timestamps = self.dedicated_counts.entry(bucket).or_default();
+        timestamps.retain(|&timestamp| window_start.is_none_or(|start| timestamp > start));
+        
+        if timestamps.len() >= limit as usize {
+            return Err(AppError::RateLimit {
+                message: format!("{} is limited to {} calls per minute", bucket, limit),
+            });
+        }
+        
+        timestamps.push(now);
+        Ok(())
+    }
 }
 
 /// Security validation utilities for input sanitization and path safety
 pub struct SecurityValidator {
     /// Frequency tracker for abuse prevention
     frequency_tracker: Arc<Mutex<FrequencyTracker>>,
+    /// Shutdown flag shared with the `ShutdownManager`
+    shutdown_flag: Arc<AtomicBool>,
+    /// Capabilities IPC commands may use; `None` grants all of them
+    granted_capabilities: Option<Vec<OperationCapability>>,
+    /// Largest IPC payload accepted, in bytes
+    max_ipc_payload_bytes: AtomicUsize,
 }
 
 impl SecurityValidator {
     /// Create a new SecurityValidator instance
     pub fn new() -> Self {
+        Self::with_shutdown_flag(Arc::new(AtomicBool::new(false)))
+    }
+    
+    /// Create a validator that observes the given shutdown flag
+    /// (see `ShutdownManager::get_shutdown_flag`)
+    pub fn with_shutdown_flag(shutdown_flag: Arc<AtomicBool>) -> Self {
         Self {
             frequency_tracker: Arc::new(Mutex::new(FrequencyTracker::new())),
+            shutdown_flag,
+            granted_capabilities: None,
+            max_ipc_payload_bytes: AtomicUsize::new(DEFAULT_MAX_IPC_PAYLOAD_BYTES),
+        }
+    }
+    
+    /// Largest IPC payload currently accepted, in bytes
+    pub fn max_ipc_payload_bytes(&self) -> usize {
+        self.max_ipc_payload_bytes.load(Ordering::Relaxed)
+    }
+    
+    /// Apply a stored `IPC_PAYLOAD_LIMIT_SETTING_KEY` value; `None` restores the default
+    pub fn apply_payload_limit_setting(&self, value: Option<&str>) -> Result<usize, AppError> {
+        let limit = match value {
+            Some(value) => parse_ipc_payload_limit(value)?,
+            None => DEFAULT_MAX_IPC_PAYLOAD_BYTES,
+        };
+        self.max_ipc_payload_bytes.store(limit, Ordering::Relaxed);
+        Ok(limit)
+    }
+    
+    /// Restrict IPC commands to the given capabilities (see `IPC_CAPABILITIES_ENV`)
+    pub fn with_granted_capabilities(mut self, granted: Option<Vec<OperationCapability>>) -> Self {
+        self.granted_capabilities = granted;
+        self
+    }
+    
+    /// Reject the operation with `AppError::CapabilityDenied` if a required
+    /// capability was not granted at startup
+    pub fn check_granted(&self, required: &[OperationCapability]) -> Result<(), AppError> {
+        if let Some(granted) = &self.granted_capabilities {
+            if let Some(denied) = required.iter().find(|capability| !granted.contains(capability)) {
+                return Err(AppError::CapabilityDenied {
+                    capability: format!("{:?}", denied),
+                });
+            }
         }
+        Ok(())
+    }
+    
+    /// Whether the application has started shutting down
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutdown_flag.load(Ordering::Relaxed)
+    }
+    
+    /// Current frequency usage per operation source
+    /// Read-only: does not count as an operation against any limit
+    pub fn frequency_status(&self) -> Result<Vec<FrequencyStatus>, AppError> {
+        let tracker = self.frequency_tracker.lock().map_err(|_| AppError::Validation {
+            field: "frequency_tracker".to_string(),
+            message: "Failed to acquire frequency tracker lock".to_string(),
+        })?;
+        Ok(tracker.status_at(Instant::now()))
+    }
+    
+    /// Count one operation against a dedicated limit shared by every caller of `bucket`
+    /// 
+    /// Applies on top of the per-source limit checked by `validate_operation_context`
+    /// and fails with `AppError::RateLimit` once `limit` calls were made in the window.
+    pub fn check_dedicated_frequency(&self, bucket: &'static str, limit: u32) -> Result<(), AppError> {
+        let mut tracker = self.frequency_tracker.lock().map_err(|_| AppError::Validation {
+            field: "frequency_tracker".to_string(),
+            message: "Failed to acquire frequency tracker lock".to_string(),
+        })?;
+        tracker.check_dedicated(bucket, limit, Instant::now())
     }
     
     /// Validate operation context and check frequency limits
+    /// The time taken is recorded under `context:<capabilities>` for security overhead analysis
     pub fn validate_operation_context(&self, context: &OperationContext) -> Result<(), AppError> {
+        let started = Instant::now();
+        let result = self.check_operation_context(context);
+        let capabilities: Vec<&str> = context.capabilities.iter().map(OperationCapability::as_str).collect();
+        crate::performance::get_performance_monitor()
+            .record_validation(&format!("context:{}", capabilities.join(",")), started.elapsed());
+        result
+    }
+    
+    fn check_operation_context(&self, context: &OperationContext) -> Result<(), AppError> {
         // Check frequency limits
         if let Ok(mut tracker) = self.frequency_tracker.lock() {
             tracker.check_frequency(context)?;
@@ -176,9 +508,12 @@ impl SecurityValidator {
                 OperationCapability::WriteNotes,
                 OperationCapability::Search,
             ],
+            // FileExport is only requested by the registry's file export and import
+            // commands, which check their own destination and import directory
             OperationSource::IPC => vec![
                 OperationCapability::ReadNotes,
                 OperationCapability::WriteNotes,
+                OperationCapability::FileExport,
                 OperationCapability::Search,
             ],
             OperationSource::Direct => vec![
@@ -227,12 +562,10 @@ impl SecurityValidator {
             });
         }
         
-        // Validate IPC content size (1MB limit)
-        if content.len() > 1024 * 1024 {
-            return Err(AppError::Validation {
-                field: "ipc_content".to_string(),
-                message: "IPC content exceeds maximum size limit".to_string(),
-            });
+        // Validate IPC content size against the configured limit
+        let limit = self.max_ipc_payload_bytes();
+        if content.len() > limit {
+            return Err(AppError::PayloadTooLarge { size: content.len(), limit });
         }
         
         // Check for malicious content patterns in IPC data
@@ -365,7 +698,7 @@ impl SecurityValidator {
             }
         }
         
-        println!("Cleaned up {} temporary files during shutdown", cleaned_count);
+        log::info!("Cleaned up {} temporary files during shutdown", cleaned_count);
         Ok(cleaned_count)
     }
     
@@ -377,12 +710,50 @@ impl SecurityValidator {
     
     /// Maximum allowed setting key/value length
     pub const MAX_SETTING_LENGTH: usize = 1024;
+    
+    /// Absolute ceiling on page sizes, whatever the configured cap
+    pub const MAX_PAGINATION_LIMIT: usize = 1000;
+    
+    /// Maximum length of a client-supplied correlation id
+    pub const MAX_CORRELATION_ID_LENGTH: usize = 64;
+    
+    /// Maximum allowed nickname length in characters
+    pub const MAX_NICKNAME_LENGTH: usize = 200;
+    
+    /// Maximum allowed tag length in characters
+    pub const MAX_TAG_LENGTH: usize = 50;
+    
+    /// Shortcut modifier spellings (lowercase) and the modifier each one stands for
+    /// `Cmd`, `Super` and `Meta` are the same key, so combining them is a duplicate
+    pub const SHORTCUT_MODIFIERS: &'static [(&'static str, &'static str)] = &[
+        ("ctrl", "Ctrl"), ("control", "Ctrl"), ("alt", "Alt"), ("shift", "Shift"),
+        ("cmd", "Meta"), ("super", "Meta"), ("meta", "Meta"),
+    ];
+    
+    /// Named (non-character) keys a shortcut may end with, besides F1-F12
+    pub const SHORTCUT_NAMED_KEYS: &'static [&'static str] = &[
+        "Space", "Enter", "Escape", "Tab", "Delete", "Backspace", "Insert",
+        "Home", "End", "PageUp", "PageDown", "Up", "Down", "Left", "Right",
+    ];
+    
+    /// File extensions allowed for export unless `export.allowed_extensions` overrides them
+    pub const DEFAULT_EXPORT_EXTENSIONS: &'static [&'static str] = &[
+        "txt", "md", "json", "csv", "html", "xml", "rtf"
+    ];
+    
+    /// Executable and script extensions that can never be exported, whatever the configuration
+    pub const DENIED_EXPORT_EXTENSIONS: &'static [&'static str] = &[
+        "exe", "dll", "so", "dylib", "sh", "bash", "zsh", "bat", "cmd", "com",
+        "ps1", "vbs", "js", "msi", "scr", "jar", "app"
+    ];
 
     /// Validates export path with operation context and enhanced security
+    /// `allowed_extensions` is the configured export extension list
     pub fn validate_export_path_with_context<P: AsRef<Path>, B: AsRef<Path>>(
         &self,
         file_path: P,
         allowed_base_dir: Option<B>,
+        allowed_extensions: &[String],
         context: &OperationContext
     ) -> Result<PathBuf, AppError> {
         // Validate operation context first
@@ -397,7 +768,7 @@ impl SecurityValidator {
         }
         
         // Perform standard path validation
-        Self::validate_export_path(file_path, allowed_base_dir)
+        Self::validate_export_path_with_extensions(file_path, allowed_base_dir, allowed_extensions)
     }
     
     /// Validates and sanitizes a file path to prevent path traversal attacks (legacy method)
@@ -411,6 +782,17 @@ impl SecurityValidator {
     pub fn validate_export_path<P: AsRef<Path>, B: AsRef<Path>>(
         file_path: P,
         allowed_base_dir: Option<B>
+    ) -> Result<PathBuf, AppError> {
+        let defaults: Vec<String> = Self::DEFAULT_EXPORT_EXTENSIONS.iter().map(|ext| ext.to_string()).collect();
+        Self::validate_export_path_with_extensions(file_path, allowed_base_dir, &defaults)
+    }
+    
+    /// Validates an export path against a configured extension list
+    /// The extension deny-list still applies whatever the list contains
+    pub fn validate_export_path_with_extensions<P: AsRef<Path>, B: AsRef<Path>>(
+        file_path: P,
+        allowed_base_dir: Option<B>,
+        allowed_extensions: &[String]
     ) -> Result<PathBuf, AppError> {
         let path = file_path.as_ref();
         let path_str = path.to_string_lossy();
@@ -467,7 +849,7 @@ impl SecurityValidator {
         }
         
         // Validate file extension
-        Self::validate_file_extension(path)?;
+        Self::validate_file_extension_against(path, allowed_extensions)?;
         
         // Build the final path within allowed directory
         let final_path = if let Some(base_dir) = allowed_base_dir {
@@ -553,13 +935,64 @@ impl SecurityValidator {
         false
     }
     
-    /// Validates file extension against whitelist
-    pub fn validate_file_extension(path: &Path) -> Result<(), AppError> {
-        // Allowed file extensions for export
-        const ALLOWED_EXTENSIONS: &[&str] = &[
-            "txt", "md", "json", "csv", "html", "xml", "rtf"
-        ];
+    /// Longest file name stem produced by `sanitize_file_name`
+    pub const MAX_FILE_STEM_LENGTH: usize = 100;
+    
+    /// Turn arbitrary text (e.g. a nickname) into a safe file name stem
+    /// 
+    /// Letters and digits are kept, `-` and `_` pass through, and any other run
+    /// of characters becomes a single `-`. Windows device names are suffixed so
+    /// they stay usable. Returns `None` when nothing usable is left.
+    pub fn sanitize_file_name(name: &str) -> Option<String> {
+        let mut stem = String::with_capacity(name.len());
+        for c in name.chars() {
+            if c.is_alphanumeric() || c == '_' || c == '-' {
+                stem.push(c);
+            } else if !stem.ends_with('-') {
+                stem.push('-');
+            }
+        }
+        let stem: String = stem.trim_matches('-').chars().take(Self::MAX_FILE_STEM_LENGTH).collect();
+        let stem = stem.trim_end_matches('-').to_string();
+        if stem.is_empty() {
+            return None;
+        }
         
+        const RESERVED: &[&str] = &["CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "LPT1", "LPT2", "LPT3"];
+        if RESERVED.contains(&stem.to_uppercase().as_str()) {
+            return Some(format!("{}_", stem));
+        }
+        Some(stem)
+    }
+    
+    /// Longest slug produced by `url_slug`
+    pub const MAX_SLUG_LENGTH: usize = 60;
+    
+    /// Turn text into a lowercase URL-safe slug made of ASCII letters, digits and `-`
+    /// Returns `None` when nothing usable is left
+    pub fn url_slug(text: &str) -> Option<String> {
+        let mut slug = String::with_capacity(text.len());
+        for c in text.chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c.to_ascii_lowercase());
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        let slug: String = slug.chars().take(Self::MAX_SLUG_LENGTH).collect();
+        let slug = slug.trim_end_matches('-');
+        (!slug.is_empty()).then(|| slug.to_string())
+    }
+    
+    /// Validates file extension against the default whitelist
+    pub fn validate_file_extension(path: &Path) -> Result<(), AppError> {
+        let defaults: Vec<String> = Self::DEFAULT_EXPORT_EXTENSIONS.iter().map(|ext| ext.to_string()).collect();
+        Self::validate_file_extension_against(path, &defaults)
+    }
+    
+    /// Validates file extension against a configured whitelist
+    /// Deny-listed extensions are rejected even when the whitelist contains them
+    pub fn validate_file_extension_against(path: &Path, allowed_extensions: &[String]) -> Result<(), AppError> {
         let extension = path.extension()
             .and_then(OsStr::to_str)
             .ok_or_else(|| AppError::Validation {
@@ -568,13 +1001,20 @@ impl SecurityValidator {
             })?;
         
         let extension_lower = extension.to_lowercase();
-        if !ALLOWED_EXTENSIONS.contains(&extension_lower.as_str()) {
+        if Self::DENIED_EXPORT_EXTENSIONS.contains(&extension_lower.as_str()) {
+            return Err(AppError::Validation {
+                field: "file_extension".to_string(),
+                message: format!("File extension '{}' is blocked for export", extension),
+            });
+        }
+        
+        if !allowed_extensions.iter().any(|allowed| allowed.eq_ignore_ascii_case(&extension_lower)) {
             return Err(AppError::Validation {
                 field: "file_extension".to_string(),
                 message: format!(
                     "File extension '{}' not allowed. Allowed: {}",
                     extension,
-                    ALLOWED_EXTENSIONS.join(", ")
+                    allowed_extensions.join(", ")
                 ),
             });
         }
@@ -582,6 +1022,40 @@ impl SecurityValidator {
         Ok(())
     }
     
+    /// Parses a comma-separated export extension list such as `"txt, .md, org"`
+    /// 
+    /// Extensions are lowercased and deduplicated; deny-listed extensions and
+    /// malformed entries are rejected so they can't be enabled via settings.
+    pub fn parse_export_extensions(value: &str) -> Result<Vec<String>, AppError> {
+        let invalid = |message: String| AppError::Validation {
+            field: "export.allowed_extensions".to_string(),
+            message,
+        };
+        
+        let mut extensions: Vec<String> = Vec::new();
+        for entry in value.split(',') {
+            let extension = entry.trim().trim_start_matches('.').to_lowercase();
+            if extension.is_empty() {
+                continue;
+            }
+            if extension.len() > 10 || !extension.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(invalid(format!("Invalid export extension '{}'", entry.trim())));
+            }
+            if Self::DENIED_EXPORT_EXTENSIONS.contains(&extension.as_str()) {
+                return Err(invalid(format!("Extension '{}' is blocked and cannot be allowed for export", extension)));
+            }
+            if !extensions.contains(&extension) {
+                extensions.push(extension);
+            }
+        }
+        
+        if extensions.is_empty() {
+            return Err(invalid("At least one export extension is required".to_string()));
+        }
+        
+        Ok(extensions)
+    }
+    
     /// Validates note content for security and length constraints with operation context
     pub fn validate_note_content_with_context(&self, content: &str, context: &OperationContext) -> Result<(), AppError> {
         // Validate operation context first
@@ -596,7 +1070,10 @@ impl SecurityValidator {
         }
         
         // Perform standard content validation
-        Self::validate_note_content(content)
+        let started = Instant::now();
+        let result = Self::validate_note_content(content);
+        crate::performance::get_performance_monitor().record_validation("note_content", started.elapsed());
+        result
     }
     
     /// Validates note content for security and length constraints (legacy method)
@@ -620,7 +1097,23 @@ impl SecurityValidator {
     
     /// Validates setting keys and values
     pub fn validate_setting(key: &str, value: &str) -> Result<(), AppError> {
-        // Validate key
+        Self::validate_setting_key(key)?;
+        
+        // Validate value
+        if value.len() > Self::MAX_SETTING_LENGTH {
+            return Err(AppError::Validation {
+                field: "setting_value".to_string(),
+                message: "Setting value too long".to_string(),
+            });
+        }
+        
+        Self::validate_no_malicious_content(value, "setting_value")?;
+        
+        Ok(())
+    }
+    
+    /// Validates a setting key (also used for namespace prefixes)
+    pub fn validate_setting_key(key: &str) -> Result<(), AppError> {
         if key.is_empty() {
             return Err(AppError::Validation {
                 field: "setting_key".to_string(),
@@ -649,16 +1142,6 @@ impl SecurityValidator {
             });
         }
         
-        // Validate value
-        if value.len() > Self::MAX_SETTING_LENGTH {
-            return Err(AppError::Validation {
-                field: "setting_value".to_string(),
-                message: "Setting value too long".to_string(),
-            });
-        }
-        
-        Self::validate_no_malicious_content(value, "setting_value")?;
-        
         Ok(())
     }
     
@@ -676,7 +1159,10 @@ impl SecurityValidator {
         }
         
         // Perform standard search validation
-        Self::validate_search_query(query)
+        let started = Instant::now();
+        let result = Self::validate_search_query(query);
+        crate::performance::get_performance_monitor().record_validation("search_query", started.elapsed());
+        result
     }
     
     /// Validates search queries to prevent injection attacks (legacy method)
@@ -731,6 +1217,102 @@ impl SecurityValidator {
         Ok(())
     }
     
+    /// Validates and normalizes a note nickname
+    /// 
+    /// Surrounding whitespace is trimmed and an all-whitespace nickname normalizes to `None`.
+    pub fn validate_nickname(nickname: &str) -> Result<Option<String>, AppError> {
+        let trimmed = nickname.trim();
+        if trimmed.is_empty() {
+            return Ok(None);
+        }
+        
+        if trimmed.chars().count() > Self::MAX_NICKNAME_LENGTH {
+            return Err(AppError::Validation {
+                field: "nickname".to_string(),
+                message: format!(
+                    "Nickname too long. Maximum {} characters allowed",
+                    Self::MAX_NICKNAME_LENGTH
+                ),
+            });
+        }
+        
+        if trimmed.chars().any(|c| c.is_control()) {
+            return Err(AppError::Validation {
+                field: "nickname".to_string(),
+                message: "Nickname cannot contain control characters".to_string(),
+            });
+        }
+        
+        Ok(Some(trimmed.to_string()))
+    }
+    
+    /// Validates a tag name, returning it trimmed
+    /// Tags are single words: whitespace, commas and control characters are rejected
+    pub fn validate_tag(tag: &str) -> Result<String, AppError> {
+        let invalid = |message: String| AppError::Validation {
+            field: "tag".to_string(),
+            message,
+        };
+        
+        let trimmed = tag.trim();
+        if trimmed.is_empty() {
+            return Err(invalid("Tag cannot be empty".to_string()));
+        }
+        if trimmed.chars().count() > Self::MAX_TAG_LENGTH {
+            return Err(invalid(format!("Tag too long. Maximum {} characters allowed", Self::MAX_TAG_LENGTH)));
+        }
+        if trimmed.chars().any(|c| c.is_whitespace() || c.is_control() || c == ',') {
+            return Err(invalid("Tag cannot contain whitespace, commas or control characters".to_string()));
+        }
+        
+        Ok(trimmed.to_string())
+    }
+    
+    /// Validates an attachment reference path, returning it trimmed
+    /// Attachments point at existing files anywhere, so absolute paths are allowed
+    pub fn validate_attachment_path(path: &str) -> Result<String, AppError> {
+        let invalid = |message: &str| AppError::Validation {
+            field: "path".to_string(),
+            message: message.to_string(),
+        };
+        
+        let trimmed = path.trim();
+        if trimmed.is_empty() {
+            return Err(invalid("Attachment path cannot be empty"));
+        }
+        if trimmed.len() > Self::MAX_PATH_LENGTH {
+            return Err(invalid("Path too long"));
+        }
+        if trimmed.chars().any(|c| c.is_control()) {
+            return Err(invalid("Attachment path cannot contain control characters"));
+        }
+        if Self::contains_path_traversal(trimmed) {
+            return Err(invalid("Path traversal detected"));
+        }
+        
+        Ok(trimmed.to_string())
+    }
+    
+    /// Validates a client-supplied correlation id: 1-64 ASCII letters, digits, `-`, `_`, `.` or `:`
+    pub fn validate_correlation_id(correlation_id: &str) -> Result<(), AppError> {
+        let invalid = |message: &str| AppError::Validation {
+            field: "correlation_id".to_string(),
+            message: message.to_string(),
+        };
+        
+        if correlation_id.is_empty() {
+            return Err(invalid("Correlation id cannot be empty"));
+        }
+        if correlation_id.len() > Self::MAX_CORRELATION_ID_LENGTH {
+            return Err(invalid("Correlation id too long"));
+        }
+        if !correlation_id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':')) {
+            return Err(invalid("Correlation id contains invalid characters"));
+        }
+        
+        Ok(())
+    }
+    
     /// Validates shortcut strings for global shortcuts
     pub fn validate_shortcut(shortcut: &str) -> Result<(), AppError> {
         if shortcut.is_empty() {
@@ -747,23 +1329,65 @@ impl SecurityValidator {
             });
         }
         
-        // Basic shortcut format validation (modifier+key)
-        let shortcut_regex = Regex::new(r"^([A-Za-z]+\+)*[A-Za-z0-9]+$")
-            .map_err(|_| AppError::Validation {
-                field: "regex".to_string(),
-                message: "Internal validation error".to_string(),
-            })?;
+        let invalid = |message: String| AppError::Validation {
+            field: "shortcut".to_string(),
+            message,
+        };
         
-        if !shortcut_regex.is_match(shortcut) {
-            return Err(AppError::Validation {
-                field: "shortcut".to_string(),
-                message: "Invalid shortcut format".to_string(),
-            });
+        // Grammar: one or more modifiers, then exactly one key, joined by '+'
+        let parts: Vec<&str> = shortcut.split('+').collect();
+        if parts.iter().any(|part| part.is_empty()) {
+            return Err(invalid("Invalid shortcut format. Use format like 'Ctrl+Shift+N'".to_string()));
+        }
+        let (key, modifiers) = parts.split_last().expect("split always yields a part");
+        
+        let mut seen: Vec<&str> = Vec::new();
+        for modifier in modifiers {
+            let canonical = Self::shortcut_modifier(modifier)
+                .ok_or_else(|| invalid(format!("Unknown modifier '{}'", modifier)))?;
+            if seen.contains(&canonical) {
+                return Err(invalid(format!("Duplicate modifier '{}'", canonical)));
+            }
+            seen.push(canonical);
+        }
+        
+        if let Some(modifier) = Self::shortcut_modifier(key) {
+            return Err(invalid(format!("Shortcut must end with a key, not the modifier '{}'", modifier)));
+        }
+        if !Self::is_shortcut_key(key) {
+            return Err(invalid(format!("Unknown key '{}'", key)));
+        }
+        if seen.is_empty() {
+            return Err(invalid(
+                "Global shortcuts need at least one modifier (Ctrl, Cmd, Alt, Shift, Super/Meta)".to_string()
+            ));
         }
         
         Ok(())
     }
     
+    /// Canonical name of a shortcut modifier, matched case-insensitively
+    fn shortcut_modifier(part: &str) -> Option<&'static str> {
+        let lower = part.to_lowercase();
+        Self::SHORTCUT_MODIFIERS
+            .iter()
+            .find(|(spelling, _)| *spelling == lower)
+            .map(|(_, canonical)| *canonical)
+    }
+    
+    /// Whether `part` is a key a shortcut may end with: a letter, a digit, F1-F12 or a named key
+    fn is_shortcut_key(part: &str) -> bool {
+        let mut chars = part.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return c.is_ascii_alphanumeric();
+        }
+        let is_function_key = part
+            .strip_prefix(['F', 'f'])
+            .and_then(|number| number.parse::<u8>().ok())
+            .is_some_and(|number| (1..=12).contains(&number) && !part[1..].starts_with('0'));
+        is_function_key || Self::SHORTCUT_NAMED_KEYS.iter().any(|named| named.eq_ignore_ascii_case(part))
+    }
+    
     /// Generic validation for malicious content patterns
     pub fn validate_no_malicious_content(content: &str, field_name: &str) -> Result<(), AppError> {
         // Check for script injection patterns
@@ -818,6 +1442,54 @@ impl SecurityValidator {
             .to_string()
     }
     
+    /// Patterns for script tags, script URLs and event handler attributes
+    fn active_content_patterns() -> &'static (Regex, Regex, Regex) {
+        static PATTERNS: std::sync::OnceLock<(Regex, Regex, Regex)> = std::sync::OnceLock::new();
+        PATTERNS.get_or_init(|| (
+            Regex::new(r"(?i)<(/?\s*script)").unwrap(),
+            Regex::new(r"(?i)\b(?:java|vb)script\s*:").unwrap(),
+            Regex::new(r"(?i)(<[^>]*?\s)(on[a-z]+\s*=)").unwrap(),
+        ))
+    }
+    
+    /// Whether content contains anything `sanitize_content` would rewrite
+    pub fn contains_active_content(content: &str) -> bool {
+        let (script_tag, script_url, event_handler) = Self::active_content_patterns();
+        script_tag.is_match(content) || script_url.is_match(content) || event_handler.is_match(content)
+    }
+    
+    /// Sanitize content according to `mode`
+    pub fn sanitize_content_with_mode(content: &str, mode: SanitizeMode) -> SanitizedContent {
+        let flagged = Self::contains_active_content(content);
+        let content = match mode {
+            SanitizeMode::Neutralize if flagged => Self::sanitize_content(content),
+            SanitizeMode::Escape => crate::markdown::escape_html(content),
+            SanitizeMode::Neutralize | SanitizeMode::Flag => content.to_string(),
+        };
+        SanitizedContent { content, flagged }
+    }
+    
+    /// Neutralizes active content in HTML before it leaves the app
+    /// 
+    /// Script tags are escaped so they render as text, `javascript:`/`vbscript:` URLs
+    /// become inert `data:` ones and event handler attributes are renamed with a
+    /// `data-` prefix. Everything else is left untouched.
+    pub fn sanitize_content(content: &str) -> String {
+        let (script_tag, script_url, event_handler) = Self::active_content_patterns();
+        
+        let content = script_tag.replace_all(content, "&lt;$1");
+        let content = script_url.replace_all(&content, "data:");
+        // Repeat until stable: each pass renames one handler per tag
+        let mut content = content.into_owned();
+        loop {
+            let renamed = event_handler.replace_all(&content, "${1}data-$2").into_owned();
+            if renamed == content {
+                return content;
+            }
+            content = renamed;
+        }
+    }
+    
     /// Validates ID parameters to prevent injection
     pub fn validate_id(id: i64) -> Result<(), AppError> {
         if id <= 0 {
@@ -840,8 +1512,14 @@ impl SecurityValidator {
     
     /// Validates pagination parameters
     pub fn validate_pagination(offset: usize, limit: usize) -> Result<(), AppError> {
-        const MAX_LIMIT: usize = 1000;
+        Self::validate_pagination_with_cap(offset, limit, Self::MAX_PAGINATION_LIMIT)
+    }
+    
+    /// Validates pagination against a configured limit cap
+    /// The cap itself is bounded by `MAX_PAGINATION_LIMIT`
+    pub fn validate_pagination_with_cap(offset: usize, limit: usize, max_limit: usize) -> Result<(), AppError> {
         const MAX_OFFSET: usize = 100_000;
+        let max_limit = max_limit.min(Self::MAX_PAGINATION_LIMIT);
         
         if limit == 0 {
             return Err(AppError::Validation {
@@ -850,10 +1528,10 @@ impl SecurityValidator {
             });
         }
         
-        if limit > MAX_LIMIT {
+        if limit > max_limit {
             return Err(AppError::Validation {
                 field: "limit".to_string(),
-                message: format!("Limit cannot exceed {}", MAX_LIMIT),
+                message: format!("Limit cannot exceed {}", max_limit),
             });
         }
         
@@ -911,6 +1589,26 @@ mod tests {
         assert!(SecurityValidator::validate_file_extension(&PathBuf::from("noextension")).is_err());
     }
     
+    #[test]
+    fn test_configured_export_extensions() {
+        // A custom extension is allowed once configured
+        let allowed = SecurityValidator::parse_export_extensions("txt, .ORG,md,org").unwrap();
+        assert_eq!(allowed, vec!["txt", "org", "md"]);
+        assert!(SecurityValidator::validate_file_extension(&PathBuf::from("notes.org")).is_err());
+        assert!(SecurityValidator::validate_file_extension_against(&PathBuf::from("notes.org"), &allowed).is_ok());
+        assert!(SecurityValidator::validate_file_extension_against(&PathBuf::from("notes.json"), &allowed).is_err());
+        
+        // Deny-listed extensions cannot be enabled...
+        assert!(SecurityValidator::parse_export_extensions("txt,sh").is_err());
+        assert!(SecurityValidator::parse_export_extensions("EXE").is_err());
+        // ...and are still rejected if a list containing them slips through
+        let smuggled = vec!["txt".to_string(), "exe".to_string()];
+        assert!(SecurityValidator::validate_file_extension_against(&PathBuf::from("run.exe"), &smuggled).is_err());
+        
+        assert!(SecurityValidator::parse_export_extensions(" , ").is_err());
+        assert!(SecurityValidator::parse_export_extensions("tar.gz").is_err());
+    }
+    
     #[test]
     fn test_note_content_validation() {
         // Should allow normal content
@@ -967,6 +1665,58 @@ mod tests {
         assert!(SecurityValidator::validate_shortcut("Ctrl+<script>").is_err());
     }
     
+    #[test]
+    fn test_shortcut_grammar() {
+        let message = |shortcut: &str| match SecurityValidator::validate_shortcut(shortcut) {
+            Err(AppError::Validation { field, message }) => {
+                assert_eq!(field, "shortcut");
+                message
+            }
+            other => panic!("Expected '{}' to be rejected, got {:?}", shortcut, other),
+        };
+        
+        for shortcut in ["Cmd+Shift+T", "Super+L", "ctrl+alt+delete", "Meta+PageDown", "Shift+F12", "Ctrl+7"] {
+            assert!(SecurityValidator::validate_shortcut(shortcut).is_ok(), "{} should be valid", shortcut);
+        }
+        
+        assert!(message("Foo+Bar").contains("Unknown modifier 'Foo'"));
+        assert!(message("Ctrl+Bar").contains("Unknown key 'Bar'"));
+        assert!(message("Ctrl+F13").contains("Unknown key"));
+        assert!(message("N").contains("at least one modifier"));
+        assert!(message("F5").contains("at least one modifier"));
+        assert!(message("Ctrl+ctrl+N").contains("Duplicate modifier 'Ctrl'"));
+        assert!(message("Cmd+Meta+N").contains("Duplicate modifier 'Meta'"));
+        assert!(message("Ctrl+Shift").contains("not the modifier 'Shift'"));
+        assert!(message("Ctrl++N").contains("Invalid shortcut format"));
+    }
+    
+    #[test]
+    fn test_nickname_validation() {
+        assert_eq!(
+            SecurityValidator::validate_nickname("Project Ideas").unwrap(),
+            Some("Project Ideas".to_string())
+        );
+        
+        // Surrounding whitespace is trimmed; all-whitespace normalizes to None
+        assert_eq!(
+            SecurityValidator::validate_nickname("  Padded \t").unwrap(),
+            Some("Padded".to_string())
+        );
+        assert_eq!(SecurityValidator::validate_nickname("   \t\n ").unwrap(), None);
+        assert_eq!(SecurityValidator::validate_nickname("").unwrap(), None);
+        
+        // Too long
+        let at_limit = "a".repeat(SecurityValidator::MAX_NICKNAME_LENGTH);
+        assert!(SecurityValidator::validate_nickname(&at_limit).is_ok());
+        let too_long = "a".repeat(SecurityValidator::MAX_NICKNAME_LENGTH + 1);
+        assert!(SecurityValidator::validate_nickname(&too_long).is_err());
+        
+        // Control characters
+        assert!(SecurityValidator::validate_nickname("Bad\u{0007}Bell").is_err());
+        assert!(SecurityValidator::validate_nickname("Line\nBreak").is_err());
+        assert!(SecurityValidator::validate_nickname("Null\0Byte").is_err());
+    }
+    
     #[test]
     fn test_id_validation() {
         // Should allow valid IDs
@@ -1020,6 +1770,13 @@ mod tests {
         let cli_forbidden = OperationContext::new_cli(vec![OperationCapability::DeleteNotes]);
         assert!(validator.validate_operation_context(&cli_forbidden).is_err());
         
+        // IPC file export and import commands are reachable
+        let ipc_file_context = OperationContext::new_ipc(vec![
+            OperationCapability::FileExport,
+            OperationCapability::WriteNotes,
+        ]);
+        assert!(validator.validate_operation_context(&ipc_file_context).is_ok());
+        
         // Test Direct capabilities
         let direct_context = OperationContext::new_direct(vec![
             OperationCapability::ReadNotes,
@@ -1053,6 +1810,51 @@ mod tests {
         assert!(result.is_err(), "11th CLI operation should fail due to frequency limit");
     }
     
+    #[test]
+    fn test_dedicated_frequency_limit_is_rate_limited_and_decays() {
+        let validator = SecurityValidator::new();
+        for _ in 0..3 {
+            validator.check_dedicated_frequency("test_bucket", 3).unwrap();
+        }
+        assert!(matches!(
+            validator.check_dedicated_frequency("test_bucket", 3),
+            Err(AppError::RateLimit { .. })
+        ));
+        // Other buckets and the per-source limits are unaffected
+        validator.check_dedicated_frequency("other_bucket", 3).unwrap();
+        let context = OperationContext::new_ipc(vec![OperationCapability::ReadNotes]);
+        validator.validate_operation_context(&context).unwrap();
+        
+        let later = Instant::now() + FREQUENCY_WINDOW + Duration::from_secs(1);
+        let mut tracker = validator.frequency_tracker.lock().unwrap();
+        tracker.check_dedicated("test_bucket", 3, later).unwrap();
+    }
+    
+    #[test]
+    fn test_frequency_status_reports_counts_and_decays() {
+        let validator = SecurityValidator::new();
+        for _ in 0..4 {
+            let context = OperationContext::new_ipc(vec![OperationCapability::ReadNotes]);
+            validator.validate_operation_context(&context).unwrap();
+        }
+        
+        // Reading the status twice must not count against the limit
+        validator.frequency_status().unwrap();
+        let status = validator.frequency_status().unwrap();
+        let ipc = status.iter().find(|entry| entry.source == OperationSource::IPC).unwrap();
+        assert_eq!(ipc.count, 4);
+        assert_eq!(ipc.limit, Some(15));
+        assert_eq!(ipc.remaining, Some(11));
+        let cli = status.iter().find(|entry| entry.source == OperationSource::CLI).unwrap();
+        assert_eq!((cli.count, cli.remaining), (0, Some(10)));
+        
+        // Once the window has passed the operations no longer count
+        let later = Instant::now() + FREQUENCY_WINDOW + Duration::from_secs(1);
+        let decayed = validator.frequency_tracker.lock().unwrap().status_at(later);
+        let ipc = decayed.iter().find(|entry| entry.source == OperationSource::IPC).unwrap();
+        assert_eq!((ipc.count, ipc.remaining), (0, Some(15)));
+    }
+    
     #[test]
     fn test_ipc_security_validation() {
         let validator = SecurityValidator::new();
@@ -1075,6 +1877,29 @@ mod tests {
         assert!(validator.validate_ipc_request(valid_content, &wrong_context).is_err());
     }
     
+    #[test]
+    fn test_oversized_ipc_payload_reports_size_and_limit() {
+        let validator = SecurityValidator::new();
+        let context = OperationContext::new_ipc(vec![OperationCapability::WriteNotes]);
+        assert_eq!(validator.max_ipc_payload_bytes(), DEFAULT_MAX_IPC_PAYLOAD_BYTES);
+        
+        validator.apply_payload_limit_setting(Some("2048")).unwrap();
+        assert!(validator.validate_ipc_request(&"x".repeat(2048), &context).is_ok());
+        match validator.validate_ipc_request(&"x".repeat(2049), &context) {
+            Err(AppError::PayloadTooLarge { size, limit }) => assert_eq!((size, limit), (2049, 2048)),
+            other => panic!("Expected PayloadTooLarge, got {:?}", other),
+        }
+        
+        // The limit cannot be raised past the ceiling, and a rejected value keeps the current one
+        let too_high = (MAX_IPC_PAYLOAD_LIMIT_BYTES + 1).to_string();
+        assert!(validator.apply_payload_limit_setting(Some(&too_high)).is_err());
+        assert!(validator.apply_payload_limit_setting(Some("0")).is_err());
+        assert_eq!(validator.max_ipc_payload_bytes(), 2048);
+        
+        validator.apply_payload_limit_setting(None).unwrap();
+        assert_eq!(validator.max_ipc_payload_bytes(), DEFAULT_MAX_IPC_PAYLOAD_BYTES);
+    }
+    
     #[test]
     fn test_ipc_file_validation() {
         let validator = SecurityValidator::new();
@@ -1135,16 +1960,17 @@ mod tests {
             OperationCapability::FileExport,
             OperationCapability::ReadNotes
         ]);
-        let result = validator.validate_export_path_with_context("test.txt", Some(&temp_dir), &context);
+        let extensions = vec!["txt".to_string()];
+        let result = validator.validate_export_path_with_context("test.txt", Some(&temp_dir), &extensions, &context);
         assert!(result.is_ok());
         
         // Export without required capability
         let no_export_context = OperationContext::new_direct(vec![OperationCapability::ReadNotes]);
-        let result = validator.validate_export_path_with_context("test.txt", Some(&temp_dir), &no_export_context);
+        let result = validator.validate_export_path_with_context("test.txt", Some(&temp_dir), &extensions, &no_export_context);
         assert!(result.is_err());
         
         // Path traversal attempt
-        let result = validator.validate_export_path_with_context("../../../etc/passwd", Some(&temp_dir), &context);
+        let result = validator.validate_export_path_with_context("../../../etc/passwd", Some(&temp_dir), &extensions, &context);
         assert!(result.is_err());
     }
     
@@ -1182,4 +2008,37 @@ mod tests {
         let cleaned_count = result.unwrap();
         assert!(cleaned_count >= 0);
     }
+
+    const SCRIPT_URL_SAMPLE: &str = "<a href=\"javascript:alert(1)\">x</a>";
+
+    #[test]
+    fn test_sanitize_mode_neutralize_rewrites_script_url() {
+        let result = SecurityValidator::sanitize_content_with_mode(SCRIPT_URL_SAMPLE, SanitizeMode::Neutralize);
+        assert!(result.flagged);
+        assert_eq!(result.content, "<a href=\"data:alert(1)\">x</a>");
+        assert_eq!(result.content, SecurityValidator::sanitize_content(SCRIPT_URL_SAMPLE));
+        assert_eq!(SanitizeMode::default(), SanitizeMode::Neutralize);
+
+        let clean = SecurityValidator::sanitize_content_with_mode("<a href=\"https://example.com\">x</a>", SanitizeMode::Neutralize);
+        assert!(!clean.flagged);
+    }
+
+    #[test]
+    fn test_sanitize_mode_escape_escapes_everything() {
+        let result = SecurityValidator::sanitize_content_with_mode(SCRIPT_URL_SAMPLE, SanitizeMode::Escape);
+        assert!(result.flagged);
+        assert_eq!(result.content, crate::markdown::escape_html(SCRIPT_URL_SAMPLE));
+        assert!(!result.content.contains('<'));
+    }
+
+    #[test]
+    fn test_sanitize_mode_flag_leaves_content_untouched() {
+        let result = SecurityValidator::sanitize_content_with_mode(SCRIPT_URL_SAMPLE, SanitizeMode::Flag);
+        assert!(result.flagged);
+        assert_eq!(result.content, SCRIPT_URL_SAMPLE);
+
+        assert_eq!(SanitizeMode::from_setting(" Flag "), Some(SanitizeMode::Flag));
+        assert_eq!(SanitizeMode::from_setting("escape"), Some(SanitizeMode::Escape));
+        assert_eq!(SanitizeMode::from_setting("strip"), None);
+    }
 }
\ No newline at end of file
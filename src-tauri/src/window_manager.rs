@@ -1,9 +1,46 @@
 use crate::error::AppError;
 use crate::settings::SettingsService;
+use crate::traits::events::EventEmitter;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Position, Size};
 use tokio::sync::Mutex;
 
+/// Event emitted after the main window is shown
+/// The payload's `focus_editor` tells the frontend whether to focus the note editor
+pub const WINDOW_SHOWN_EVENT: &str = "window-shown";
+
+/// Event asking the frontend to open a note in place of whatever it is showing
+/// The payload's `note_id` is the note to open; `null` means open a blank new note
+pub const OPEN_NOTE_EVENT: &str = "open-note";
+
+/// Setting controlling whether showing the window also focuses it and the editor
+pub const FOCUS_ON_SHOW_SETTING_KEY: &str = "window.focus_on_show";
+
+/// Setting choosing how the window is put away: `hide` (default) or `minimize`
+pub const TOGGLE_MODE_SETTING_KEY: &str = "window.toggle_mode";
+
+/// How `hide_window` puts the window away
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum WindowToggleMode {
+    /// Hide the window entirely, removing it from the taskbar
+    #[default]
+    Hide,
+    /// Minimize the window so it stays reachable from the taskbar
+    Minimize,
+}
+
+impl WindowToggleMode {
+    /// Parse a `window.toggle_mode` value; `None` for anything unrecognised
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "hide" => Some(WindowToggleMode::Hide),
+            "minimize" => Some(WindowToggleMode::Minimize),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum LayoutMode {
     Default,
@@ -33,17 +70,28 @@ pub struct WindowManager {
     app_handle: Option<AppHandle>,
     settings_service: Arc<SettingsService>,
     current_layout: Arc<Mutex<LayoutMode>>,
-    _previous_app_focused: Arc<Mutex<bool>>,
+    /// Whether our window had focus when it was last hidden
+    /// Tauri only reports focus for the app's own windows, so the application that
+    /// had focus before ours (to hand focus back to it) can't be recorded on any platform
+    focused_on_hide: Arc<Mutex<bool>>,
+    /// Simulated visibility in test mode, where there is no real window
+    test_visible: AtomicBool,
+    /// Simulated minimized state in test mode
+    test_minimized: AtomicBool,
+    emitter: Option<Arc<dyn EventEmitter>>,
     is_test_mode: bool,
 }
 
 impl WindowManager {
     pub fn new(app_handle: AppHandle, settings_service: Arc<SettingsService>) -> Self {
         Self {
+            emitter: Some(Arc::new(app_handle.clone())),
             app_handle: Some(app_handle),
             settings_service,
             current_layout: Arc::new(Mutex::new(LayoutMode::Default)),
-            _previous_app_focused: Arc::new(Mutex::new(false)),
+            focused_on_hide: Arc::new(Mutex::new(false)),
+            test_visible: AtomicBool::new(true),
+            test_minimized: AtomicBool::new(false),
             is_test_mode: false,
         }
     }
@@ -56,11 +104,45 @@ impl WindowManager {
             app_handle: None,
             settings_service: _settings_service,
             current_layout: Arc::new(Mutex::new(LayoutMode::Default)),
-            _previous_app_focused: Arc::new(Mutex::new(false)),
+            focused_on_hide: Arc::new(Mutex::new(false)),
+            test_visible: AtomicBool::new(true),
+            test_minimized: AtomicBool::new(false),
+            emitter: None,
             is_test_mode: true,
         })
     }
 
+    /// Set a custom event emitter (e.g. a mock in tests)
+    pub fn set_event_emitter(&mut self, emitter: Arc<dyn EventEmitter>) {
+        self.emitter = Some(emitter);
+    }
+
+    /// Emit an event to the frontend, logging rather than failing on emission errors
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        if let Some(emitter) = &self.emitter {
+            if let Err(e) = emitter.emit_event(event, payload) {
+                log::warn!("{}", e);
+            }
+        }
+    }
+
+    /// Whether showing the window should focus it and the editor (`window.focus_on_show`, default on)
+    async fn focus_on_show(&self) -> Result<bool, AppError> {
+        let value = self.settings_service.get_setting(FOCUS_ON_SHOW_SETTING_KEY).await?;
+        Ok(value.is_none_or(|value| value.trim() != "false"))
+    }
+
+    /// How the window is put away (`window.toggle_mode`); unrecognised values fall back to hiding
+    pub async fn toggle_mode(&self) -> Result<WindowToggleMode, AppError> {
+        let value = self.settings_service.get_setting(TOGGLE_MODE_SETTING_KEY).await?;
+        Ok(value.as_deref().and_then(WindowToggleMode::from_setting).unwrap_or_default())
+    }
+
+    /// Whether the window had focus when it was last hidden
+    pub async fn was_focused_on_hide(&self) -> bool {
+        *self.focused_on_hide.lock().await
+    }
+
     /// Initialize the window manager with saved settings
     pub async fn initialize(&self) -> Result<(), AppError> {
         if self.is_test_mode {
@@ -96,58 +178,72 @@ impl WindowManager {
 
     /// Toggle the window visibility
     pub async fn toggle_window(&self) -> Result<(), AppError> {
-        if self.is_test_mode {
-            return Ok(());
+        if self.is_window_visible().await.unwrap_or(false) {
+            self.hide_window().await
+        } else {
+            self.show_window().await
         }
+    }
 
-        let app_handle = self.app_handle.as_ref().ok_or_else(|| AppError::Runtime {
-            message: "AppHandle not available".to_string(),
-        })?;
+    /// Show the window and emit `window-shown`
+    /// With `window.focus_on_show` enabled the window is focused and the event asks
+    /// the frontend to focus the editor, so typing can resume without a click
+    pub async fn show_window(&self) -> Result<(), AppError> {
+        let focus = self.focus_on_show().await?;
 
-        let window = app_handle.get_webview_window("main").ok_or_else(|| AppError::Runtime {
-            message: "Main window not found".to_string(),
-        })?;
+        if self.is_test_mode {
+            self.test_visible.store(true, Ordering::Relaxed);
+            self.test_minimized.store(false, Ordering::Relaxed);
+        } else {
+            let app_handle = self.app_handle.as_ref().ok_or_else(|| AppError::Runtime {
+                message: "AppHandle not available".to_string(),
+            })?;
 
-        if window.is_visible().unwrap_or(false) {
-            window.hide().map_err(|e| AppError::Runtime {
-                message: format!("Failed to hide window: {}", e),
+            let window = app_handle.get_webview_window("main").ok_or_else(|| AppError::Runtime {
+                message: "Main window not found".to_string(),
             })?;
-        } else {
+
+            if window.is_minimized().unwrap_or(false) {
+                window.unminimize().map_err(|e| AppError::Runtime {
+                    message: format!("Failed to restore window: {}", e),
+                })?;
+            }
             window.show().map_err(|e| AppError::Runtime {
                 message: format!("Failed to show window: {}", e),
             })?;
-            window.set_focus().map_err(|e| AppError::Runtime {
-                message: format!("Failed to focus window: {}", e),
-            })?;
+            if focus {
+                window.set_focus().map_err(|e| AppError::Runtime {
+                    message: format!("Failed to focus window: {}", e),
+                })?;
+            }
         }
 
+        self.emit(WINDOW_SHOWN_EVENT, serde_json::json!({ "focus_editor": focus }));
         Ok(())
     }
 
-    /// Show the window
-    pub async fn show_window(&self) -> Result<(), AppError> {
-        if self.is_test_mode {
-            return Ok(());
+    /// Bring up the window on the given note, or on a blank new note for `None`
+    /// 
+    /// The window is shown only if hidden; when it is already showing another note
+    /// the frontend navigates to this one instead of opening a second view.
+    pub async fn open_note(&self, note_id: Option<i64>) -> Result<(), AppError> {
+        if !self.is_window_visible().await.unwrap_or(false) {
+            self.show_window().await?;
         }
-
-        let app_handle = self.app_handle.as_ref().ok_or_else(|| AppError::Runtime {
-            message: "AppHandle not available".to_string(),
-        })?;
-
-        let window = app_handle.get_webview_window("main").ok_or_else(|| AppError::Runtime {
-            message: "Main window not found".to_string(),
-        })?;
-
-        window.show().map_err(|e| AppError::Runtime {
-            message: format!("Failed to show window: {}", e),
-        })?;
-
+        self.emit(OPEN_NOTE_EVENT, serde_json::json!({ "note_id": note_id, "replace": true }));
         Ok(())
     }
 
-    /// Hide the window
+    /// Hide or minimize the window per `window.toggle_mode`, recording whether it had focus
+    /// Focus then moves wherever the OS sends it; it can't be handed back to a specific app
     pub async fn hide_window(&self) -> Result<(), AppError> {
+        let mode = self.toggle_mode().await?;
+
         if self.is_test_mode {
+            match mode {
+                WindowToggleMode::Hide => self.test_visible.store(false, Ordering::Relaxed),
+                WindowToggleMode::Minimize => self.test_minimized.store(true, Ordering::Relaxed),
+            }
             return Ok(());
         }
 
@@ -159,17 +255,23 @@ impl WindowManager {
             message: "Main window not found".to_string(),
         })?;
 
-        window.hide().map_err(|e| AppError::Runtime {
-            message: format!("Failed to hide window: {}", e),
-        })?;
+        *self.focused_on_hide.lock().await = window.is_focused().unwrap_or(false);
+        match mode {
+            WindowToggleMode::Hide => window.hide().map_err(|e| AppError::Runtime {
+                message: format!("Failed to hide window: {}", e),
+            })?,
+            WindowToggleMode::Minimize => window.minimize().map_err(|e| AppError::Runtime {
+                message: format!("Failed to minimize window: {}", e),
+            })?,
+        }
 
         Ok(())
     }
 
-    /// Check if the window is currently visible
+    /// Check if the window is currently visible; a minimized window counts as not visible
     pub async fn is_window_visible(&self) -> Result<bool, AppError> {
         if self.is_test_mode {
-            return Ok(true);
+            return Ok(self.test_visible.load(Ordering::Relaxed) && !self.test_minimized.load(Ordering::Relaxed));
         }
 
         let app_handle = self.app_handle.as_ref().ok_or_else(|| AppError::Runtime {
@@ -180,9 +282,10 @@ impl WindowManager {
             message: "Main window not found".to_string(),
         })?;
 
-        window.is_visible().map_err(|e| AppError::Runtime {
+        let visible = window.is_visible().map_err(|e| AppError::Runtime {
             message: format!("Failed to check window visibility: {}", e),
-        })
+        })?;
+        Ok(visible && !window.is_minimized().unwrap_or(false))
     }
 
     /// Set the window layout mode
@@ -392,4 +495,91 @@ impl WindowManager {
 
         Ok((size.width, size.height))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DbService;
+    use crate::testing::mocks::MockEventEmitter;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_show_window_sets_visible_and_emits_event() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = Arc::new(DbService::new(temp_dir.path().join("test.db")).unwrap());
+        let settings_service = Arc::new(SettingsService::new(db_service));
+        let emitter = MockEventEmitter::new();
+        let mut window_manager = WindowManager::new_test(settings_service.clone()).unwrap();
+        window_manager.set_event_emitter(Arc::new(emitter.clone()));
+
+        window_manager.hide_window().await.unwrap();
+        assert!(!window_manager.is_window_visible().await.unwrap());
+
+        window_manager.toggle_window().await.unwrap();
+        assert!(window_manager.is_window_visible().await.unwrap());
+        assert_eq!(emitter.payloads_for(WINDOW_SHOWN_EVENT), vec![serde_json::json!({ "focus_editor": true })]);
+
+        // Focusing on show can be turned off
+        settings_service.set_setting(FOCUS_ON_SHOW_SETTING_KEY, "false").await.unwrap();
+        window_manager.hide_window().await.unwrap();
+        window_manager.show_window().await.unwrap();
+        assert!(window_manager.is_window_visible().await.unwrap());
+        assert_eq!(emitter.payloads_for(WINDOW_SHOWN_EVENT)[1], serde_json::json!({ "focus_editor": false }));
+    }
+
+    #[tokio::test]
+    async fn test_toggle_mode_routes_to_hide_or_minimize() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = Arc::new(DbService::new(temp_dir.path().join("test.db")).unwrap());
+        let settings_service = Arc::new(SettingsService::new(db_service));
+        let window_manager = WindowManager::new_test(settings_service.clone()).unwrap();
+
+        // Hiding is the default
+        assert_eq!(window_manager.toggle_mode().await.unwrap(), WindowToggleMode::Hide);
+        window_manager.toggle_window().await.unwrap();
+        assert!(!window_manager.test_visible.load(Ordering::Relaxed));
+        assert!(!window_manager.test_minimized.load(Ordering::Relaxed));
+        window_manager.toggle_window().await.unwrap();
+        assert!(window_manager.is_window_visible().await.unwrap());
+
+        // Minimize keeps the window shown, and toggling again restores it
+        settings_service.set_setting(TOGGLE_MODE_SETTING_KEY, "minimize").await.unwrap();
+        window_manager.toggle_window().await.unwrap();
+        assert!(window_manager.test_visible.load(Ordering::Relaxed));
+        assert!(window_manager.test_minimized.load(Ordering::Relaxed));
+        assert!(!window_manager.is_window_visible().await.unwrap());
+        window_manager.toggle_window().await.unwrap();
+        assert!(window_manager.is_window_visible().await.unwrap());
+        assert!(!window_manager.test_minimized.load(Ordering::Relaxed));
+
+        // Unrecognised values fall back to hiding
+        settings_service.set_setting(TOGGLE_MODE_SETTING_KEY, "vanish").await.unwrap();
+        window_manager.hide_window().await.unwrap();
+        assert!(!window_manager.test_visible.load(Ordering::Relaxed));
+        assert_eq!(WindowToggleMode::from_setting(" Minimize "), Some(WindowToggleMode::Minimize));
+    }
+
+    #[tokio::test]
+    async fn test_open_note_navigates_without_reshowing() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = Arc::new(DbService::new(temp_dir.path().join("test.db")).unwrap());
+        let settings_service = Arc::new(SettingsService::new(db_service));
+        let emitter = MockEventEmitter::new();
+        let mut window_manager = WindowManager::new_test(settings_service).unwrap();
+        window_manager.set_event_emitter(Arc::new(emitter.clone()));
+
+        // A hidden window is shown, then asked to open the note
+        window_manager.hide_window().await.unwrap();
+        window_manager.open_note(Some(7)).await.unwrap();
+        assert!(window_manager.is_window_visible().await.unwrap());
+        assert_eq!(emitter.payloads_for(WINDOW_SHOWN_EVENT).len(), 1);
+
+        // An already visible window just navigates
+        window_manager.open_note(None).await.unwrap();
+        assert_eq!(emitter.payloads_for(WINDOW_SHOWN_EVENT).len(), 1);
+        assert_eq!(emitter.payloads_for(OPEN_NOTE_EVENT), vec![
+            serde_json::json!({ "note_id": 7, "replace": true }),
+            serde_json::json!({ "note_id": null, "replace": true }),
+        ]);
+    }
+}
@@ -140,6 +140,7 @@ fn test_cross_platform_cli_parsing() {
         // Simulate command line arguments
         let cli_args = CliArgs {
             content: expected_content.clone(),
+            append_daily: false,
             should_show_gui: false,
         };
         
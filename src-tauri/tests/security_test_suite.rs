@@ -18,7 +18,7 @@ struct SimpleAppState {
     db: Arc<DbService>,
     search: Arc<SearchService>,
     settings: Arc<SettingsService>,
-    plugin_manager: Arc<tokio::sync::Mutex<PluginManager>>,
+    plugin_manager: Arc<tokio::sync::RwLock<PluginManager>>,
     security_validator: Arc<SecurityValidator>,
     shutdown_manager: Arc<ShutdownManager>,
 }
@@ -32,7 +32,7 @@ async fn create_security_test_state() -> SimpleAppState {
     let security_validator = Arc::new(SecurityValidator::new());
     let search_service = Arc::new(SearchService::new(db_service.clone()));
     let settings_service = Arc::new(SettingsService::new(db_service.clone()));
-    let plugin_manager = Arc::new(tokio::sync::Mutex::new(PluginManager::new()));
+    let plugin_manager = Arc::new(tokio::sync::RwLock::new(PluginManager::new()));
     
     SimpleAppState {
         db: db_service,
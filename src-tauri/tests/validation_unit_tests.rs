@@ -517,7 +517,7 @@ mod shortcut_validation_tests {
             "Ctrl+Alt+Delete",
             "Cmd+Shift+T",
             "Super+L",
-            "F1",
+            "Shift+F1",
             "Ctrl+Shift+Escape",
         ];
         
@@ -542,6 +542,8 @@ mod shortcut_validation_tests {
             "cmd.exe",                   // Executable
             "/bin/bash",                 // Shell
             "powershell.exe",            // PowerShell
+            "F1",                        // No modifier
+            "Foo+Bar",                   // Unknown modifier and key
             &"a".repeat(51),             // Too long
         ];
         
@@ -145,7 +145,7 @@ async fn test_window_management_settings_persistence() {
     let exported = settings_service.export_settings().await.unwrap();
     
     // Reset settings
-    settings_service.reset_to_defaults().await.unwrap();
+    settings_service.reset_to_defaults(true).await.unwrap();
     
     // Import settings back
     let import_count = settings_service.import_settings(exported).await.unwrap();